@@ -105,6 +105,12 @@ impl Transform {
         );
         rotation_matrix * scaled_vector
     }
+
+    /// 世界坐标点转换到局部坐标（transform_point 的逆变换）
+    pub fn inverse_transform_point(&self, point: Vector3<f32>) -> Vector3<f32> {
+        let local = self.rotation.invert() * (point - self.translation);
+        local.div_element_wise(self.scale)
+    }
 }
 
 /// 3x3 矩阵的便捷别名
@@ -148,4 +154,16 @@ mod tests {
         assert!((result.translation.y - 1.0).abs() < 1e-5);
         assert!((result.translation.z - 0.0).abs() < 1e-5);
     }
+
+    #[test]
+    fn test_inverse_transform_point() {
+        let t = Transform {
+            translation: Vector3::new(1.0, 2.0, 3.0),
+            rotation: Quaternion::from_angle_y(Deg(30.0)),
+            scale: Vector3::new(2.0, 1.0, 0.5),
+        };
+        let point = Vector3::new(0.3, -0.7, 1.1);
+        let back = t.inverse_transform_point(t.transform_point(point));
+        assert!((back - point).magnitude() < 1e-5);
+    }
 }
@@ -0,0 +1,195 @@
+use crate::common::Transform;
+use crate::physics::gjk::SupportMap;
+use cgmath::*;
+
+/// 轴对齐包围盒
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vector3<f32>,
+    pub max: Vector3<f32>,
+}
+
+impl Aabb {
+    pub fn new(min: Vector3<f32>, max: Vector3<f32>) -> Self {
+        Self { min, max }
+    }
+
+    /// 空包围盒，与任意包围盒合并后得到对方
+    pub fn empty() -> Self {
+        Self {
+            min: Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            max: Vector3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item = Vector3<f32>>) -> Self {
+        let mut aabb = Self::empty();
+        for point in points {
+            aabb.include(point);
+        }
+        aabb
+    }
+
+    /// 凸体的包围盒：沿六个坐标轴方向取支撑点
+    pub fn from_support(shape: &dyn SupportMap) -> Self {
+        let axes = [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()];
+        let mut min = Vector3::zero();
+        let mut max = Vector3::zero();
+        for (i, axis) in axes.into_iter().enumerate() {
+            max[i] = shape.support(axis)[i];
+            min[i] = shape.support(-axis)[i];
+        }
+        Self { min, max }
+    }
+
+    /// 扩展包围盒以包含给定点
+    pub fn include(&mut self, point: Vector3<f32>) {
+        for i in 0..3 {
+            self.min[i] = self.min[i].min(point[i]);
+            self.max[i] = self.max[i].max(point[i]);
+        }
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        let mut result = *self;
+        result.include(other.min);
+        result.include(other.max);
+        result
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+
+    /// 是否完全包含另一个包围盒
+    pub fn contains(&self, other: &Aabb) -> bool {
+        (0..3).all(|i| self.min[i] <= other.min[i] && other.max[i] <= self.max[i])
+    }
+
+    /// 各方向是否都有界（平面等半空间形状的包围盒无界）
+    pub fn is_bounded(&self) -> bool {
+        (0..3).all(|i| self.min[i].is_finite() && self.max[i].is_finite())
+    }
+
+    /// 表面积，用于层次包围盒的代价估计
+    pub fn surface_area(&self) -> f32 {
+        let d = self.max - self.min;
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    /// 射线 origin + t · direction（0 ≤ t ≤ max_t）进入包围盒时的 t，起点在盒内时为 0
+    pub fn ray_intersection(
+        &self,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        max_t: f32,
+    ) -> Option<f32> {
+        self.ray_interval(origin, direction, max_t)
+            .map(|(t_min, _)| t_min)
+    }
+
+    /// 射线在包围盒内的参数区间 [t_min, t_max]（已截断到 [0, max_t]）
+    pub fn ray_interval(
+        &self,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        max_t: f32,
+    ) -> Option<(f32, f32)> {
+        let mut t_min = 0.0f32;
+        let mut t_max = max_t;
+        for i in 0..3 {
+            if direction[i].abs() < 1e-12 {
+                // 射线与该轴的两个面平行
+                if origin[i] < self.min[i] || origin[i] > self.max[i] {
+                    return None;
+                }
+                continue;
+            }
+            let inv = 1.0 / direction[i];
+            let t1 = (self.min[i] - origin[i]) * inv;
+            let t2 = (self.max[i] - origin[i]) * inv;
+            t_min = t_min.max(t1.min(t2));
+            t_max = t_max.min(t1.max(t2));
+            if t_min > t_max {
+                return None;
+            }
+        }
+        Some((t_min, t_max))
+    }
+
+    pub fn center(&self) -> Vector3<f32> {
+        (self.min + self.max) / 2.0
+    }
+
+    /// 各方向向外扩张 margin
+    pub fn expanded(&self, margin: f32) -> Aabb {
+        let margin = Vector3::new(margin, margin, margin);
+        Aabb {
+            min: self.min - margin,
+            max: self.max + margin,
+        }
+    }
+
+    /// 八个角点经变换后的包围盒
+    pub fn transformed(&self, transform: &Transform) -> Aabb {
+        Self::from_points(
+            self.corners()
+                .map(|corner| transform.transform_point(corner)),
+        )
+    }
+
+    /// 八个角点经逆变换（世界到局部）后的包围盒
+    pub fn inverse_transformed(&self, transform: &Transform) -> Aabb {
+        Self::from_points(
+            self.corners()
+                .map(|corner| transform.inverse_transform_point(corner)),
+        )
+    }
+
+    fn corners(&self) -> [Vector3<f32>; 8] {
+        let mut corners = [self.min; 8];
+        for (index, corner) in corners.iter_mut().enumerate() {
+            for axis in 0..3 {
+                if index & (1 << axis) != 0 {
+                    corner[axis] = self.max[axis];
+                }
+            }
+        }
+        corners
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::shape::Sphere;
+
+    #[test]
+    fn test_aabb_union_and_intersection() {
+        let a = Aabb::new(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 1.0));
+        let b = Aabb::new(Vector3::new(0.5, 0.5, 0.5), Vector3::new(2.0, 2.0, 2.0));
+        let c = Aabb::new(Vector3::new(1.5, 0.0, 0.0), Vector3::new(2.0, 1.0, 1.0));
+
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&c));
+        assert_eq!(a.union(&c).max, Vector3::new(2.0, 1.0, 1.0));
+        assert_eq!(Aabb::empty().union(&a), a);
+        assert!(a.union(&c).contains(&c));
+        assert!(!a.contains(&b));
+        assert_eq!(a.surface_area(), 6.0);
+
+        let origin = Vector3::new(-1.0, 0.5, 0.5);
+        let t = a.ray_intersection(origin, Vector3::unit_x(), 10.0);
+        assert_eq!(t, Some(1.0));
+        assert_eq!(a.ray_intersection(origin, Vector3::unit_x(), 0.5), None);
+        assert_eq!(a.ray_intersection(origin, Vector3::unit_y(), 10.0), None);
+
+        let sphere = Sphere {
+            center: Vector3::new(1.0, 2.0, 3.0),
+            radius: 0.5,
+        };
+        let bounds = Aabb::from_support(&sphere);
+        assert!((bounds.min - Vector3::new(0.5, 1.5, 2.5)).magnitude() < 1e-6);
+        assert!((bounds.max - Vector3::new(1.5, 2.5, 3.5)).magnitude() < 1e-6);
+    }
+}
@@ -0,0 +1,415 @@
+use crate::physics::aabb::Aabb;
+use crate::physics::phymgr::BodyHandle;
+use cgmath::*;
+
+// 空节点索引
+const NULL_NODE: usize = usize::MAX;
+/// 叶节点胖包围盒相对物体包围盒的外扩量，物体在此范围内移动时树无需更新
+pub const AABB_MARGIN: f32 = 0.1;
+
+struct TreeNode {
+    aabb: Aabb,
+    parent: usize,
+    child1: usize,
+    child2: usize,
+    height: u32,                // 叶节点为 0
+    handle: Option<BodyHandle>, // 只有叶节点有
+}
+
+impl TreeNode {
+    fn is_leaf(&self) -> bool {
+        self.child1 == NULL_NODE
+    }
+}
+
+/// 动态包围盒树（动态 BVH）
+///
+/// 每个叶节点保存一个物体的胖包围盒（外扩 `AABB_MARGIN`），内部节点的包围盒是两个子节点的并集。
+/// 插入时沿表面积代价最小的路径下降寻找兄弟节点；物体移出胖包围盒时先删除再重新插入，
+/// 之后沿祖先向上重新拟合包围盒，并在每一层尝试交换子节点与孙节点（树旋转），
+/// 只要能减小内部节点的表面积就旋转，使树在物体不断移动时保持紧凑。
+pub struct DynamicAabbTree {
+    nodes: Vec<TreeNode>,
+    free_nodes: Vec<usize>,
+    root: usize,
+}
+
+impl Default for DynamicAabbTree {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free_nodes: Vec::new(),
+            root: NULL_NODE,
+        }
+    }
+}
+
+impl DynamicAabbTree {
+    /// 插入一个物体，返回叶节点编号（代理），之后用它移动或删除该物体
+    pub fn create_proxy(&mut self, aabb: &Aabb, handle: BodyHandle) -> usize {
+        let leaf = self.allocate_node(aabb.expanded(AABB_MARGIN), Some(handle));
+        self.insert_leaf(leaf);
+        leaf
+    }
+
+    pub fn destroy_proxy(&mut self, proxy: usize) {
+        self.remove_leaf(proxy);
+        self.free_node(proxy);
+    }
+
+    /// 更新物体的包围盒；仍在胖包围盒内时不做任何事并返回 false
+    pub fn move_proxy(&mut self, proxy: usize, aabb: &Aabb) -> bool {
+        if self.nodes[proxy].aabb.contains(aabb) {
+            return false;
+        }
+
+        self.remove_leaf(proxy);
+        self.nodes[proxy].aabb = aabb.expanded(AABB_MARGIN);
+        self.insert_leaf(proxy);
+        true
+    }
+
+    /// 叶节点的胖包围盒
+    pub fn fat_aabb(&self, proxy: usize) -> &Aabb {
+        &self.nodes[proxy].aabb
+    }
+
+    /// 树高，空树为 0
+    pub fn height(&self) -> u32 {
+        if self.root == NULL_NODE {
+            0
+        } else {
+            self.nodes[self.root].height + 1
+        }
+    }
+
+    /// 遍历胖包围盒与区域相交的所有物体
+    pub fn query(&self, region: &Aabb, callback: &mut dyn FnMut(BodyHandle)) {
+        if self.root == NULL_NODE {
+            return;
+        }
+
+        let mut stack = vec![self.root];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.aabb.intersects(region) {
+                continue;
+            }
+            match node.handle {
+                Some(handle) => callback(handle),
+                None => {
+                    stack.push(node.child1);
+                    stack.push(node.child2);
+                }
+            }
+        }
+    }
+
+    /// 遍历胖包围盒与射线 origin + t · direction（0 ≤ t ≤ max_t）相交的物体
+    ///
+    /// 回调返回新的 max_t，之后只访问更近的包围盒；找最近命中时返回命中距离即可剪枝，
+    /// 需要所有命中时原样返回 max_t。
+    pub fn query_ray(
+        &self,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        mut max_t: f32,
+        callback: &mut dyn FnMut(BodyHandle) -> f32,
+    ) {
+        if self.root == NULL_NODE {
+            return;
+        }
+
+        let mut stack = vec![self.root];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node
+                .aabb
+                .ray_intersection(origin, direction, max_t)
+                .is_none()
+            {
+                continue;
+            }
+            match node.handle {
+                Some(handle) => max_t = max_t.min(callback(handle)),
+                None => {
+                    stack.push(node.child1);
+                    stack.push(node.child2);
+                }
+            }
+        }
+    }
+
+    fn allocate_node(&mut self, aabb: Aabb, handle: Option<BodyHandle>) -> usize {
+        let node = TreeNode {
+            aabb,
+            parent: NULL_NODE,
+            child1: NULL_NODE,
+            child2: NULL_NODE,
+            height: 0,
+            handle,
+        };
+        if let Some(index) = self.free_nodes.pop() {
+            self.nodes[index] = node;
+            index
+        } else {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        }
+    }
+
+    fn free_node(&mut self, index: usize) {
+        self.nodes[index].handle = None;
+        self.free_nodes.push(index);
+    }
+
+    fn insert_leaf(&mut self, leaf: usize) {
+        if self.root == NULL_NODE {
+            self.root = leaf;
+            self.nodes[leaf].parent = NULL_NODE;
+            return;
+        }
+
+        // 沿代价最小的路径下降，寻找最合适的兄弟节点
+        let leaf_aabb = self.nodes[leaf].aabb;
+        let mut index = self.root;
+        while !self.nodes[index].is_leaf() {
+            let node = &self.nodes[index];
+            let area = node.aabb.surface_area();
+            let combined_area = node.aabb.union(&leaf_aabb).surface_area();
+
+            // 在这里新建父节点的代价
+            let cost = 2.0 * combined_area;
+            // 继续下降时，祖先包围盒增大带来的代价
+            let inheritance_cost = 2.0 * (combined_area - area);
+
+            let child_cost = |child: usize| {
+                let child = &self.nodes[child];
+                let union_area = child.aabb.union(&leaf_aabb).surface_area();
+                if child.is_leaf() {
+                    union_area + inheritance_cost
+                } else {
+                    union_area - child.aabb.surface_area() + inheritance_cost
+                }
+            };
+            let cost1 = child_cost(node.child1);
+            let cost2 = child_cost(node.child2);
+
+            if cost < cost1 && cost < cost2 {
+                break;
+            }
+            index = if cost1 < cost2 {
+                node.child1
+            } else {
+                node.child2
+            };
+        }
+
+        // 新建父节点，把兄弟节点与新叶节点挂在下面
+        let sibling = index;
+        let old_parent = self.nodes[sibling].parent;
+        let new_parent = self.allocate_node(self.nodes[sibling].aabb.union(&leaf_aabb), None);
+        self.nodes[new_parent].parent = old_parent;
+        self.nodes[new_parent].child1 = sibling;
+        self.nodes[new_parent].child2 = leaf;
+        self.nodes[new_parent].height = self.nodes[sibling].height + 1;
+        self.nodes[sibling].parent = new_parent;
+        self.nodes[leaf].parent = new_parent;
+
+        if old_parent == NULL_NODE {
+            self.root = new_parent;
+        } else {
+            self.replace_child(old_parent, sibling, new_parent);
+        }
+
+        self.refit_ancestors(new_parent);
+    }
+
+    fn remove_leaf(&mut self, leaf: usize) {
+        if leaf == self.root {
+            self.root = NULL_NODE;
+            return;
+        }
+
+        let parent = self.nodes[leaf].parent;
+        let grandparent = self.nodes[parent].parent;
+        let sibling = if self.nodes[parent].child1 == leaf {
+            self.nodes[parent].child2
+        } else {
+            self.nodes[parent].child1
+        };
+
+        // 用兄弟节点顶替父节点
+        self.nodes[sibling].parent = grandparent;
+        if grandparent == NULL_NODE {
+            self.root = sibling;
+        } else {
+            self.replace_child(grandparent, parent, sibling);
+        }
+        self.free_node(parent);
+        self.nodes[leaf].parent = NULL_NODE;
+
+        self.refit_ancestors(grandparent);
+    }
+
+    fn replace_child(&mut self, parent: usize, old_child: usize, new_child: usize) {
+        let node = &mut self.nodes[parent];
+        if node.child1 == old_child {
+            node.child1 = new_child;
+        } else {
+            node.child2 = new_child;
+        }
+    }
+
+    // 根据两个子节点重算包围盒与高度
+    fn refit(&mut self, index: usize) {
+        let (child1, child2) = (self.nodes[index].child1, self.nodes[index].child2);
+        self.nodes[index].aabb = self.nodes[child1].aabb.union(&self.nodes[child2].aabb);
+        self.nodes[index].height = 1 + self.nodes[child1].height.max(self.nodes[child2].height);
+    }
+
+    // 从给定节点向上重新拟合包围盒，并在每一层尝试旋转
+    fn refit_ancestors(&mut self, mut index: usize) {
+        while index != NULL_NODE {
+            self.refit(index);
+            self.rotate(index);
+            index = self.nodes[index].parent;
+        }
+    }
+
+    /// 树旋转：尝试把一个子节点与另一个子节点的某个孙节点交换，
+    /// 选择使被改动的内部节点表面积减小最多的交换；都不能减小时不旋转
+    fn rotate(&mut self, index: usize) {
+        let (b, c) = (self.nodes[index].child1, self.nodes[index].child2);
+
+        // (要移走的子节点, 接收它的兄弟节点, 与之交换的孙节点, 表面积减小量)
+        let mut best: Option<(usize, usize, usize, f32)> = None;
+        for (child, sibling) in [(b, c), (c, b)] {
+            let sibling_node = &self.nodes[sibling];
+            if sibling_node.is_leaf() {
+                continue;
+            }
+            let area = sibling_node.aabb.surface_area();
+            let child_aabb = self.nodes[child].aabb;
+            for (grandchild, remaining) in [
+                (sibling_node.child1, sibling_node.child2),
+                (sibling_node.child2, sibling_node.child1),
+            ] {
+                let new_area = child_aabb.union(&self.nodes[remaining].aabb).surface_area();
+                let gain = area - new_area;
+                if gain > best.map_or(0.0, |(_, _, _, g)| g) {
+                    best = Some((child, sibling, grandchild, gain));
+                }
+            }
+        }
+
+        let Some((child, sibling, grandchild, _)) = best else {
+            return;
+        };
+        self.replace_child(index, child, grandchild);
+        self.replace_child(sibling, grandchild, child);
+        self.nodes[grandchild].parent = index;
+        self.nodes[child].parent = sibling;
+        self.refit(sibling);
+        self.refit(index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Transform;
+    use crate::physics::phymgr::BodySet;
+    use crate::physics::shape::{PhysicsBody, Shape, Sphere};
+
+    impl DynamicAabbTree {
+        // 检查父子链接、包围盒包含关系与高度，返回叶节点数
+        fn validate(&self, index: usize) -> usize {
+            let node = &self.nodes[index];
+            if node.is_leaf() {
+                assert_eq!(node.height, 0);
+                assert!(node.handle.is_some());
+                return 1;
+            }
+            let (child1, child2) = (&self.nodes[node.child1], &self.nodes[node.child2]);
+            assert_eq!(child1.parent, index);
+            assert_eq!(child2.parent, index);
+            assert!(node.aabb.contains(&child1.aabb) && node.aabb.contains(&child2.aabb));
+            assert_eq!(node.height, 1 + child1.height.max(child2.height));
+            self.validate(node.child1) + self.validate(node.child2)
+        }
+    }
+
+    fn dummy_handles(count: usize) -> Vec<BodyHandle> {
+        let mut bodies = BodySet::default();
+        (0..count)
+            .map(|_| {
+                bodies.insert(PhysicsBody::new_dynamic(
+                    Shape::Sphere(Sphere {
+                        center: Vector3::zero(),
+                        radius: 0.5,
+                    }),
+                    Transform::identity(),
+                    1.0,
+                ))
+            })
+            .collect()
+    }
+
+    fn unit_aabb_at(x: f32, y: f32, z: f32) -> Aabb {
+        let center = Vector3::new(x, y, z);
+        let half = Vector3::new(0.5, 0.5, 0.5);
+        Aabb::new(center - half, center + half)
+    }
+
+    #[test]
+    fn test_tree_queries_and_balance() {
+        // 一排沿 X 轴依次插入的物体：不做旋转时树会退化成链表
+        let handles = dummy_handles(256);
+        let mut tree = DynamicAabbTree::default();
+        let mut proxies = handles
+            .iter()
+            .enumerate()
+            .map(|(i, &handle)| tree.create_proxy(&unit_aabb_at(i as f32 * 2.0, 0.0, 0.0), handle))
+            .collect::<Vec<_>>();
+        assert_eq!(tree.validate(tree.root), 256);
+        assert!(tree.height() <= 20, "height = {}", tree.height());
+
+        let mut found = Vec::new();
+        let region = Aabb::new(Vector3::new(7.0, -1.0, -1.0), Vector3::new(15.0, 1.0, 1.0));
+        tree.query(&region, &mut |handle| found.push(handle));
+        found.sort_unstable();
+        assert_eq!(found, handles[4..=7].to_vec());
+
+        // 射线沿 X 轴穿过所有物体；回调返回命中距离后只会访问更近的物体
+        let mut nearest = None;
+        tree.query_ray(
+            Vector3::new(-5.0, 0.0, 0.0),
+            Vector3::unit_x(),
+            1000.0,
+            &mut |handle| {
+                let index = handles.iter().position(|&h| h == handle).unwrap();
+                let t = index as f32 * 2.0 - 0.5 + 5.0;
+                if nearest.is_none_or(|(best, _)| t < best) {
+                    nearest = Some((t, handle));
+                }
+                t
+            },
+        );
+        assert_eq!(nearest.map(|(_, handle)| handle), Some(handles[0]));
+
+        // 小幅移动不触发更新，移出胖包围盒后重新插入
+        assert!(!tree.move_proxy(proxies[0], &unit_aabb_at(0.05, 0.0, 0.0)));
+        assert!(tree.move_proxy(proxies[0], &unit_aabb_at(-100.0, 0.0, 0.0)));
+        let mut found = Vec::new();
+        tree.query(&unit_aabb_at(-100.0, 0.0, 0.0), &mut |handle| {
+            found.push(handle)
+        });
+        assert_eq!(found, vec![handles[0]]);
+
+        for proxy in proxies.drain(..128) {
+            tree.destroy_proxy(proxy);
+        }
+        assert_eq!(tree.validate(tree.root), 128);
+    }
+}
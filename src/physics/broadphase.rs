@@ -0,0 +1,564 @@
+use crate::physics::aabb::Aabb;
+use crate::physics::aabbtree::DynamicAabbTree;
+use crate::physics::filter::can_collide_in;
+use crate::physics::phymgr::{BodyHandle, BodySet};
+use cgmath::*;
+
+/// 粗检测接口：维护所有物体的包围盒，输出候选物体对，并支持包围盒与射线查询
+///
+/// 查询基于上次 `update` 时的包围盒，且可能比物体实际包围盒大（胖包围盒），
+/// 调用方需要自行做精确检测。
+pub trait BroadPhase {
+    /// 同步物理体集合中所有物体的包围盒，并重新计算候选对
+    fn update(&mut self, bodies: &BodySet);
+
+    /// 上次更新得到的候选对，每对中句柄槽位较小的在前，整体按句柄排序；
+    /// 碰撞分组不匹配或互相忽略的物体对不会输出
+    fn pairs(&self) -> &[(BodyHandle, BodyHandle)];
+
+    /// 遍历包围盒与区域相交的物体
+    fn query_aabb(&self, region: &Aabb, callback: &mut dyn FnMut(BodyHandle));
+
+    /// 遍历包围盒与射线 origin + t · direction（0 ≤ t ≤ max_t）相交的物体，
+    /// 回调返回新的 max_t，之后只访问更近的物体
+    fn query_ray(
+        &self,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        max_t: f32,
+        callback: &mut dyn FnMut(BodyHandle) -> f32,
+    );
+}
+
+/// 可选的粗检测算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BroadPhaseKind {
+    #[default]
+    SweepAndPrune,
+    AabbTree,
+}
+
+impl BroadPhaseKind {
+    pub const ALL: [BroadPhaseKind; 2] = [BroadPhaseKind::SweepAndPrune, BroadPhaseKind::AabbTree];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BroadPhaseKind::SweepAndPrune => "Sweep and Prune",
+            BroadPhaseKind::AabbTree => "Dynamic AABB Tree",
+        }
+    }
+
+    pub fn create(&self) -> Box<dyn BroadPhase> {
+        match self {
+            BroadPhaseKind::SweepAndPrune => Box::<SweepAndPrune>::default(),
+            BroadPhaseKind::AabbTree => Box::<AabbTreeBroadPhase>::default(),
+        }
+    }
+}
+
+// 物理体在粗检测中的代理
+struct Proxy {
+    handle: BodyHandle,
+    aabb: Aabb,
+    seen: bool, // 本次更新中是否仍存在于物理体集合中
+}
+
+// 包围盒在扫描轴上的端点
+#[derive(Clone, Copy)]
+struct Endpoint {
+    value: f32,
+    slot: u32, // 所属代理的槽位（与句柄的槽位索引相同）
+    is_min: bool,
+}
+
+impl Endpoint {
+    // 值相同时 min 端点排在 max 端点之前，使恰好接触的包围盒也成为候选对
+    fn precedes(&self, other: &Endpoint) -> bool {
+        self.value < other.value || (self.value == other.value && self.is_min && !other.is_min)
+    }
+}
+
+/// 增量式扫描与剪枝（sweep and prune）粗检测
+///
+/// 所有物体包围盒在扫描轴上的端点保存在一个有序列表中。物体每帧移动很少，
+/// 列表基本有序，插入排序接近 O(n)；之后沿扫描轴扫描一遍，只对投影区间重叠的物体
+/// 检查另外两个轴，输出候选物体对交给窄相检测。
+/// 扫描轴取物体中心分布最分散的轴，轴变化时整体重排一次。
+#[derive(Default)]
+pub struct SweepAndPrune {
+    proxies: Vec<Option<Proxy>>, // 按句柄槽位索引
+    endpoints: Vec<Endpoint>,
+    axis: usize,
+    pairs: Vec<(BodyHandle, BodyHandle)>,
+}
+
+impl BroadPhase for SweepAndPrune {
+    fn update(&mut self, bodies: &BodySet) {
+        self.sync_proxies(bodies);
+
+        let axis = self.choose_axis();
+        let axis_changed = axis != self.axis;
+        self.axis = axis;
+
+        for endpoint in &mut self.endpoints {
+            let aabb = &self.proxies[endpoint.slot as usize].as_ref().unwrap().aabb;
+            endpoint.value = if endpoint.is_min {
+                aabb.min[axis]
+            } else {
+                aabb.max[axis]
+            };
+        }
+
+        if axis_changed {
+            self.endpoints.sort_by(|a, b| {
+                if a.precedes(b) {
+                    std::cmp::Ordering::Less
+                } else if b.precedes(a) {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            });
+        } else {
+            insertion_sort(&mut self.endpoints);
+        }
+
+        self.sweep(bodies);
+    }
+
+    fn pairs(&self) -> &[(BodyHandle, BodyHandle)] {
+        &self.pairs
+    }
+
+    fn query_aabb(&self, region: &Aabb, callback: &mut dyn FnMut(BodyHandle)) {
+        for proxy in self.proxies.iter().flatten() {
+            if proxy.aabb.intersects(region) {
+                callback(proxy.handle);
+            }
+        }
+    }
+
+    fn query_ray(
+        &self,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        mut max_t: f32,
+        callback: &mut dyn FnMut(BodyHandle) -> f32,
+    ) {
+        for proxy in self.proxies.iter().flatten() {
+            if proxy
+                .aabb
+                .ray_intersection(origin, direction, max_t)
+                .is_some()
+            {
+                max_t = max_t.min(callback(proxy.handle));
+            }
+        }
+    }
+}
+
+impl SweepAndPrune {
+    /// 物体的包围盒（上次更新时的值）
+    pub fn aabb(&self, handle: BodyHandle) -> Option<&Aabb> {
+        self.proxies
+            .get(handle.index())?
+            .as_ref()
+            .filter(|proxy| proxy.handle == handle)
+            .map(|proxy| &proxy.aabb)
+    }
+
+    fn sync_proxies(&mut self, bodies: &BodySet) {
+        for proxy in self.proxies.iter_mut().flatten() {
+            proxy.seen = false;
+        }
+
+        for (handle, body) in bodies.iter() {
+            let slot = handle.index();
+            if slot >= self.proxies.len() {
+                self.proxies.resize_with(slot + 1, || None);
+            }
+            // 新的槽位才需要加入端点；槽位被复用时沿用原来的端点
+            if self.proxies[slot].is_none() {
+                for is_min in [true, false] {
+                    self.endpoints.push(Endpoint {
+                        value: 0.0,
+                        slot: slot as u32,
+                        is_min,
+                    });
+                }
+            }
+            self.proxies[slot] = Some(Proxy {
+                handle,
+                aabb: body.aabb(),
+                seen: true,
+            });
+        }
+
+        // 移除已删除物体的代理与端点
+        let mut removed = false;
+        for proxy in &mut self.proxies {
+            if proxy.as_ref().is_some_and(|p| !p.seen) {
+                *proxy = None;
+                removed = true;
+            }
+        }
+        if removed {
+            let proxies = &self.proxies;
+            self.endpoints
+                .retain(|endpoint| proxies[endpoint.slot as usize].is_some());
+        }
+    }
+
+    // 有限包围盒中心方差最大的轴
+    fn choose_axis(&self) -> usize {
+        let centers = self
+            .proxies
+            .iter()
+            .flatten()
+            .map(|proxy| proxy.aabb.center())
+            .filter(|center| center.x.is_finite() && center.y.is_finite() && center.z.is_finite())
+            .collect::<Vec<_>>();
+        if centers.len() < 2 {
+            return self.axis;
+        }
+
+        let count = centers.len() as f32;
+        let mean = centers.iter().fold([0.0; 3], |acc, c| {
+            [
+                acc[0] + c.x / count,
+                acc[1] + c.y / count,
+                acc[2] + c.z / count,
+            ]
+        });
+        let variance = centers.iter().fold([0.0f32; 3], |acc, c| {
+            [0, 1, 2].map(|i| acc[i] + (c[i] - mean[i]).powi(2))
+        });
+
+        // 只有明显更分散时才换轴，避免来回切换导致整体重排
+        let best = (0..3)
+            .max_by(|&a, &b| variance[a].total_cmp(&variance[b]))
+            .unwrap();
+        if variance[best] > variance[self.axis] * 1.5 {
+            best
+        } else {
+            self.axis
+        }
+    }
+
+    fn sweep(&mut self, bodies: &BodySet) {
+        self.pairs.clear();
+        let mut active: Vec<u32> = Vec::new();
+
+        for endpoint in &self.endpoints {
+            if !endpoint.is_min {
+                if let Some(pos) = active.iter().position(|&slot| slot == endpoint.slot) {
+                    active.swap_remove(pos);
+                }
+                continue;
+            }
+
+            let proxy = self.proxies[endpoint.slot as usize].as_ref().unwrap();
+            for &other_slot in &active {
+                let other = self.proxies[other_slot as usize].as_ref().unwrap();
+                if proxy.aabb.intersects(&other.aabb)
+                    && can_collide_in(bodies, proxy.handle, other.handle)
+                {
+                    let pair = if proxy.handle.index() < other.handle.index() {
+                        (proxy.handle, other.handle)
+                    } else {
+                        (other.handle, proxy.handle)
+                    };
+                    self.pairs.push(pair);
+                }
+            }
+            active.push(endpoint.slot);
+        }
+
+        // 输出顺序与扫描顺序无关，保证模拟结果可复现
+        self.pairs.sort_unstable();
+    }
+}
+
+fn insertion_sort(endpoints: &mut [Endpoint]) {
+    for i in 1..endpoints.len() {
+        let mut j = i;
+        while j > 0 && endpoints[j].precedes(&endpoints[j - 1]) {
+            endpoints.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
+
+// 动态包围盒树粗检测中的物体代理
+struct TreeProxy {
+    handle: BodyHandle,
+    aabb: Aabb,
+    node: Option<usize>, // 树中的叶节点；无界包围盒（如平面）不放进树里
+    seen: bool,
+}
+
+/// 基于动态包围盒树的粗检测
+///
+/// 每个物体用胖包围盒在树中查询重叠的物体得到候选对。平面等无界包围盒不放进树里，
+/// 单独与所有物体比较。
+#[derive(Default)]
+pub struct AabbTreeBroadPhase {
+    tree: DynamicAabbTree,
+    proxies: Vec<Option<TreeProxy>>, // 按句柄槽位索引
+    pairs: Vec<(BodyHandle, BodyHandle)>,
+}
+
+impl AabbTreeBroadPhase {
+    pub fn tree(&self) -> &DynamicAabbTree {
+        &self.tree
+    }
+
+    fn sync_proxies(&mut self, bodies: &BodySet) {
+        for proxy in self.proxies.iter_mut().flatten() {
+            proxy.seen = false;
+        }
+
+        for (handle, body) in bodies.iter() {
+            let slot = handle.index();
+            if slot >= self.proxies.len() {
+                self.proxies.resize_with(slot + 1, || None);
+            }
+
+            let aabb = body.aabb();
+            let bounded = aabb.is_bounded();
+            match &mut self.proxies[slot] {
+                Some(proxy) if proxy.handle == handle && proxy.node.is_some() == bounded => {
+                    if let Some(node) = proxy.node {
+                        self.tree.move_proxy(node, &aabb);
+                    }
+                    proxy.aabb = aabb;
+                    proxy.seen = true;
+                }
+                entry => {
+                    // 新物体，或槽位被复用、包围盒有界性变化：重新创建代理
+                    if let Some(node) = entry.as_ref().and_then(|proxy| proxy.node) {
+                        self.tree.destroy_proxy(node);
+                    }
+                    *entry = Some(TreeProxy {
+                        handle,
+                        aabb,
+                        node: bounded.then(|| self.tree.create_proxy(&aabb, handle)),
+                        seen: true,
+                    });
+                }
+            }
+        }
+
+        for entry in &mut self.proxies {
+            if entry.as_ref().is_some_and(|proxy| !proxy.seen) {
+                if let Some(node) = entry.as_ref().and_then(|proxy| proxy.node) {
+                    self.tree.destroy_proxy(node);
+                }
+                *entry = None;
+            }
+        }
+    }
+
+    // 粗检测使用的包围盒：树中物体为胖包围盒，无界物体为其本身
+    fn broad_aabb(&self, proxy: &TreeProxy) -> Aabb {
+        match proxy.node {
+            Some(node) => *self.tree.fat_aabb(node),
+            None => proxy.aabb,
+        }
+    }
+}
+
+impl BroadPhase for AabbTreeBroadPhase {
+    fn update(&mut self, bodies: &BodySet) {
+        self.sync_proxies(bodies);
+
+        self.pairs.clear();
+        let mut pairs = Vec::new();
+        for proxy in self.proxies.iter().flatten() {
+            let aabb = self.broad_aabb(proxy);
+            if proxy.node.is_some() {
+                // 每对只在槽位较小的一方查询时记录一次
+                self.tree.query(&aabb, &mut |other| {
+                    if other.index() > proxy.handle.index()
+                        && can_collide_in(bodies, proxy.handle, other)
+                    {
+                        pairs.push((proxy.handle, other));
+                    }
+                });
+            } else {
+                for other in self.proxies.iter().flatten() {
+                    let counted_by_other =
+                        other.node.is_none() && other.handle.index() < proxy.handle.index();
+                    if other.handle != proxy.handle
+                        && !counted_by_other
+                        && aabb.intersects(&self.broad_aabb(other))
+                        && can_collide_in(bodies, proxy.handle, other.handle)
+                    {
+                        let pair = if proxy.handle.index() < other.handle.index() {
+                            (proxy.handle, other.handle)
+                        } else {
+                            (other.handle, proxy.handle)
+                        };
+                        pairs.push(pair);
+                    }
+                }
+            }
+        }
+
+        // 输出顺序与树结构无关，保证模拟结果可复现
+        pairs.sort_unstable();
+        self.pairs = pairs;
+    }
+
+    fn pairs(&self) -> &[(BodyHandle, BodyHandle)] {
+        &self.pairs
+    }
+
+    fn query_aabb(&self, region: &Aabb, callback: &mut dyn FnMut(BodyHandle)) {
+        self.tree.query(region, callback);
+        for proxy in self.proxies.iter().flatten() {
+            if proxy.node.is_none() && proxy.aabb.intersects(region) {
+                callback(proxy.handle);
+            }
+        }
+    }
+
+    fn query_ray(
+        &self,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        mut max_t: f32,
+        callback: &mut dyn FnMut(BodyHandle) -> f32,
+    ) {
+        for proxy in self.proxies.iter().flatten() {
+            if proxy.node.is_none()
+                && proxy
+                    .aabb
+                    .ray_intersection(origin, direction, max_t)
+                    .is_some()
+            {
+                max_t = max_t.min(callback(proxy.handle));
+            }
+        }
+        self.tree.query_ray(origin, direction, max_t, callback);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Transform;
+    use crate::physics::shape::{PhysicsBody, Plane, Shape, Sphere};
+
+    // 简单的线性同余随机数，保证测试可复现
+    fn next_random(state: &mut u32) -> f32 {
+        *state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+        (*state >> 8) as f32 / (1 << 24) as f32
+    }
+
+    fn brute_force_pairs(bodies: &BodySet) -> Vec<(BodyHandle, BodyHandle)> {
+        let all = bodies.iter().collect::<Vec<_>>();
+        let mut pairs = Vec::new();
+        for (i, &(handle1, body1)) in all.iter().enumerate() {
+            for &(handle2, body2) in &all[(i + 1)..] {
+                if body1.aabb().intersects(&body2.aabb()) {
+                    pairs.push((handle1, handle2));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    // 在 20×5×20 的范围内随机移动 200 个球和一个地面平面，并不时删除、添加物体，
+    // 每帧更新粗检测后交给 check 检查
+    fn simulate_random_world(
+        broad_phase: &mut dyn BroadPhase,
+        mut check: impl FnMut(&dyn BroadPhase, &BodySet),
+    ) {
+        let mut bodies = BodySet::default();
+        let mut state = 7;
+        let mut handles = (0..200)
+            .map(|_| {
+                let position = Vector3::new(
+                    next_random(&mut state) * 20.0,
+                    next_random(&mut state) * 5.0,
+                    next_random(&mut state) * 20.0,
+                );
+                bodies.insert(PhysicsBody::new_dynamic(
+                    Shape::Sphere(Sphere {
+                        center: Vector3::zero(),
+                        radius: 0.5,
+                    }),
+                    Transform::translation(position.x, position.y, position.z),
+                    1.0,
+                ))
+            })
+            .collect::<Vec<_>>();
+        bodies.insert(PhysicsBody::new_static(
+            Shape::Plane(Plane {
+                normal: Vector3::unit_y(),
+                distance: 0.0,
+            }),
+            Transform::translation(0.0, 0.2, 0.0),
+        ));
+
+        for frame in 0..20 {
+            for (_, body) in bodies.iter_mut() {
+                body.transform.translation += Vector3::new(
+                    next_random(&mut state) - 0.5,
+                    next_random(&mut state) - 0.5,
+                    next_random(&mut state) - 0.5,
+                ) * 0.5;
+            }
+            if frame % 5 == 4 {
+                let removed = handles.swap_remove(frame);
+                bodies.remove(removed);
+                handles.push(bodies.insert(PhysicsBody::new_dynamic(
+                    Shape::Box {
+                        half_extents: Vector3::new(1.0, 1.0, 1.0),
+                    },
+                    Transform::translation(10.0, 2.0, 10.0),
+                    1.0,
+                )));
+            }
+
+            broad_phase.update(&bodies);
+            check(broad_phase, &bodies);
+        }
+    }
+
+    #[test]
+    fn test_sweep_and_prune_matches_brute_force() {
+        simulate_random_world(&mut SweepAndPrune::default(), |broad_phase, bodies| {
+            let expected = brute_force_pairs(bodies);
+            // 地面平面与低处的物体成对
+            assert!(!expected.is_empty());
+            assert_eq!(broad_phase.pairs(), expected.as_slice());
+        });
+    }
+
+    #[test]
+    fn test_aabb_tree_finds_all_pairs_and_regions() {
+        let mut broad_phase = AabbTreeBroadPhase::default();
+        simulate_random_world(&mut broad_phase, |broad_phase, bodies| {
+            // 胖包围盒可能多给出一些候选对，但不能漏掉
+            let pairs = broad_phase.pairs();
+            for pair in brute_force_pairs(bodies) {
+                assert!(pairs.binary_search(&pair).is_ok(), "missing {:?}", pair);
+            }
+
+            let region = Aabb::new(Vector3::new(5.0, 1.0, 5.0), Vector3::new(10.0, 3.0, 10.0));
+            let mut found = Vec::new();
+            broad_phase.query_aabb(&region, &mut |handle| found.push(handle));
+            for (handle, body) in bodies.iter() {
+                if body.aabb().intersects(&region) {
+                    assert!(found.contains(&handle));
+                }
+            }
+        });
+
+        // 201 个物体中平面不在树里
+        assert!(broad_phase.tree().height() < 30);
+    }
+}
@@ -1,5 +1,8 @@
 use crate::common::Transform; // 明确导入Transform以避免歧义
-use crate::physics::shape::{Cylinder, PhysicsBody, Plane, Shape};
+use crate::physics::gjk::{collide_convex, SupportMap, TransformedHull};
+use crate::physics::heightfield::{collide_heightfield, HeightGrid};
+use crate::physics::shape::{ConvexHull, Cylinder, PhysicsBody, Plane, Shape, Sphere};
+use crate::physics::trimesh::collide_trimesh;
 use cgmath::*;
 
 // 碰撞信息
@@ -8,284 +11,1342 @@ pub struct CollisionInfo {
     pub contact_point: Vector3<f32>, // 接触点
     pub penetration_depth: f32,      // 穿透深度
     pub normal: Vector3<f32>,        // 指向第一个物体的法向量
+    pub feature: u32,                // 接触特征编号，用于跨帧匹配同一接触点
 }
 
-/// 检测两个圆柱体之间的碰撞
-pub fn collide_cylinder_cylinder(cyl1: &Cylinder, cyl2: &Cylinder) -> Option<CollisionInfo> {
-    // 将圆柱体轴向量标准化
-    let axis1 = cyl1.axis.normalize();
-    let axis2 = cyl2.axis.normalize();
+/// 接触流形：一对物体之间的一组接触点，法线均指向第一个物体
+///
+/// 面与面贴合时需要多个接触点才能稳定支撑，例如长方体平放在地面上。
+#[derive(Clone, Default)]
+pub struct ContactManifold {
+    pub points: Vec<CollisionInfo>,
+}
 
-    // 计算两圆柱体底面中心的向量
-    let center_diff = cyl2.center - cyl1.center;
+impl ContactManifold {
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
 
-    // 计算两轴线之间的最短距离
-    let cross_product = axis1.cross(axis2);
-    let denom = cross_product.magnitude2();
+    /// 交换两物体顺序后的流形
+    pub(crate) fn flipped(self) -> Self {
+        Self {
+            points: self.points.into_iter().map(flip_normal).collect(),
+        }
+    }
 
-    if denom < 1e-6 {
-        // 两轴平行的情况
-        let dist_from_axis = (center_diff - axis1 * center_diff.dot(axis1)).magnitude();
-        let max_radius = cyl1.radius + cyl2.radius;
-
-        if dist_from_axis <= max_radius {
-            // 计算沿轴向的重叠
-            let proj1 = center_diff.dot(axis1);
-            let half_height1 = cyl1.height / 2.0;
-            let half_height2 = cyl2.height / 2.0;
-
-            // 检查高度方向是否有重叠
-            if proj1.abs() <= half_height1 + half_height2 {
-                // 计算接触点（取两轴线上最近点的中点）
-                let contact_point = cyl1.center
-                    + axis1 * proj1
-                    + (axis1.cross(Vector3::unit_x())).normalize() * (max_radius - dist_from_axis)
-                        / 2.0;
-
-                // 法向量指向第二个圆柱体
-                let normal = if dist_from_axis > 0.0 {
-                    (cyl2.center - cyl1.center).normalize()
-                } else {
-                    axis1
-                };
-
-                return Some(CollisionInfo {
-                    contact_point,
-                    penetration_depth: max_radius - dist_from_axis,
-                    normal,
-                });
-            }
+    /// 空流形表示没有碰撞
+    pub(crate) fn into_option(self) -> Option<Self> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self)
         }
-    } else {
-        // 两轴不平行的情况 - 计算两直线间的最短距离
-        let r12 = cyl2.center - cyl1.center;
-        let a = axis1.magnitude2(); // |u|^2
-        let b = axis1.dot(axis2); // u·v
-        let c = axis2.magnitude2(); // |v|^2
-        let d = axis1.dot(r12); // u·w
-        let e = axis2.dot(r12); // v·w
-
-        let denominator = a * c - b * b;
-        if denominator < 1e-6 {
-            // 接近平行的情况
-            return collide_cylinder_cylinder_parallel(cyl1, cyl2);
-        }
-
-        let t_u = (b * e - c * d) / denominator;
-        let t_v = (a * e - b * d) / denominator;
-
-        let point_on_axis1 = cyl1.center + axis1 * t_u;
-        let point_on_axis2 = cyl2.center + axis2 * t_v;
-        let shortest_dist_vec = point_on_axis2 - point_on_axis1;
-        let dist = shortest_dist_vec.magnitude();
-
-        let max_radius = cyl1.radius + cyl2.radius;
-
-        if dist <= max_radius {
-            // 检查沿各自轴的范围是否重叠
-            let half_h1 = cyl1.height / 2.0;
-            let half_h2 = cyl2.height / 2.0;
-
-            // 检查第一个圆柱体轴上的投影
-            let proj1 = (point_on_axis1 - cyl1.center).dot(axis1);
-            if proj1.abs() > half_h1 {
-                // 最近点超出圆柱体范围，需要调整
-                return None; // 简化处理，实际应找到边界点
-            }
+    }
+}
 
-            // 检查第二个圆柱体轴上的投影
-            let proj2 = (point_on_axis2 - cyl2.center).dot(axis2);
-            if proj2.abs() > half_h2 {
-                // 最近点超出圆柱体范围，需要调整
-                return None; // 简化处理，实际应找到边界点
-            }
+impl From<CollisionInfo> for ContactManifold {
+    fn from(info: CollisionInfo) -> Self {
+        Self { points: vec![info] }
+    }
+}
 
-            // 计算接触点（两最近点的中点）
-            let contact_point = (point_on_axis1 + point_on_axis2) / 2.0;
+/// 世界空间中的有向包围盒（OBB）
+#[derive(Clone, Copy)]
+pub struct Obb {
+    pub center: Vector3<f32>,
+    pub axes: [Vector3<f32>; 3], // 三个局部轴（单位向量，两两正交）
+    pub half_extents: Vector3<f32>,
+}
 
-            // 法向量从第一个圆柱体指向第二个
-            let normal = if dist > 0.0 {
-                shortest_dist_vec.normalize()
-            } else {
-                // 如果距离为0，使用轴向量之一
-                axis1
-            };
+impl Obb {
+    /// 由长方体形状与物体变换构造
+    pub fn new(half_extents: Vector3<f32>, transform: &Transform) -> Self {
+        let rotation = transform.rotation;
+        Self {
+            center: transform.translation,
+            axes: [
+                rotation * Vector3::unit_x(),
+                rotation * Vector3::unit_y(),
+                rotation * Vector3::unit_z(),
+            ],
+            half_extents: half_extents.mul_element_wise(transform.scale),
+        }
+    }
 
-            return Some(CollisionInfo {
-                contact_point,
-                penetration_depth: max_radius - dist,
-                normal,
-            });
+    /// 八个顶点，索引的第 0/1/2 位分别表示沿 x/y/z 轴取正向还是负向
+    pub fn vertices(&self) -> [Vector3<f32>; 8] {
+        let mut vertices = [self.center; 8];
+        for (index, vertex) in vertices.iter_mut().enumerate() {
+            for axis in 0..3 {
+                let sign = if index & (1 << axis) != 0 { 1.0 } else { -1.0 };
+                *vertex += self.axes[axis] * (sign * self.half_extents[axis]);
+            }
         }
+        vertices
+    }
+
+    /// 在给定方向上的投影半径
+    pub fn project_radius(&self, direction: Vector3<f32>) -> f32 {
+        (0..3)
+            .map(|axis| self.axes[axis].dot(direction).abs() * self.half_extents[axis])
+            .sum()
+    }
+
+    /// 世界坐标点转换到盒子的局部坐标
+    fn point_to_local(&self, point: Vector3<f32>) -> Vector3<f32> {
+        let offset = point - self.center;
+        Vector3::new(
+            offset.dot(self.axes[0]),
+            offset.dot(self.axes[1]),
+            offset.dot(self.axes[2]),
+        )
+    }
+
+    fn local_to_world(&self, local: Vector3<f32>) -> Vector3<f32> {
+        self.center + self.axes[0] * local.x + self.axes[1] * local.y + self.axes[2] * local.z
+    }
+
+    fn contains(&self, point: Vector3<f32>) -> bool {
+        let local = self.point_to_local(point);
+        (0..3).all(|axis| local[axis].abs() <= self.half_extents[axis])
+    }
+}
+
+/// 检测两个圆柱体之间的碰撞，返回的法线指向第一个圆柱体
+///
+/// 轴线平行时用解析解求穿透方向与深度，否则用 GJK/EPA。端面相对时（法线接近某一轴线），
+/// 再把落入对方内部的端面边缘采样点作为接触点，叠放的圆柱体才有多个支撑点。
+pub fn collide_cylinder_cylinder(cyl1: &Cylinder, cyl2: &Cylinder) -> Option<ContactManifold> {
+    let axis1 = cyl1.axis.normalize();
+    let axis2 = cyl2.axis.normalize();
+
+    let info = if axis1.cross(axis2).magnitude2() < 1e-6 {
+        collide_cylinder_cylinder_parallel(cyl1, cyl2)?
+    } else {
+        collide_convex(cyl1, cyl2)?
+    };
+    let normal = info.normal;
+
+    let cap_contact = axis1.dot(normal).abs().max(axis2.dot(normal).abs()) > CAP_CONTACT_COS;
+    if !cap_contact {
+        return Some(info.into());
     }
 
-    None
+    // 第一个圆柱体朝向 -normal 的端面边缘落入第二个圆柱体、第二个圆柱体朝向 normal 的端面边缘
+    // 落入第一个圆柱体的点；深度为沿法线到对方支撑平面的距离
+    let top2 = cyl2.support(normal).dot(normal);
+    let bottom1 = cyl1.support(-normal).dot(normal);
+    let mut points = cap_rim(cyl1, -normal)
+        .filter(|&point| cylinder_contains(cyl2, point))
+        .map(|point| (point, top2 - point.dot(normal)))
+        .chain(
+            cap_rim(cyl2, normal)
+                .filter(|&point| cylinder_contains(cyl1, point))
+                .map(|point| (point, point.dot(normal) - bottom1)),
+        )
+        .enumerate()
+        .filter(|(_, (_, depth))| *depth >= 0.0)
+        .map(|(index, (point, depth))| CollisionInfo {
+            contact_point: point,
+            penetration_depth: depth,
+            normal,
+            feature: index as u32 + 1,
+        })
+        .collect::<Vec<_>>();
+
+    if points.len() > MAX_PLANE_CONTACTS {
+        points = reduce_contacts(&points, normal);
+    }
+    if points.is_empty() {
+        points.push(CollisionInfo { feature: 0, ..info });
+    }
+    Some(ContactManifold { points })
+}
+
+// 法线与轴线夹角的余弦大于该值时视为端面接触
+const CAP_CONTACT_COS: f32 = 0.95;
+
+// 圆柱体朝向 direction 一侧端面边缘上的采样点
+fn cap_rim(cylinder: &Cylinder, direction: Vector3<f32>) -> impl Iterator<Item = Vector3<f32>> {
+    let axis = cylinder.axis.normalize();
+    let side = if axis.dot(direction) >= 0.0 {
+        1.0
+    } else {
+        -1.0
+    };
+    let cap_center = cylinder.center + axis * (side * cylinder.height / 2.0);
+    let u = any_perpendicular(axis);
+    let v = axis.cross(u);
+    let radius = cylinder.radius;
+    (0..CYLINDER_RIM_SAMPLES).map(move |i| {
+        let angle = i as f32 / CYLINDER_RIM_SAMPLES as f32 * std::f32::consts::TAU;
+        cap_center + (u * angle.cos() + v * angle.sin()) * radius
+    })
+}
+
+// 点是否在圆柱体内部，半径留少量容差，使半径相同、正对叠放的圆柱体边缘点也算在内
+fn cylinder_contains(cylinder: &Cylinder, point: Vector3<f32>) -> bool {
+    let axis = cylinder.axis.normalize();
+    let offset = point - cylinder.center;
+    let along = offset.dot(axis);
+    let radial = (offset - axis * along).magnitude();
+    along.abs() <= cylinder.height / 2.0 && radial <= cylinder.radius * 1.01
 }
 
 /// 辅助函数：处理平行轴情况下的圆柱体碰撞
+///
+/// 侧面接触与端面接触都可能发生，取穿透深度较小的方向作为分离方向。
 fn collide_cylinder_cylinder_parallel(cyl1: &Cylinder, cyl2: &Cylinder) -> Option<CollisionInfo> {
     let axis1 = cyl1.axis.normalize();
     let center_diff = cyl2.center - cyl1.center;
 
-    // 计算两轴线之间的距离
-    let dist_from_axis = (center_diff - axis1 * center_diff.dot(axis1)).magnitude();
-    let max_radius = cyl1.radius + cyl2.radius;
-
-    if dist_from_axis <= max_radius {
-        // 计算沿轴向的重叠
-        let proj = center_diff.dot(axis1);
-        let half_height1 = cyl1.height / 2.0;
-        let half_height2 = cyl2.height / 2.0;
-
-        // 检查高度方向是否有重叠
-        let h1_start = -half_height1;
-        let h1_end = half_height1;
-        let h2_start = proj - half_height2;
-        let h2_end = proj + half_height2;
-
-        let overlap_start = h1_start.max(h2_start);
-        let overlap_end = h1_end.min(h2_end);
-
-        if overlap_start <= overlap_end {
-            // 有重叠
-            let contact_point = cyl1.center
-                + axis1 * (overlap_start + overlap_end) / 2.0
-                + (axis1.cross(Vector3::unit_x())).normalize() * (max_radius - dist_from_axis)
-                    / 2.0;
-
-            let normal = if dist_from_axis > 0.0 {
-                (cyl2.center - cyl1.center - axis1 * proj).normalize()
-            } else {
-                // 如果轴线重合，任选垂直方向
-                axis1.cross(Vector3::unit_x()).normalize()
-            };
+    // 沿轴向与径向分解两中心的偏移
+    let proj = center_diff.dot(axis1);
+    let radial = center_diff - axis1 * proj;
+    let dist_from_axis = radial.magnitude();
+
+    let radial_penetration = cyl1.radius + cyl2.radius - dist_from_axis;
+    let axial_penetration = (cyl1.height + cyl2.height) / 2.0 - proj.abs();
+    if radial_penetration < 0.0 || axial_penetration < 0.0 {
+        return None;
+    }
+
+    if axial_penetration < radial_penetration {
+        // 端面接触：接触点取两端面之间、两轴连线的中点
+        let side = if proj >= 0.0 { 1.0 } else { -1.0 };
+        let contact_point = cyl1.center
+            + axis1 * (side * (cyl1.height / 2.0 - axial_penetration / 2.0))
+            + radial / 2.0;
+
+        return Some(CollisionInfo {
+            contact_point,
+            penetration_depth: axial_penetration,
+            normal: -axis1 * side,
+            feature: 1,
+        });
+    }
+
+    // 侧面接触：接触点取轴向重叠区间中点处，两侧面之间
+    let overlap_start = (-cyl1.height / 2.0).max(proj - cyl2.height / 2.0);
+    let overlap_end = (cyl1.height / 2.0).min(proj + cyl2.height / 2.0);
+    let radial_dir = if dist_from_axis > 1e-6 {
+        radial / dist_from_axis
+    } else {
+        // 如果轴线重合，任选垂直方向
+        any_perpendicular(axis1)
+    };
+    let contact_point = cyl1.center
+        + axis1 * ((overlap_start + overlap_end) / 2.0)
+        + radial_dir * (cyl1.radius - radial_penetration / 2.0);
+
+    Some(CollisionInfo {
+        contact_point,
+        penetration_depth: radial_penetration,
+        normal: -radial_dir,
+        feature: 2,
+    })
+}
 
-            return Some(CollisionInfo {
-                contact_point,
-                penetration_depth: max_radius - dist_from_axis,
+/// 任取一个与给定单位向量垂直的单位向量
+pub fn any_perpendicular(v: Vector3<f32>) -> Vector3<f32> {
+    let helper = if v.x.abs() < 0.9 {
+        Vector3::unit_x()
+    } else {
+        Vector3::unit_y()
+    };
+    v.cross(helper).normalize()
+}
+
+/// 检测圆柱体与平面之间的碰撞
+///
+/// 平面视为半空间，法线一侧为外部。返回的法线即平面法线（指向圆柱体）。
+/// 接触点取两个端面边缘上位于平面下方的采样点，以及最深入平面的点；
+/// 端面贴合平面时得到多个支撑点，圆柱体才能平稳立住而不是绕单个接触点来回摇摆。
+pub fn collide_cylinder_plane(cylinder: &Cylinder, plane: &Plane) -> Option<ContactManifold> {
+    let axis = cylinder.axis.normalize();
+    let normal = plane.normal.normalize();
+    let half_height = cylinder.height / 2.0;
+
+    // 沿 -normal 方向的支撑点，即圆柱体上最深入平面的点
+    let axis_dot = axis.dot(normal);
+    let cap_offset = if axis_dot.abs() < 1e-6 {
+        // 轴与平面平行，最深点位于侧面母线上，取其中点
+        Vector3::zero()
+    } else {
+        -axis * axis_dot.signum() * half_height
+    };
+    let radial = -normal + axis * axis_dot;
+    let radial_offset = if radial.magnitude2() > 1e-12 {
+        radial.normalize() * cylinder.radius
+    } else {
+        // 轴与法线平行，端面整体贴合平面，取端面中心
+        Vector3::zero()
+    };
+    let deepest_point = cylinder.center + cap_offset + radial_offset;
+
+    // 两个端面边缘的采样点，特征编号依次排列，最深点排在最后
+    let rim = cap_rim(cylinder, axis).chain(cap_rim(cylinder, -axis));
+
+    let mut points = rim
+        .chain([deepest_point])
+        .enumerate()
+        .filter_map(|(index, point)| {
+            let dist = point.dot(normal) - plane.distance;
+            (dist <= 0.0).then(|| CollisionInfo {
+                contact_point: point,
+                penetration_depth: -dist,
                 normal,
+                feature: index as u32,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if points.len() > MAX_PLANE_CONTACTS {
+        points = reduce_contacts(&points, normal);
+    }
+
+    ContactManifold { points }.into_option()
+}
+
+/// 检测两个球体之间的碰撞
+pub fn collide_sphere_sphere(sphere1: &Sphere, sphere2: &Sphere) -> Option<CollisionInfo> {
+    let offset = sphere1.center - sphere2.center;
+    let dist = offset.magnitude();
+    let penetration = sphere1.radius + sphere2.radius - dist;
+    if penetration < 0.0 {
+        return None;
+    }
+
+    // 法向量从第二个球指向第一个
+    let normal = if dist > 1e-6 {
+        offset / dist
+    } else {
+        // 球心重合，任选向上方向
+        Vector3::unit_y()
+    };
+
+    Some(CollisionInfo {
+        // 接触点取两球表面之间的中点
+        contact_point: sphere1.center - normal * (sphere1.radius - penetration / 2.0),
+        penetration_depth: penetration,
+        normal,
+        feature: 0,
+    })
+}
+
+/// 检测球体与平面之间的碰撞
+///
+/// 平面视为半空间，返回的法线即平面法线（指向球体）。
+pub fn collide_sphere_plane(sphere: &Sphere, plane: &Plane) -> Option<CollisionInfo> {
+    let normal = plane.normal.normalize();
+    let dist = sphere.center.dot(normal) - plane.distance - sphere.radius;
+    if dist > 0.0 {
+        return None;
+    }
+
+    Some(CollisionInfo {
+        contact_point: sphere.center - normal * sphere.radius,
+        penetration_depth: -dist,
+        normal,
+        feature: 0,
+    })
+}
+
+/// 检测球体与圆柱体之间的碰撞
+///
+/// 求圆柱体上离球心最近的点；球心在圆柱体内部时，沿穿透最浅的方向（侧面或端面）推出。
+/// 返回的法线指向球体。
+pub fn collide_sphere_cylinder(sphere: &Sphere, cylinder: &Cylinder) -> Option<CollisionInfo> {
+    let axis = cylinder.axis.normalize();
+    let half_height = cylinder.height / 2.0;
+
+    // 沿轴向与径向分解球心相对圆柱中心的偏移
+    let offset = sphere.center - cylinder.center;
+    let proj = offset.dot(axis);
+    let radial = offset - axis * proj;
+    let dist_from_axis = radial.magnitude();
+    let radial_dir = if dist_from_axis > 1e-6 {
+        radial / dist_from_axis
+    } else {
+        any_perpendicular(axis)
+    };
+
+    if proj.abs() <= half_height && dist_from_axis <= cylinder.radius {
+        // 球心在圆柱体内部
+        let side_depth = cylinder.radius - dist_from_axis;
+        let cap_depth = half_height - proj.abs();
+        let side = if proj >= 0.0 { 1.0 } else { -1.0 };
+        let (normal, depth, feature) = if cap_depth < side_depth {
+            (axis * side, cap_depth, 1)
+        } else {
+            (radial_dir, side_depth, 2)
+        };
+
+        return Some(CollisionInfo {
+            contact_point: sphere.center + normal * depth,
+            penetration_depth: depth + sphere.radius,
+            normal,
+            feature,
+        });
+    }
+
+    // 圆柱体上离球心最近的点
+    let closest = cylinder.center
+        + axis * proj.clamp(-half_height, half_height)
+        + radial_dir * dist_from_axis.min(cylinder.radius);
+    let diff = sphere.center - closest;
+    let dist = diff.magnitude();
+    if dist > sphere.radius {
+        return None;
+    }
+
+    // 最近点在端面上为端面接触，否则为侧面或边缘接触
+    let feature = if dist_from_axis < cylinder.radius {
+        1
+    } else if proj.abs() <= half_height {
+        2
+    } else {
+        3
+    };
+
+    Some(CollisionInfo {
+        contact_point: closest,
+        penetration_depth: sphere.radius - dist,
+        normal: diff / dist.max(1e-6),
+        feature,
+    })
+}
+
+/// 世界空间中的胶囊体：线段 start-end 上所有点半径 radius 范围内的集合
+#[derive(Clone, Copy)]
+pub struct Capsule {
+    pub start: Vector3<f32>,
+    pub end: Vector3<f32>,
+    pub radius: f32,
+}
+
+impl Capsule {
+    /// 由胶囊体形状与物体变换构造，局部轴为 Y 轴
+    pub fn new(radius: f32, half_height: f32, transform: &Transform) -> Self {
+        let half_axis = transform.transform_vector(Vector3::new(0.0, half_height, 0.0));
+        Self {
+            start: transform.translation - half_axis,
+            end: transform.translation + half_axis,
+            radius: radius * transform.scale.x, // 简化处理，假设各向同性缩放
+        }
+    }
+
+    /// 线段上某一点处的球
+    fn sphere_at(&self, center: Vector3<f32>) -> Sphere {
+        Sphere {
+            center,
+            radius: self.radius,
+        }
+    }
+}
+
+/// 线段 a-b 上离点 p 最近的点
+pub fn closest_point_on_segment(p: Vector3<f32>, a: Vector3<f32>, b: Vector3<f32>) -> Vector3<f32> {
+    let ab = b - a;
+    let length2 = ab.magnitude2();
+    if length2 < 1e-12 {
+        return a;
+    }
+    a + ab * ((p - a).dot(ab) / length2).clamp(0.0, 1.0)
+}
+
+/// 两线段 p1-q1 与 p2-q2 之间的最近点对（参数限制在线段范围内）
+pub fn closest_points_segment_segment(
+    p1: Vector3<f32>,
+    q1: Vector3<f32>,
+    p2: Vector3<f32>,
+    q2: Vector3<f32>,
+) -> (Vector3<f32>, Vector3<f32>) {
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+    let a = d1.magnitude2();
+    let e = d2.magnitude2();
+    let f = d2.dot(r);
+
+    // 退化为点的情况
+    if a < 1e-12 && e < 1e-12 {
+        return (p1, p2);
+    }
+    if a < 1e-12 {
+        return (p1, p2 + d2 * (f / e).clamp(0.0, 1.0));
+    }
+    let c = d1.dot(r);
+    if e < 1e-12 {
+        return (p1 + d1 * (-c / a).clamp(0.0, 1.0), p2);
+    }
+
+    let b = d1.dot(d2);
+    let denom = a * e - b * b;
+    // 平行时任取 s = 0，再由 t 反求
+    let mut s = if denom > 1e-12 {
+        ((b * f - c * e) / denom).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let mut t = (b * s + f) / e;
+    if t < 0.0 {
+        t = 0.0;
+        s = (-c / a).clamp(0.0, 1.0);
+    } else if t > 1.0 {
+        t = 1.0;
+        s = ((b - c) / a).clamp(0.0, 1.0);
+    }
+
+    (p1 + d1 * s, p2 + d2 * t)
+}
+
+/// 检测两个胶囊体之间的碰撞，返回的法线指向第一个胶囊体
+///
+/// 一般情况下取两线段最近点处的两个球检测；两线段近似平行时（如并排躺倒），
+/// 取重叠区间两端各一个接触点，避免单点支撑导致的来回滚动。
+pub fn collide_capsule_capsule(capsule1: &Capsule, capsule2: &Capsule) -> Option<ContactManifold> {
+    let d1 = capsule1.end - capsule1.start;
+    let d2 = capsule2.end - capsule2.start;
+    let length2 = d1.magnitude2();
+    let parallel = d1.cross(d2).magnitude2() < 1e-6 * length2 * d2.magnitude2();
+
+    if parallel && length2 > 1e-12 {
+        // 第二条线段两端在第一条线段上的投影参数
+        let project = |p: Vector3<f32>| ((p - capsule1.start).dot(d1) / length2).clamp(0.0, 1.0);
+        let t0 = project(capsule2.start);
+        let t1 = project(capsule2.end);
+        if (t1 - t0).abs() * length2.sqrt() > 1e-3 {
+            let points = [t0, t1]
+                .iter()
+                .enumerate()
+                .filter_map(|(index, &t)| {
+                    let point1 = capsule1.start + d1 * t;
+                    let point2 = closest_point_on_segment(point1, capsule2.start, capsule2.end);
+                    collide_sphere_sphere(&capsule1.sphere_at(point1), &capsule2.sphere_at(point2))
+                        .map(|info| CollisionInfo {
+                            feature: index as u32,
+                            ..info
+                        })
+                })
+                .collect();
+            return ContactManifold { points }.into_option();
+        }
+    }
+
+    let (point1, point2) =
+        closest_points_segment_segment(capsule1.start, capsule1.end, capsule2.start, capsule2.end);
+    collide_sphere_sphere(&capsule1.sphere_at(point1), &capsule2.sphere_at(point2))
+        .map(|info| CollisionInfo { feature: 2, ..info }.into())
+}
+
+/// 检测胶囊体与平面之间的碰撞：线段两端的球分别与平面检测
+///
+/// 返回的法线即平面法线（指向胶囊体）。
+pub fn collide_capsule_plane(capsule: &Capsule, plane: &Plane) -> Option<ContactManifold> {
+    let points = [capsule.start, capsule.end]
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &center)| {
+            collide_sphere_plane(&capsule.sphere_at(center), plane).map(|info| CollisionInfo {
+                feature: index as u32,
+                ..info
+            })
+        })
+        .collect();
+
+    ContactManifold { points }.into_option()
+}
+
+/// 检测胶囊体与球体之间的碰撞，返回的法线指向胶囊体
+pub fn collide_capsule_sphere(capsule: &Capsule, sphere: &Sphere) -> Option<CollisionInfo> {
+    let center = closest_point_on_segment(sphere.center, capsule.start, capsule.end);
+    collide_sphere_sphere(&capsule.sphere_at(center), sphere)
+}
+
+// 求线段到长方体最近点时交替投影的迭代次数
+const SEGMENT_BOX_ITERATIONS: usize = 8;
+
+/// 检测胶囊体与长方体之间的碰撞，返回的法线指向胶囊体
+///
+/// 线段两端的球分别与盒子检测，以支撑平躺在盒面上的胶囊体；
+/// 再用交替投影求出线段上离盒子最近的点，补充跨过盒子棱边时的接触。
+pub fn collide_capsule_box(capsule: &Capsule, obb: &Obb) -> Option<ContactManifold> {
+    let mut points = [capsule.start, capsule.end]
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &center)| {
+            collide_sphere_box(&capsule.sphere_at(center), obb).map(|info| CollisionInfo {
+                feature: index as u32,
+                ..info
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // 线段与盒子均为凸集，交替投影收敛到最近点对
+    let mut on_segment = (capsule.start + capsule.end) / 2.0;
+    for _ in 0..SEGMENT_BOX_ITERATIONS {
+        let local = obb.point_to_local(on_segment);
+        let clamped = Vector3::new(
+            local.x.clamp(-obb.half_extents.x, obb.half_extents.x),
+            local.y.clamp(-obb.half_extents.y, obb.half_extents.y),
+            local.z.clamp(-obb.half_extents.z, obb.half_extents.z),
+        );
+        on_segment =
+            closest_point_on_segment(obb.local_to_world(clamped), capsule.start, capsule.end);
+    }
+
+    // 只有比两端更深时才补充，平躺时中点与两端等深，无需重复
+    let end_depth = points
+        .iter()
+        .map(|info| info.penetration_depth)
+        .fold(0.0, f32::max);
+    if let Some(info) = collide_sphere_box(&capsule.sphere_at(on_segment), obb) {
+        if info.penetration_depth > end_depth + 1e-3 {
+            points.push(CollisionInfo { feature: 2, ..info });
+        }
+    }
+
+    ContactManifold { points }.into_option()
+}
+
+/// 分离轴测试中穿透最浅的轴
+#[derive(Clone, Copy)]
+struct SatAxis {
+    axis: Vector3<f32>, // 已单位化，从第一个物体指向第二个物体
+    overlap: f32,
+    kind: SatAxisKind,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SatAxisKind {
+    FaceA(usize),
+    FaceB(usize),
+    Edge(usize, usize),
+}
+
+// 面轴优先于边轴：边轴的穿透需明显更浅才会被采用，避免平放时在两类轴之间抖动
+const SAT_RELATIVE_TOLERANCE: f32 = 0.95;
+const SAT_ABSOLUTE_TOLERANCE: f32 = 0.001;
+
+/// 检测两个有向长方体之间的碰撞（分离轴定理）
+///
+/// 面接触时将入射面裁剪到参考面内，得到最多八个接触点；边与边接触时返回单个接触点。
+pub fn collide_box_box(box1: &Obb, box2: &Obb) -> Option<ContactManifold> {
+    let offset = box2.center - box1.center;
+    let mut best: Option<SatAxis> = None;
+
+    let mut test_axis = |axis: Vector3<f32>, kind: SatAxisKind| -> bool {
+        let length2 = axis.magnitude2();
+        if length2 < 1e-6 {
+            // 两条边近似平行，叉积轴无意义
+            return true;
+        }
+        let mut axis = axis / length2.sqrt();
+        if axis.dot(offset) < 0.0 {
+            axis = -axis;
+        }
+
+        let overlap = box1.project_radius(axis) + box2.project_radius(axis) - offset.dot(axis);
+        if overlap < 0.0 {
+            return false;
+        }
+
+        let better = match best {
+            None => true,
+            Some(current) if matches!(kind, SatAxisKind::FaceA(_)) => overlap < current.overlap,
+            Some(current) => {
+                overlap < current.overlap * SAT_RELATIVE_TOLERANCE - SAT_ABSOLUTE_TOLERANCE
+            }
+        };
+        if better {
+            best = Some(SatAxis {
+                axis,
+                overlap,
+                kind,
             });
         }
+        true
+    };
+
+    for i in 0..3 {
+        if !test_axis(box1.axes[i], SatAxisKind::FaceA(i)) {
+            return None;
+        }
+    }
+    for j in 0..3 {
+        if !test_axis(box2.axes[j], SatAxisKind::FaceB(j)) {
+            return None;
+        }
+    }
+    for i in 0..3 {
+        for j in 0..3 {
+            if !test_axis(box1.axes[i].cross(box2.axes[j]), SatAxisKind::Edge(i, j)) {
+                return None;
+            }
+        }
     }
 
-    None
+    let best = best?;
+    match best.kind {
+        SatAxisKind::FaceA(i) => box_face_contacts(box1, box2, i, best.axis, 0),
+        SatAxisKind::FaceB(j) => {
+            box_face_contacts(box2, box1, j, -best.axis, 3).map(ContactManifold::flipped)
+        }
+        SatAxisKind::Edge(i, j) => {
+            box_edge_contact(box1, box2, i, j, best.axis, best.overlap).map(ContactManifold::from)
+        }
+    }
 }
 
-/// 检测圆柱体与平面之间的碰撞
-pub fn collide_cylinder_plane(cylinder: &Cylinder, plane: &Plane) -> Option<CollisionInfo> {
-    // 标准化圆柱体轴向量
-    let axis_normalized = cylinder.axis.normalize();
-
-    // 计算圆柱体中心点到平面的距离
-    let dist_to_center = cylinder.center.dot(plane.normal) - plane.distance;
-
-    // 计算圆柱体轴线在平面法线方向上的投影
-    let axis_projection = axis_normalized.dot(plane.normal).abs();
-    let half_height_proj = (cylinder.height / 2.0) * axis_projection;
-
-    // 计算最大可能距离（考虑半径）
-    let max_dist = half_height_proj + cylinder.radius;
-
-    // 检查是否碰撞
-    if dist_to_center.abs() <= max_dist {
-        // 确定碰撞类型
-        if half_height_proj >= dist_to_center.abs() {
-            // 侧面碰撞：圆柱体侧面与平面相交
-            // 计算圆柱体轴线到平面的最近点
-            let t = (plane.distance - cylinder.center.dot(plane.normal))
-                / axis_normalized.dot(plane.normal);
-            let closest_point_on_axis = cylinder.center + axis_normalized * t;
-
-            // 计算圆柱体表面上距离平面最近的点
-            let radial_direction =
-                (plane.normal - axis_normalized * plane.normal.dot(axis_normalized)).normalize();
-            let surface_point = closest_point_on_axis + radial_direction * cylinder.radius;
-
-            // 计算穿透深度
-            let dist_to_surface = surface_point.dot(plane.normal) - plane.distance;
-            let penetration_depth = cylinder.radius - dist_to_surface.abs();
-
-            let contact_point = surface_point - plane.normal * dist_to_surface;
-            let normal = if dist_to_surface > 0.0 {
-                plane.normal
+/// 面接触：以参考盒子的面为基准，裁剪入射盒子上最反向的面
+///
+/// `normal` 为参考面的外法线（指向入射盒子），返回的法线指向参考盒子。
+fn box_face_contacts(
+    reference: &Obb,
+    incident: &Obb,
+    reference_axis: usize,
+    normal: Vector3<f32>,
+    feature_base: u32,
+) -> Option<ContactManifold> {
+    // 参考面：中心点与两条切向轴
+    let face_center = reference.center + normal * reference.half_extents[reference_axis];
+    let u_axis = (reference_axis + 1) % 3;
+    let v_axis = (reference_axis + 2) % 3;
+
+    // 入射面：入射盒子上法线与参考面法线最反向的面
+    let incident_axis = (0..3)
+        .max_by(|&a, &b| {
+            let da = incident.axes[a].dot(normal).abs();
+            let db = incident.axes[b].dot(normal).abs();
+            da.total_cmp(&db)
+        })
+        .unwrap();
+    let incident_sign = -incident.axes[incident_axis].dot(normal).signum();
+    let incident_normal = incident.axes[incident_axis] * incident_sign;
+    let incident_center = incident.center + incident_normal * incident.half_extents[incident_axis];
+    let a = (incident_axis + 1) % 3;
+    let b = (incident_axis + 2) % 3;
+    let ea = incident.axes[a] * incident.half_extents[a];
+    let eb = incident.axes[b] * incident.half_extents[b];
+    let mut polygon = vec![
+        incident_center + ea + eb,
+        incident_center - ea + eb,
+        incident_center - ea - eb,
+        incident_center + ea - eb,
+    ];
+
+    // 依次用参考面的四条侧边平面裁剪入射面
+    for axis in [u_axis, v_axis] {
+        let direction = reference.axes[axis];
+        let limit = reference.half_extents[axis];
+        for sign in [1.0, -1.0] {
+            polygon = clip_polygon(
+                &polygon,
+                direction * sign,
+                limit + direction.dot(face_center) * sign,
+            );
+            if polygon.is_empty() {
+                return None;
+            }
+        }
+    }
+
+    // 保留位于参考面下方的点
+    let points = polygon
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &point)| {
+            let separation = (point - face_center).dot(normal);
+            (separation <= 0.0).then(|| CollisionInfo {
+                // 接触点取入射点与参考面之间的中点
+                contact_point: point - normal * (separation / 2.0),
+                penetration_depth: -separation,
+                normal: -normal,
+                feature: ((feature_base + reference_axis as u32) << 4) | index as u32,
+            })
+        })
+        .collect();
+
+    ContactManifold { points }.into_option()
+}
+
+/// Sutherland-Hodgman：保留满足 normal·p <= offset 的部分
+fn clip_polygon(polygon: &[Vector3<f32>], normal: Vector3<f32>, offset: f32) -> Vec<Vector3<f32>> {
+    let mut output = Vec::with_capacity(polygon.len() + 4);
+    for (i, &current) in polygon.iter().enumerate() {
+        let next = polygon[(i + 1) % polygon.len()];
+        let d_current = normal.dot(current) - offset;
+        let d_next = normal.dot(next) - offset;
+
+        if d_current <= 0.0 {
+            output.push(current);
+        }
+        if (d_current <= 0.0) != (d_next <= 0.0) {
+            let t = d_current / (d_current - d_next);
+            output.push(current + (next - current) * t);
+        }
+    }
+    output
+}
+
+/// 边与边接触：取两条支撑边之间的最近点对中点作为接触点
+fn box_edge_contact(
+    box1: &Obb,
+    box2: &Obb,
+    edge1: usize,
+    edge2: usize,
+    axis: Vector3<f32>,
+    overlap: f32,
+) -> Option<CollisionInfo> {
+    // 支撑边上的一点：除边方向外，其余两轴取朝向对方的一侧
+    let edge_point = |obb: &Obb, edge: usize, direction: Vector3<f32>| {
+        let mut point = obb.center;
+        for k in (0..3).filter(|&k| k != edge) {
+            let sign = if obb.axes[k].dot(direction) >= 0.0 {
+                1.0
             } else {
-                -plane.normal
+                -1.0
             };
+            point += obb.axes[k] * (sign * obb.half_extents[k]);
+        }
+        point
+    };
+    let p1 = edge_point(box1, edge1, axis);
+    let p2 = edge_point(box2, edge2, -axis);
+    let d1 = box1.axes[edge1];
+    let d2 = box2.axes[edge2];
+
+    // 两直线最近点
+    let r = p1 - p2;
+    let b = d1.dot(d2);
+    let c = d1.dot(r);
+    let f = d2.dot(r);
+    let denom = 1.0 - b * b;
+    if denom < 1e-6 {
+        return None;
+    }
+    let s = ((b * f - c) / denom).clamp(-box1.half_extents[edge1], box1.half_extents[edge1]);
+    let t = (f + b * s).clamp(-box2.half_extents[edge2], box2.half_extents[edge2]);
+
+    let closest1 = p1 + d1 * s;
+    let closest2 = p2 + d2 * t;
 
-            return Some(CollisionInfo {
-                contact_point,
-                penetration_depth: penetration_depth.abs(),
+    Some(CollisionInfo {
+        contact_point: (closest1 + closest2) / 2.0,
+        penetration_depth: overlap,
+        normal: -axis,
+        feature: ((6 + edge1 * 3 + edge2) as u32) << 4,
+    })
+}
+
+/// 检测长方体与平面之间的碰撞：所有位于平面下方的顶点都作为接触点
+///
+/// 返回的法线即平面法线（指向长方体），顶点索引作为特征编号。
+pub fn collide_box_plane(obb: &Obb, plane: &Plane) -> Option<ContactManifold> {
+    let normal = plane.normal.normalize();
+    let points = obb
+        .vertices()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &vertex)| {
+            let dist = vertex.dot(normal) - plane.distance;
+            (dist <= 0.0).then(|| CollisionInfo {
+                contact_point: vertex,
+                penetration_depth: -dist,
                 normal,
-            });
-        } else {
-            // 顶部或底部碰撞
-            // 计算圆柱体顶部和底部中心到平面的距离
-            let top_center = cylinder.center + axis_normalized * (cylinder.height / 2.0);
-            let bottom_center = cylinder.center - axis_normalized * (cylinder.height / 2.0);
-
-            let dist_to_top = top_center.dot(plane.normal) - plane.distance;
-            let dist_to_bottom = bottom_center.dot(plane.normal) - plane.distance;
-
-            // 检查顶部是否碰撞
-            if dist_to_top.abs() <= cylinder.radius {
-                // 顶部碰撞
-                let contact_point = top_center - plane.normal * dist_to_top;
-
-                let normal = if dist_to_top > 0.0 {
-                    plane.normal
-                } else {
-                    -plane.normal
-                };
-
-                return Some(CollisionInfo {
-                    contact_point,
-                    penetration_depth: cylinder.radius - dist_to_top.abs(),
-                    normal,
-                });
+                feature: index as u32,
+            })
+        })
+        .collect();
+
+    ContactManifold { points }.into_option()
+}
+
+/// 检测球体与长方体之间的碰撞，返回的法线指向球体
+pub fn collide_sphere_box(sphere: &Sphere, obb: &Obb) -> Option<CollisionInfo> {
+    let local = obb.point_to_local(sphere.center);
+    let clamped = Vector3::new(
+        local.x.clamp(-obb.half_extents.x, obb.half_extents.x),
+        local.y.clamp(-obb.half_extents.y, obb.half_extents.y),
+        local.z.clamp(-obb.half_extents.z, obb.half_extents.z),
+    );
+
+    if clamped != local {
+        // 球心在盒子外部：最近点在盒子表面上
+        let closest = obb.local_to_world(clamped);
+        let diff = sphere.center - closest;
+        let dist = diff.magnitude();
+        if dist > sphere.radius {
+            return None;
+        }
+
+        return Some(CollisionInfo {
+            contact_point: closest,
+            penetration_depth: sphere.radius - dist,
+            normal: diff / dist.max(1e-6),
+            feature: 0,
+        });
+    }
+
+    // 球心在盒子内部：沿距离最近的面推出
+    let axis = (0..3)
+        .min_by(|&a, &b| {
+            let da = obb.half_extents[a] - local[a].abs();
+            let db = obb.half_extents[b] - local[b].abs();
+            da.total_cmp(&db)
+        })
+        .unwrap();
+    let face_gap = obb.half_extents[axis] - local[axis].abs();
+    let sign = if local[axis] >= 0.0 { 1.0 } else { -1.0 };
+    let normal = obb.axes[axis] * sign;
+
+    Some(CollisionInfo {
+        contact_point: sphere.center + normal * face_gap,
+        penetration_depth: face_gap + sphere.radius,
+        normal,
+        feature: 1,
+    })
+}
+
+// 圆柱体端面边缘的采样点数，用于生成长方体与圆柱体之间的接触点
+pub(crate) const CYLINDER_RIM_SAMPLES: usize = 8;
+
+/// 检测长方体与圆柱体之间的碰撞，返回的法线指向长方体
+///
+/// 圆柱体没有有限的分离轴集合，这里测试盒子三个面轴、圆柱轴、盒子各轴与圆柱轴的叉积，
+/// 以及两者中心连线的径向分量，覆盖常见的面、端面与侧面接触。
+/// 接触点取落入对方内部的盒子顶点与圆柱端面边缘采样点。
+pub fn collide_box_cylinder(obb: &Obb, cylinder: &Cylinder) -> Option<ContactManifold> {
+    let cyl_axis = cylinder.axis.normalize();
+    let half_height = cylinder.height / 2.0;
+    let offset = obb.center - cylinder.center;
+
+    // 圆柱体在给定方向上的投影半径
+    let cylinder_radius_along = |direction: Vector3<f32>| {
+        let cos = cyl_axis.dot(direction);
+        cos.abs() * half_height + cylinder.radius * (1.0 - cos * cos).max(0.0).sqrt()
+    };
+
+    let mut candidates = vec![obb.axes[0], obb.axes[1], obb.axes[2], cyl_axis];
+    candidates.extend(obb.axes.iter().map(|axis| axis.cross(cyl_axis)));
+    candidates.push(offset - cyl_axis * offset.dot(cyl_axis));
+
+    // 轴方向从圆柱体指向盒子
+    let mut best: Option<(Vector3<f32>, f32)> = None;
+    for (index, candidate) in candidates.into_iter().enumerate() {
+        let length2 = candidate.magnitude2();
+        if length2 < 1e-6 {
+            continue;
+        }
+        let mut axis = candidate / length2.sqrt();
+        if axis.dot(offset) < 0.0 {
+            axis = -axis;
+        }
+
+        let overlap = obb.project_radius(axis) + cylinder_radius_along(axis) - offset.dot(axis);
+        if overlap < 0.0 {
+            return None;
+        }
+
+        // 面轴与圆柱轴（前四个）优先
+        let better = match best {
+            None => true,
+            Some((_, current)) if index < 4 => overlap < current,
+            Some((_, current)) => {
+                overlap < current * SAT_RELATIVE_TOLERANCE - SAT_ABSOLUTE_TOLERANCE
             }
-            // 检查底部是否碰撞
-            else if dist_to_bottom.abs() <= cylinder.radius {
-                // 底部碰撞
-                let contact_point = bottom_center - plane.normal * dist_to_bottom;
-
-                let normal = if dist_to_bottom > 0.0 {
-                    plane.normal
-                } else {
-                    -plane.normal
-                };
-
-                return Some(CollisionInfo {
-                    contact_point,
-                    penetration_depth: cylinder.radius - dist_to_bottom.abs(),
+        };
+        if better {
+            best = Some((axis, overlap));
+        }
+    }
+    let (normal, overlap) = best?;
+
+    // 沿法线方向，圆柱体的最远处与盒子的最近处
+    let cylinder_max = cylinder.center.dot(normal) + cylinder_radius_along(normal);
+    let box_min = obb.center.dot(normal) - obb.project_radius(normal);
+
+    let mut points = Vec::new();
+
+    // 落入圆柱体内部的盒子顶点
+    for (index, vertex) in obb.vertices().into_iter().enumerate() {
+        let rel = vertex - cylinder.center;
+        let h = rel.dot(cyl_axis);
+        let radial = (rel - cyl_axis * h).magnitude();
+        if h.abs() <= half_height && radial <= cylinder.radius {
+            let depth = cylinder_max - vertex.dot(normal);
+            if depth >= 0.0 {
+                points.push(CollisionInfo {
+                    contact_point: vertex,
+                    penetration_depth: depth,
                     normal,
+                    feature: index as u32,
                 });
             }
         }
     }
 
-    None
+    // 落入盒子内部的圆柱端面边缘点：均匀采样，外加沿 -normal 最深的边缘点
+    let u = any_perpendicular(cyl_axis);
+    let v = cyl_axis.cross(u);
+    let radial_normal = normal - cyl_axis * normal.dot(cyl_axis);
+    let deepest_dir = (radial_normal.magnitude2() > 1e-6).then(|| -radial_normal.normalize());
+    for (cap, sign) in [1.0f32, -1.0].into_iter().enumerate() {
+        let cap_center = cylinder.center + cyl_axis * (sign * half_height);
+        let directions = (0..CYLINDER_RIM_SAMPLES)
+            .map(|k| {
+                let angle = k as f32 / CYLINDER_RIM_SAMPLES as f32 * std::f32::consts::TAU;
+                u * angle.cos() + v * angle.sin()
+            })
+            .chain(deepest_dir);
+        for (k, direction) in directions.enumerate() {
+            let point = cap_center + direction * cylinder.radius;
+            if obb.contains(point) {
+                let depth = point.dot(normal) - box_min;
+                if depth >= 0.0 {
+                    points.push(CollisionInfo {
+                        contact_point: point,
+                        penetration_depth: depth,
+                        normal,
+                        feature: (8 + cap * (CYLINDER_RIM_SAMPLES + 1) + k) as u32,
+                    });
+                }
+            }
+        }
+    }
+
+    if points.is_empty() {
+        // 边与侧面交叉等情况：取两侧支撑点的中点作为单个接触点
+        let box_support = obb.support(-normal);
+        let cylinder_support = cylinder.center
+            + cyl_axis * (half_height * cyl_axis.dot(normal).signum())
+            + deepest_dir.map_or(Vector3::zero(), |d| -d * cylinder.radius);
+        points.push(CollisionInfo {
+            contact_point: (box_support + cylinder_support) / 2.0,
+            penetration_depth: overlap,
+            normal,
+            feature: 32,
+        });
+    }
+
+    Some(ContactManifold { points })
 }
 
-/// 检测两个物理体之间的碰撞
-pub fn collide_bodies(body1: &PhysicsBody, body2: &PhysicsBody) -> Option<CollisionInfo> {
+// 凸包与平面接触点过多时只保留沿切向最外侧的几个点
+const MAX_PLANE_CONTACTS: usize = 4;
+
+/// 检测凸包与平面之间的碰撞：位于平面下方的顶点作为接触点
+///
+/// 返回的法线即平面法线（指向凸包）。接触点多于四个时，保留沿两条切向的最外侧点，
+/// 足以稳定支撑且不会拖慢求解器。
+pub fn collide_hull_plane(
+    hull: &ConvexHull,
+    transform: &Transform,
+    plane: &Plane,
+) -> Option<ContactManifold> {
+    let normal = plane.normal.normalize();
+    let mut points = hull
+        .points
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &local)| {
+            let vertex = transform.transform_point(local);
+            let dist = vertex.dot(normal) - plane.distance;
+            (dist <= 0.0).then(|| CollisionInfo {
+                contact_point: vertex,
+                penetration_depth: -dist,
+                normal,
+                feature: index as u32,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if points.len() > MAX_PLANE_CONTACTS {
+        points = reduce_contacts(&points, normal);
+    }
+
+    ContactManifold { points }.into_option()
+}
+
+/// 共面接触点过多时，保留沿两条切向方向最外侧的点（最多四个）
+pub(crate) fn reduce_contacts(
+    points: &[CollisionInfo],
+    normal: Vector3<f32>,
+) -> Vec<CollisionInfo> {
+    let u = any_perpendicular(normal);
+    let v = normal.cross(u);
+    let mut kept: Vec<CollisionInfo> = Vec::with_capacity(MAX_PLANE_CONTACTS);
+    for direction in [u, -u, v, -v] {
+        let extreme = points
+            .iter()
+            .max_by(|a, b| {
+                let da = a.contact_point.dot(direction);
+                let db = b.contact_point.dot(direction);
+                da.total_cmp(&db)
+            })
+            .copied()
+            .unwrap();
+        if !kept.iter().any(|p| p.feature == extreme.feature) {
+            kept.push(extreme);
+        }
+    }
+    kept
+}
+
+/// 沿 -normal 方向的支撑点与平面的单点碰撞，用于没有专门处理的凸形状与平面
+fn collide_support_plane(shape: &dyn SupportMap, plane: &Plane) -> Option<CollisionInfo> {
+    let normal = plane.normal.normalize();
+    let deepest_point = shape.support(-normal);
+    let dist = deepest_point.dot(normal) - plane.distance;
+    if dist > 0.0 {
+        return None;
+    }
+
+    Some(CollisionInfo {
+        contact_point: deepest_point,
+        penetration_depth: -dist,
+        normal,
+        feature: 0,
+    })
+}
+
+/// 物理体形状的世界空间支撑映射，平面等非有界形状返回 None
+pub(crate) fn support_map<'a>(
+    shape: &'a Shape,
+    transform: &'a Transform,
+) -> Option<Box<dyn SupportMap + 'a>> {
+    match shape {
+        Shape::Cylinder(cylinder) => Some(Box::new(transform_cylinder(cylinder, transform))),
+        Shape::Sphere(sphere) => Some(Box::new(transform_sphere(sphere, transform))),
+        Shape::Box { half_extents } => Some(Box::new(Obb::new(*half_extents, transform))),
+        Shape::Capsule {
+            radius,
+            half_height,
+        } => Some(Box::new(Capsule::new(*radius, *half_height, transform))),
+        Shape::ConvexHull(hull) => Some(Box::new(TransformedHull { hull, transform })),
+        Shape::Plane(_) | Shape::TriMesh(_) | Shape::HeightField { .. } => None,
+    }
+}
+
+/// 没有专门检测函数的形状组合：凸体之间走 GJK/EPA，凸体与平面取支撑点
+fn collide_fallback(body1: &PhysicsBody, body2: &PhysicsBody) -> Option<ContactManifold> {
+    let t1 = &body1.transform;
+    let t2 = &body2.transform;
+
+    match (&body1.shape, &body2.shape) {
+        // 平面与平面之间不做检测：support_map 对平面返回 None
+        (_, Shape::Plane(plane)) => support_map(&body1.shape, t1)
+            .and_then(|shape| collide_support_plane(shape.as_ref(), &transform_plane(plane, t2))),
+        (Shape::Plane(plane), _) => support_map(&body2.shape, t2)
+            .and_then(|shape| collide_support_plane(shape.as_ref(), &transform_plane(plane, t1)))
+            .map(flip_normal),
+        _ => {
+            let shape1 = support_map(&body1.shape, t1)?;
+            let shape2 = support_map(&body2.shape, t2)?;
+            collide_convex(shape1.as_ref(), shape2.as_ref())
+        }
+    }
+    .map(Into::into)
+}
+
+/// 检测两个物理体之间的碰撞，返回的接触流形法线指向 body1
+pub fn collide_bodies(body1: &PhysicsBody, body2: &PhysicsBody) -> Option<ContactManifold> {
+    let t1 = &body1.transform;
+    let t2 = &body2.transform;
+
     match (&body1.shape, &body2.shape) {
         (Shape::Cylinder(cyl1), Shape::Cylinder(cyl2)) => {
             // 应用变换后检测碰撞
-            let transformed_cyl1 = transform_cylinder(cyl1, &body1.transform);
-            let transformed_cyl2 = transform_cylinder(cyl2, &body2.transform);
+            let transformed_cyl1 = transform_cylinder(cyl1, t1);
+            let transformed_cyl2 = transform_cylinder(cyl2, t2);
             collide_cylinder_cylinder(&transformed_cyl1, &transformed_cyl2)
         }
-        (Shape::Cylinder(cylinder), Shape::Plane(plane)) => collide_cylinder_plane(cylinder, plane),
-        (Shape::Plane(plane), Shape::Cylinder(cylinder)) => collide_cylinder_plane(cylinder, plane),
-        _ => {
-            // 暂时只支持圆柱体之间的碰撞
-            None
+        (Shape::Cylinder(cylinder), Shape::Plane(plane)) => collide_cylinder_plane(
+            &transform_cylinder(cylinder, t1),
+            &transform_plane(plane, t2),
+        ),
+        (Shape::Plane(plane), Shape::Cylinder(cylinder)) => collide_cylinder_plane(
+            &transform_cylinder(cylinder, t2),
+            &transform_plane(plane, t1),
+        )
+        .map(ContactManifold::flipped),
+        (Shape::Sphere(sphere1), Shape::Sphere(sphere2)) => collide_sphere_sphere(
+            &transform_sphere(sphere1, t1),
+            &transform_sphere(sphere2, t2),
+        )
+        .map(Into::into),
+        (Shape::Sphere(sphere), Shape::Plane(plane)) => {
+            collide_sphere_plane(&transform_sphere(sphere, t1), &transform_plane(plane, t2))
+                .map(Into::into)
+        }
+        (Shape::Plane(plane), Shape::Sphere(sphere)) => {
+            collide_sphere_plane(&transform_sphere(sphere, t2), &transform_plane(plane, t1))
+                .map(flip_normal)
+                .map(Into::into)
+        }
+        (Shape::Sphere(sphere), Shape::Cylinder(cylinder)) => collide_sphere_cylinder(
+            &transform_sphere(sphere, t1),
+            &transform_cylinder(cylinder, t2),
+        )
+        .map(Into::into),
+        (Shape::Cylinder(cylinder), Shape::Sphere(sphere)) => collide_sphere_cylinder(
+            &transform_sphere(sphere, t2),
+            &transform_cylinder(cylinder, t1),
+        )
+        .map(flip_normal)
+        .map(Into::into),
+        (Shape::Box { half_extents: he1 }, Shape::Box { half_extents: he2 }) => {
+            collide_box_box(&Obb::new(*he1, t1), &Obb::new(*he2, t2))
+        }
+        (Shape::Box { half_extents }, Shape::Plane(plane)) => {
+            collide_box_plane(&Obb::new(*half_extents, t1), &transform_plane(plane, t2))
+        }
+        (Shape::Plane(plane), Shape::Box { half_extents }) => {
+            collide_box_plane(&Obb::new(*half_extents, t2), &transform_plane(plane, t1))
+                .map(ContactManifold::flipped)
+        }
+        (Shape::Sphere(sphere), Shape::Box { half_extents }) => {
+            collide_sphere_box(&transform_sphere(sphere, t1), &Obb::new(*half_extents, t2))
+                .map(Into::into)
+        }
+        (Shape::Box { half_extents }, Shape::Sphere(sphere)) => {
+            collide_sphere_box(&transform_sphere(sphere, t2), &Obb::new(*half_extents, t1))
+                .map(flip_normal)
+                .map(Into::into)
+        }
+        (Shape::Box { half_extents }, Shape::Cylinder(cylinder)) => collide_box_cylinder(
+            &Obb::new(*half_extents, t1),
+            &transform_cylinder(cylinder, t2),
+        ),
+        (Shape::Cylinder(cylinder), Shape::Box { half_extents }) => collide_box_cylinder(
+            &Obb::new(*half_extents, t2),
+            &transform_cylinder(cylinder, t1),
+        )
+        .map(ContactManifold::flipped),
+        (
+            Shape::Capsule {
+                radius: r1,
+                half_height: h1,
+            },
+            Shape::Capsule {
+                radius: r2,
+                half_height: h2,
+            },
+        ) => collide_capsule_capsule(&Capsule::new(*r1, *h1, t1), &Capsule::new(*r2, *h2, t2)),
+        (
+            Shape::Capsule {
+                radius,
+                half_height,
+            },
+            Shape::Plane(plane),
+        ) => collide_capsule_plane(
+            &Capsule::new(*radius, *half_height, t1),
+            &transform_plane(plane, t2),
+        ),
+        (
+            Shape::Plane(plane),
+            Shape::Capsule {
+                radius,
+                half_height,
+            },
+        ) => collide_capsule_plane(
+            &Capsule::new(*radius, *half_height, t2),
+            &transform_plane(plane, t1),
+        )
+        .map(ContactManifold::flipped),
+        (
+            Shape::Capsule {
+                radius,
+                half_height,
+            },
+            Shape::Sphere(sphere),
+        ) => collide_capsule_sphere(
+            &Capsule::new(*radius, *half_height, t1),
+            &transform_sphere(sphere, t2),
+        )
+        .map(Into::into),
+        (
+            Shape::Sphere(sphere),
+            Shape::Capsule {
+                radius,
+                half_height,
+            },
+        ) => collide_capsule_sphere(
+            &Capsule::new(*radius, *half_height, t2),
+            &transform_sphere(sphere, t1),
+        )
+        .map(flip_normal)
+        .map(Into::into),
+        (
+            Shape::Capsule {
+                radius,
+                half_height,
+            },
+            Shape::Box { half_extents },
+        ) => collide_capsule_box(
+            &Capsule::new(*radius, *half_height, t1),
+            &Obb::new(*half_extents, t2),
+        ),
+        (
+            Shape::Box { half_extents },
+            Shape::Capsule {
+                radius,
+                half_height,
+            },
+        ) => collide_capsule_box(
+            &Capsule::new(*radius, *half_height, t2),
+            &Obb::new(*half_extents, t1),
+        )
+        .map(ContactManifold::flipped),
+        (Shape::ConvexHull(hull), Shape::Plane(plane)) => {
+            collide_hull_plane(hull, t1, &transform_plane(plane, t2))
+        }
+        (Shape::Plane(plane), Shape::ConvexHull(hull)) => {
+            collide_hull_plane(hull, t2, &transform_plane(plane, t1)).map(ContactManifold::flipped)
+        }
+        // 三角网格、高度场与平面之间（都是静态形状）不做检测
+        (
+            Shape::TriMesh(_) | Shape::HeightField { .. } | Shape::Plane(_),
+            Shape::TriMesh(_) | Shape::HeightField { .. } | Shape::Plane(_),
+        ) => None,
+        (_, Shape::TriMesh(mesh)) => collide_trimesh(&body1.shape, t1, mesh, t2),
+        (Shape::TriMesh(mesh), _) => {
+            collide_trimesh(&body2.shape, t2, mesh, t1).map(ContactManifold::flipped)
+        }
+        (_, Shape::HeightField { .. }) => {
+            let grid = HeightGrid::from_shape(&body2.shape)?;
+            collide_heightfield(&body1.shape, t1, &grid, t2)
+        }
+        (Shape::HeightField { .. }, _) => {
+            let grid = HeightGrid::from_shape(&body1.shape)?;
+            collide_heightfield(&body2.shape, t2, &grid, t1).map(ContactManifold::flipped)
         }
+        // 其余组合没有专门的检测函数，交给通用的凸体检测
+        _ => collide_fallback(body1, body2),
+    }
+}
+
+/// 交换两物体顺序后，法线需要反向以保持指向第一个物体
+fn flip_normal(info: CollisionInfo) -> CollisionInfo {
+    CollisionInfo {
+        normal: -info.normal,
+        ..info
+    }
+}
+
+/// 应用变换到平面（忽略缩放）
+pub(crate) fn transform_plane(plane: &Plane, transform: &Transform) -> Plane {
+    let normal = (transform.rotation * plane.normal).normalize();
+
+    Plane {
+        normal,
+        distance: plane.distance + normal.dot(transform.translation),
     }
 }
 
 /// 应用变换到圆柱体
-fn transform_cylinder(cylinder: &Cylinder, transform: &Transform) -> Cylinder {
+pub(crate) fn transform_cylinder(cylinder: &Cylinder, transform: &Transform) -> Cylinder {
     let new_center = transform.transform_point(cylinder.center);
     let new_axis = transform.transform_vector(cylinder.axis);
 
@@ -297,6 +1358,14 @@ fn transform_cylinder(cylinder: &Cylinder, transform: &Transform) -> Cylinder {
     }
 }
 
+/// 应用变换到球体
+pub(crate) fn transform_sphere(sphere: &Sphere, transform: &Transform) -> Sphere {
+    Sphere {
+        center: transform.transform_point(sphere.center),
+        radius: sphere.radius * transform.scale.x, // 简化处理，假设各向同性缩放
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -321,8 +1390,8 @@ mod tests {
         let collision = collide_cylinder_cylinder(&cyl1, &cyl2);
         assert!(collision.is_some());
 
-        if let Some(info) = collision {
-            assert!(info.penetration_depth > 0.0);
+        if let Some(manifold) = collision {
+            assert!(manifold.points.iter().all(|p| p.penetration_depth > 0.0));
         }
     }
 
@@ -347,4 +1416,397 @@ mod tests {
             "Expected collision between cylinder and plane"
         );
     }
+
+    #[test]
+    fn test_cylinder_cap_manifolds() {
+        let upright = |y: f32| Cylinder {
+            center: Vector3::new(0.0, y, 0.0),
+            axis: Vector3::unit_y(),
+            radius: 0.5,
+            height: 1.0,
+        };
+        let plane = Plane {
+            normal: Vector3::unit_y(),
+            distance: 0.0,
+        };
+
+        // 端面贴地时有多个支撑点，且分布在轴线两侧
+        let manifold = collide_cylinder_plane(&upright(0.45), &plane).expect("cap touches plane");
+        assert!(manifold.points.len() >= 3);
+        assert!(manifold.points.len() <= MAX_PLANE_CONTACTS);
+        for point in &manifold.points {
+            assert!((point.penetration_depth - 0.05).abs() < 1e-4);
+            assert!((point.normal - Vector3::unit_y()).magnitude() < 1e-5);
+        }
+        let xs = manifold.points.iter().map(|point| point.contact_point.x);
+        assert!(xs.clone().fold(f32::MAX, f32::min) < -0.3);
+        assert!(xs.fold(f32::MIN, f32::max) > 0.3);
+        assert!(collide_cylinder_plane(&upright(0.6), &plane).is_none());
+
+        // 倾斜的圆柱体只有一侧边缘着地
+        let tilted = Cylinder {
+            axis: Vector3::new(1.0, 1.0, 0.0).normalize(),
+            ..upright(0.6)
+        };
+        let manifold = collide_cylinder_plane(&tilted, &plane).expect("rim touches plane");
+        assert!(manifold
+            .points
+            .iter()
+            .all(|point| point.contact_point.y <= 1e-5));
+
+        // 正对叠放的圆柱体：法线沿轴线，上方圆柱体的底面边缘都是接触点
+        let manifold = collide_cylinder_cylinder(&upright(0.95), &upright(0.0))
+            .expect("stacked cylinders touch");
+        assert!(manifold.points.len() >= 3);
+        for point in &manifold.points {
+            assert!((point.normal - Vector3::unit_y()).magnitude() < 1e-3);
+            assert!((point.penetration_depth - 0.05).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_sphere_sphere_collision() {
+        let sphere1 = Sphere {
+            center: Vector3::new(0.0, 1.5, 0.0),
+            radius: 1.0,
+        };
+        let sphere2 = Sphere {
+            center: Vector3::new(0.0, 0.0, 0.0),
+            radius: 1.0,
+        };
+
+        let info = collide_sphere_sphere(&sphere1, &sphere2).expect("spheres overlap");
+        assert!((info.penetration_depth - 0.5).abs() < 1e-5);
+        assert!((info.normal - Vector3::unit_y()).magnitude() < 1e-5);
+
+        let far = Sphere {
+            center: Vector3::new(3.0, 0.0, 0.0),
+            radius: 1.0,
+        };
+        assert!(collide_sphere_sphere(&far, &sphere2).is_none());
+    }
+
+    #[test]
+    fn test_sphere_plane_collision() {
+        let sphere = Sphere {
+            center: Vector3::new(0.0, 0.8, 0.0),
+            radius: 1.0,
+        };
+        let plane = Plane {
+            normal: Vector3::new(0.0, 1.0, 0.0),
+            distance: 0.0,
+        };
+
+        let info = collide_sphere_plane(&sphere, &plane).expect("sphere touches plane");
+        assert!((info.penetration_depth - 0.2).abs() < 1e-5);
+        assert!((info.contact_point.y + 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_sphere_cylinder_collision() {
+        let cylinder = Cylinder {
+            center: Vector3::new(0.0, 0.0, 0.0),
+            axis: Vector3::new(0.0, 1.0, 0.0),
+            radius: 1.0,
+            height: 2.0,
+        };
+
+        // 侧面接触
+        let side = Sphere {
+            center: Vector3::new(1.4, 0.0, 0.0),
+            radius: 0.5,
+        };
+        let info = collide_sphere_cylinder(&side, &cylinder).expect("side contact");
+        assert!((info.penetration_depth - 0.1).abs() < 1e-5);
+        assert!((info.normal - Vector3::unit_x()).magnitude() < 1e-5);
+
+        // 端面接触
+        let cap = Sphere {
+            center: Vector3::new(0.2, 1.3, 0.0),
+            radius: 0.5,
+        };
+        let info = collide_sphere_cylinder(&cap, &cylinder).expect("cap contact");
+        assert!((info.penetration_depth - 0.2).abs() < 1e-5);
+        assert!((info.normal - Vector3::unit_y()).magnitude() < 1e-5);
+
+        // 球心在圆柱体内部
+        let inside = Sphere {
+            center: Vector3::new(0.0, 0.9, 0.0),
+            radius: 0.5,
+        };
+        let info = collide_sphere_cylinder(&inside, &cylinder).expect("deep contact");
+        assert!((info.normal - Vector3::unit_y()).magnitude() < 1e-5);
+        assert!((info.penetration_depth - 0.6).abs() < 1e-5);
+
+        // 边缘附近但不接触
+        let corner = Sphere {
+            center: Vector3::new(1.4, 1.4, 0.0),
+            radius: 0.5,
+        };
+        assert!(collide_sphere_cylinder(&corner, &cylinder).is_none());
+    }
+
+    fn unit_box_at(center: Vector3<f32>) -> Obb {
+        Obb::new(
+            Vector3::new(0.5, 0.5, 0.5),
+            &Transform::translation(center.x, center.y, center.z),
+        )
+    }
+
+    #[test]
+    fn test_box_plane_manifold() {
+        let plane = Plane {
+            normal: Vector3::new(0.0, 1.0, 0.0),
+            distance: 0.0,
+        };
+
+        // 平放的盒子底面四个顶点都应成为接触点
+        let manifold = collide_box_plane(&unit_box_at(Vector3::new(0.0, 0.45, 0.0)), &plane)
+            .expect("box touches plane");
+        assert_eq!(manifold.points.len(), 4);
+        for point in &manifold.points {
+            assert!((point.penetration_depth - 0.05).abs() < 1e-5);
+            assert!((point.normal - Vector3::unit_y()).magnitude() < 1e-5);
+        }
+
+        assert!(collide_box_plane(&unit_box_at(Vector3::new(0.0, 0.6, 0.0)), &plane).is_none());
+    }
+
+    #[test]
+    fn test_box_box_face_and_edge_contacts() {
+        let lower = unit_box_at(Vector3::new(0.0, 0.0, 0.0));
+
+        // 叠放：上表面与下表面贴合，裁剪后得到四个接触点，法线指向第一个盒子
+        let upper = unit_box_at(Vector3::new(0.2, 0.95, 0.0));
+        let manifold = collide_box_box(&upper, &lower).expect("stacked boxes overlap");
+        assert_eq!(manifold.points.len(), 4);
+        for point in &manifold.points {
+            assert!((point.penetration_depth - 0.05).abs() < 1e-5);
+            assert!((point.normal - Vector3::unit_y()).magnitude() < 1e-5);
+        }
+        let flipped = collide_box_box(&lower, &upper).expect("stacked boxes overlap");
+        assert!((flipped.points[0].normal + Vector3::unit_y()).magnitude() < 1e-5);
+
+        // 分离
+        assert!(collide_box_box(&unit_box_at(Vector3::new(1.1, 0.0, 0.0)), &lower).is_none());
+
+        // 绕两条不同轴旋转 45° 后边与边交叉
+        let rotated = |axis: Vector3<f32>, center: Vector3<f32>| {
+            Obb::new(
+                Vector3::new(0.5, 0.5, 0.5),
+                &Transform {
+                    translation: center,
+                    rotation: Quaternion::from_axis_angle(axis, Deg(45.0)),
+                    scale: Vector3::new(1.0, 1.0, 1.0),
+                },
+            )
+        };
+        let edge1 = rotated(Vector3::unit_z(), Vector3::zero());
+        let edge2 = rotated(Vector3::unit_x(), Vector3::new(0.0, 1.35, 0.0));
+        let manifold = collide_box_box(&edge2, &edge1).expect("edges cross");
+        assert_eq!(manifold.points.len(), 1);
+        let depth = 2.0f32.sqrt() - 1.35;
+        assert!((manifold.points[0].penetration_depth - depth).abs() < 1e-4);
+        assert!((manifold.points[0].normal - Vector3::unit_y()).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn test_sphere_box_collision() {
+        let obb = unit_box_at(Vector3::zero());
+
+        let face = Sphere {
+            center: Vector3::new(0.0, 0.8, 0.0),
+            radius: 0.5,
+        };
+        let info = collide_sphere_box(&face, &obb).expect("face contact");
+        assert!((info.penetration_depth - 0.2).abs() < 1e-5);
+        assert!((info.normal - Vector3::unit_y()).magnitude() < 1e-5);
+
+        let inside = Sphere {
+            center: Vector3::new(0.3, 0.0, 0.0),
+            radius: 0.5,
+        };
+        let info = collide_sphere_box(&inside, &obb).expect("deep contact");
+        assert!((info.normal - Vector3::unit_x()).magnitude() < 1e-5);
+        assert!((info.penetration_depth - 0.7).abs() < 1e-5);
+
+        let corner = Sphere {
+            center: Vector3::new(0.9, 0.9, 0.9),
+            radius: 0.5,
+        };
+        assert!(collide_sphere_box(&corner, &obb).is_none());
+    }
+
+    #[test]
+    fn test_box_cylinder_collision() {
+        let cylinder = Cylinder {
+            center: Vector3::new(0.0, 0.0, 0.0),
+            axis: Vector3::new(0.0, 1.0, 0.0),
+            radius: 1.0,
+            height: 1.0,
+        };
+
+        // 盒子平放在圆柱体端面上：四个底面顶点都在端面内
+        let obb = unit_box_at(Vector3::new(0.0, 0.95, 0.0));
+        let manifold = collide_box_cylinder(&obb, &cylinder).expect("box on cap");
+        assert_eq!(manifold.points.len(), 4);
+        for point in &manifold.points {
+            assert!((point.penetration_depth - 0.05).abs() < 1e-5);
+            assert!((point.normal - Vector3::unit_y()).magnitude() < 1e-5);
+        }
+
+        // 圆柱体侧面贴着盒子的面
+        let obb = unit_box_at(Vector3::new(1.45, 0.0, 0.0));
+        let manifold = collide_box_cylinder(&obb, &cylinder).expect("box against side");
+        assert!(manifold
+            .points
+            .iter()
+            .all(|p| (p.normal - Vector3::unit_x()).magnitude() < 1e-5));
+        let deepest = manifold
+            .points
+            .iter()
+            .map(|p| p.penetration_depth)
+            .fold(0.0, f32::max);
+        assert!((deepest - 0.05).abs() < 1e-5);
+
+        assert!(
+            collide_box_cylinder(&unit_box_at(Vector3::new(1.6, 0.0, 0.0)), &cylinder).is_none()
+        );
+    }
+
+    // 沿 X 轴躺倒的胶囊体
+    fn lying_capsule(center: Vector3<f32>) -> Capsule {
+        Capsule {
+            start: center - Vector3::new(0.5, 0.0, 0.0),
+            end: center + Vector3::new(0.5, 0.0, 0.0),
+            radius: 0.25,
+        }
+    }
+
+    #[test]
+    fn test_closest_points_segment_segment() {
+        // 交叉的两条线段
+        let (c1, c2) = closest_points_segment_segment(
+            Vector3::new(-1.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.5, 1.0, -1.0),
+            Vector3::new(0.5, 1.0, 1.0),
+        );
+        assert!((c1 - Vector3::new(0.5, 0.0, 0.0)).magnitude() < 1e-5);
+        assert!((c2 - Vector3::new(0.5, 1.0, 0.0)).magnitude() < 1e-5);
+
+        // 最近点被限制在线段端点
+        let (c1, c2) = closest_points_segment_segment(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(3.0, 1.0, 0.0),
+            Vector3::new(3.0, 2.0, 0.0),
+        );
+        assert!((c1 - Vector3::new(1.0, 0.0, 0.0)).magnitude() < 1e-5);
+        assert!((c2 - Vector3::new(3.0, 1.0, 0.0)).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn test_capsule_capsule_collision() {
+        let lower = lying_capsule(Vector3::zero());
+
+        // 平行叠放：重叠区间两端各一个接触点
+        let upper = lying_capsule(Vector3::new(0.25, 0.45, 0.0));
+        let manifold = collide_capsule_capsule(&upper, &lower).expect("parallel overlap");
+        assert_eq!(manifold.points.len(), 2);
+        for point in &manifold.points {
+            assert!((point.penetration_depth - 0.05).abs() < 1e-5);
+            assert!((point.normal - Vector3::unit_y()).magnitude() < 1e-5);
+        }
+
+        // 交叉叠放：单个接触点
+        let crossed = Capsule {
+            start: Vector3::new(0.2, 0.4, -0.5),
+            end: Vector3::new(0.2, 0.4, 0.5),
+            radius: 0.25,
+        };
+        let manifold = collide_capsule_capsule(&crossed, &lower).expect("crossed overlap");
+        assert_eq!(manifold.points.len(), 1);
+        assert!((manifold.points[0].penetration_depth - 0.1).abs() < 1e-5);
+
+        assert!(
+            collide_capsule_capsule(&lying_capsule(Vector3::new(0.0, 0.6, 0.0)), &lower).is_none()
+        );
+    }
+
+    #[test]
+    fn test_capsule_plane_and_sphere_collision() {
+        let plane = Plane {
+            normal: Vector3::new(0.0, 1.0, 0.0),
+            distance: 0.0,
+        };
+        let manifold = collide_capsule_plane(&lying_capsule(Vector3::new(0.0, 0.2, 0.0)), &plane)
+            .expect("lying capsule touches plane");
+        assert_eq!(manifold.points.len(), 2);
+        assert!((manifold.points[0].penetration_depth - 0.05).abs() < 1e-5);
+
+        let sphere = Sphere {
+            center: Vector3::new(0.8, 0.0, 0.0),
+            radius: 0.5,
+        };
+        let info = collide_capsule_sphere(&lying_capsule(Vector3::zero()), &sphere)
+            .expect("sphere touches capsule end");
+        assert!((info.penetration_depth - 0.45).abs() < 1e-5);
+        assert!((info.normal + Vector3::unit_x()).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn test_capsule_box_collision() {
+        let obb = unit_box_at(Vector3::zero());
+
+        // 平躺在盒子顶面上：两端各一个接触点
+        let manifold = collide_capsule_box(&lying_capsule(Vector3::new(0.0, 0.7, 0.0)), &obb)
+            .expect("capsule on box");
+        assert_eq!(manifold.points.len(), 2);
+        for point in &manifold.points {
+            assert!((point.penetration_depth - 0.05).abs() < 1e-5);
+            assert!((point.normal - Vector3::unit_y()).magnitude() < 1e-5);
+        }
+
+        // 竖直跨过盒子的棱边：两端都不接触，由线段最近点补充接触
+        let across = Capsule {
+            start: Vector3::new(0.7, 0.7, -1.0),
+            end: Vector3::new(0.7, 0.7, 1.0),
+            radius: 0.3,
+        };
+        let manifold = collide_capsule_box(&across, &obb).expect("capsule across edge");
+        assert_eq!(manifold.points.len(), 1);
+        let expected = 0.3 - 0.2 * 2.0f32.sqrt();
+        assert!((manifold.points[0].penetration_depth - expected).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_unspecialized_pairs_fall_back_to_gjk() {
+        // 胶囊体与圆柱体没有专门的检测函数
+        let capsule = PhysicsBody::new_dynamic(
+            Shape::Capsule {
+                radius: 0.25,
+                half_height: 0.5,
+            },
+            Transform::translation(0.0, 1.2, 0.0),
+            1.0,
+        );
+        let cylinder = PhysicsBody::new_static(
+            Shape::Cylinder(Cylinder {
+                center: Vector3::zero(),
+                axis: Vector3::unit_y(),
+                radius: 1.0,
+                height: 1.0,
+            }),
+            Transform::identity(),
+        );
+
+        let manifold = collide_bodies(&capsule, &cylinder).expect("capsule end on cylinder cap");
+        let info = manifold.points[0];
+        assert!((info.penetration_depth - 0.05).abs() < 1e-3);
+        assert!((info.normal - Vector3::unit_y()).magnitude() < 1e-3);
+
+        let flipped = collide_bodies(&cylinder, &capsule).unwrap();
+        assert!((flipped.points[0].normal + Vector3::unit_y()).magnitude() < 1e-3);
+    }
 }
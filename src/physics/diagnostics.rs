@@ -0,0 +1,134 @@
+use crate::physics::shape::{PhysicsBody, PhysicsState};
+use cgmath::*;
+use std::collections::VecDeque;
+
+/// 某一时刻整个世界的能量与动量，只统计动态物体
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Diagnostics {
+    pub kinetic_energy: f32,    // 平动动能 Σ m·v²/2
+    pub rotational_energy: f32, // 转动动能 Σ ωᵀ·I·ω/2
+    pub potential_energy: f32,  // 重力势能 Σ -m·g·x，以世界原点为零点
+    pub linear_momentum: Vector3<f32>,
+    pub angular_momentum: Vector3<f32>, // 相对世界原点，Σ x × m·v + I·ω
+}
+
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Self {
+            kinetic_energy: 0.0,
+            rotational_energy: 0.0,
+            potential_energy: 0.0,
+            linear_momentum: Vector3::zero(),
+            angular_momentum: Vector3::zero(),
+        }
+    }
+}
+
+impl Diagnostics {
+    /// 统计给定物体的能量与动量，gravity 用于计算重力势能
+    pub fn measure<'a>(
+        bodies: impl IntoIterator<Item = &'a PhysicsBody>,
+        gravity: Vector3<f32>,
+    ) -> Self {
+        let mut result = Self::default();
+        for body in bodies {
+            if !matches!(body.state, PhysicsState::Dynamic) {
+                continue;
+            }
+
+            let position = body.position();
+            let momentum = body.velocity * body.mass;
+            let spin = match body.inv_inertia_world().invert() {
+                Some(inertia) => inertia * body.angular_velocity,
+                None => Vector3::zero(),
+            };
+            result.kinetic_energy += 0.5 * body.mass * body.velocity.magnitude2();
+            result.rotational_energy += 0.5 * body.angular_velocity.dot(spin);
+            result.potential_energy -= body.mass * gravity.dot(position);
+            result.linear_momentum += momentum;
+            result.angular_momentum += position.cross(momentum) + spin;
+        }
+        result
+    }
+
+    /// 机械能总和
+    pub fn total_energy(&self) -> f32 {
+        self.kinetic_energy + self.rotational_energy + self.potential_energy
+    }
+}
+
+/// 最近若干步的诊断数据，超出容量时丢弃最早的记录
+pub struct DiagnosticsHistory {
+    samples: VecDeque<Diagnostics>,
+    capacity: usize,
+}
+
+impl DiagnosticsHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, sample: Diagnostics) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn latest(&self) -> Option<&Diagnostics> {
+        self.samples.back()
+    }
+
+    /// 按时间顺序取出某一项数值，用于绘制曲线
+    pub fn series(&self, value: impl Fn(&Diagnostics) -> f32) -> Vec<f32> {
+        self.samples.iter().map(value).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Transform;
+    use crate::physics::shape::{Shape, Sphere};
+
+    #[test]
+    fn test_measure_and_history() {
+        let sphere = Shape::Sphere(Sphere {
+            center: Vector3::zero(),
+            radius: 1.0,
+        });
+        let mut transform = Transform::identity();
+        transform.translation = Vector3::new(0.0, 2.0, 0.0);
+        let mut body = PhysicsBody::new_dynamic(sphere.clone(), transform, 2.0);
+        body.velocity = Vector3::new(3.0, 0.0, 0.0);
+        body.angular_velocity = Vector3::new(0.0, 0.0, 5.0);
+        let ground = PhysicsBody::new_static(sphere, Transform::identity());
+
+        let gravity = Vector3::new(0.0, -10.0, 0.0);
+        let diagnostics = Diagnostics::measure([&body, &ground], gravity);
+        assert!((diagnostics.kinetic_energy - 9.0).abs() < 1e-4);
+        // 实心球 I = 2/5·m·r² = 0.8
+        assert!((diagnostics.rotational_energy - 0.5 * 0.8 * 25.0).abs() < 1e-4);
+        assert!((diagnostics.potential_energy - 40.0).abs() < 1e-4);
+        assert!((diagnostics.linear_momentum - Vector3::new(6.0, 0.0, 0.0)).magnitude() < 1e-4);
+        // (0, 2, 0) × (6, 0, 0) = (0, 0, -12)，加上自转 I·ω = (0, 0, 4)
+        assert!((diagnostics.angular_momentum - Vector3::new(0.0, 0.0, -8.0)).magnitude() < 1e-4);
+
+        let mut history = DiagnosticsHistory::new(2);
+        for energy in [1.0, 2.0, 3.0] {
+            history.push(Diagnostics {
+                kinetic_energy: energy,
+                ..Default::default()
+            });
+        }
+        assert_eq!(history.series(|d| d.total_energy()), vec![2.0, 3.0]);
+        assert_eq!(history.latest().unwrap().kinetic_energy, 3.0);
+    }
+}
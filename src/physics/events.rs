@@ -0,0 +1,96 @@
+use crate::physics::phymgr::BodyHandle;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// 模拟过程中产生的事件，每步结束后追加到世界的事件队列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhysicsEvent {
+    /// 物体开始与传感器重叠
+    TriggerEnter {
+        sensor: BodyHandle,
+        other: BodyHandle,
+    },
+    /// 物体仍与传感器重叠，每步一次
+    TriggerStay {
+        sensor: BodyHandle,
+        other: BodyHandle,
+    },
+    /// 物体离开传感器，或其中一方被移除
+    TriggerExit {
+        sensor: BodyHandle,
+        other: BodyHandle,
+    },
+    /// 两个物体开始接触
+    ContactStarted {
+        body1: BodyHandle,
+        body2: BodyHandle,
+    },
+    /// 两个物体不再接触，或其中一方被移除
+    ContactEnded {
+        body1: BodyHandle,
+        body2: BodyHandle,
+    },
+}
+
+impl fmt::Display for PhysicsEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, a, b) = match *self {
+            PhysicsEvent::TriggerEnter { sensor, other } => ("Trigger Enter", sensor, other),
+            PhysicsEvent::TriggerStay { sensor, other } => ("Trigger Stay", sensor, other),
+            PhysicsEvent::TriggerExit { sensor, other } => ("Trigger Exit", sensor, other),
+            PhysicsEvent::ContactStarted { body1, body2 } => ("Contact Started", body1, body2),
+            PhysicsEvent::ContactEnded { body1, body2 } => ("Contact Ended", body1, body2),
+        };
+        write!(f, "{}: #{} #{}", name, a.index(), b.index())
+    }
+}
+
+/// 记录上一步接触和与传感器重叠的物体对，与本步比较后生成事件
+#[derive(Default)]
+pub struct EventTracker {
+    contacts: BTreeSet<(BodyHandle, BodyHandle)>,
+    overlaps: BTreeSet<(BodyHandle, BodyHandle)>, // (传感器, 另一物体)
+}
+
+impl EventTracker {
+    /// 用本步的接触对与重叠对更新记录，并按句柄顺序追加事件
+    ///
+    /// 本步没有检测的物体对（如两侧都在休眠）由 untested 判断，沿用上一步的状态。
+    pub fn update(
+        &mut self,
+        mut contacts: BTreeSet<(BodyHandle, BodyHandle)>,
+        mut overlaps: BTreeSet<(BodyHandle, BodyHandle)>,
+        untested: impl Fn(BodyHandle, BodyHandle) -> bool,
+        events: &mut Vec<PhysicsEvent>,
+    ) {
+        contacts.extend(
+            self.contacts
+                .iter()
+                .filter(|&&(body1, body2)| untested(body1, body2)),
+        );
+        overlaps.extend(
+            self.overlaps
+                .iter()
+                .filter(|&&(sensor, other)| untested(sensor, other)),
+        );
+
+        for &(sensor, other) in overlaps.difference(&self.overlaps) {
+            events.push(PhysicsEvent::TriggerEnter { sensor, other });
+        }
+        for &(sensor, other) in overlaps.intersection(&self.overlaps) {
+            events.push(PhysicsEvent::TriggerStay { sensor, other });
+        }
+        for &(sensor, other) in self.overlaps.difference(&overlaps) {
+            events.push(PhysicsEvent::TriggerExit { sensor, other });
+        }
+        for &(body1, body2) in contacts.difference(&self.contacts) {
+            events.push(PhysicsEvent::ContactStarted { body1, body2 });
+        }
+        for &(body1, body2) in self.contacts.difference(&contacts) {
+            events.push(PhysicsEvent::ContactEnded { body1, body2 });
+        }
+
+        self.contacts = contacts;
+        self.overlaps = overlaps;
+    }
+}
@@ -0,0 +1,99 @@
+use crate::physics::phymgr::{BodyHandle, BodySet};
+use crate::physics::shape::PhysicsBody;
+
+/// 碰撞分组：memberships 为物体所属的组，filter 为它愿意与之碰撞的组，每一位代表一个组
+///
+/// 两个物体都在对方的 filter 中时才会碰撞，例如碎片属于 DEBRIS 组、
+/// filter 去掉 DEBRIS 后碎片之间互不碰撞，但仍会与其它物体碰撞。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CollisionGroups {
+    pub memberships: u32,
+    pub filter: u32,
+}
+
+impl CollisionGroups {
+    /// 属于所有组并与所有组碰撞，物体的默认分组
+    pub const ALL: CollisionGroups = CollisionGroups::new(u32::MAX, u32::MAX);
+    /// 不与任何物体碰撞
+    pub const NONE: CollisionGroups = CollisionGroups::new(0, 0);
+
+    pub const fn new(memberships: u32, filter: u32) -> Self {
+        Self {
+            memberships,
+            filter,
+        }
+    }
+
+    pub fn interacts_with(&self, other: &CollisionGroups) -> bool {
+        self.memberships & other.filter != 0 && other.memberships & self.filter != 0
+    }
+}
+
+impl Default for CollisionGroups {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// 按碰撞分组和双方的忽略列表判断两个物体之间是否需要检测碰撞
+pub fn can_collide(
+    (handle1, body1): (BodyHandle, &PhysicsBody),
+    (handle2, body2): (BodyHandle, &PhysicsBody),
+) -> bool {
+    body1
+        .collision_groups
+        .interacts_with(&body2.collision_groups)
+        && !body1.ignored_bodies.contains(&handle2)
+        && !body2.ignored_bodies.contains(&handle1)
+}
+
+/// 按句柄判断，任一句柄无效时返回 false
+pub fn can_collide_in(bodies: &BodySet, handle1: BodyHandle, handle2: BodyHandle) -> bool {
+    match (bodies.get(handle1), bodies.get(handle2)) {
+        (Some(body1), Some(body2)) => can_collide((handle1, body1), (handle2, body2)),
+        _ => false,
+    }
+}
+
+/// 场景查询（射线、扫掠）的过滤条件
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryFilter {
+    pub groups: CollisionGroups, // 查询本身的分组，按与物体之间相同的规则筛选
+    pub exclude: Option<BodyHandle>, // 跳过的物体，如发出射线的物体自身
+}
+
+impl QueryFilter {
+    pub fn new(groups: CollisionGroups) -> Self {
+        Self {
+            groups,
+            exclude: None,
+        }
+    }
+
+    pub fn excluding(mut self, handle: BodyHandle) -> Self {
+        self.exclude = Some(handle);
+        self
+    }
+
+    pub fn accepts(&self, handle: BodyHandle, body: &PhysicsBody) -> bool {
+        self.exclude != Some(handle) && self.groups.interacts_with(&body.collision_groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collision_groups() {
+        const DEBRIS: u32 = 1 << 1;
+        let debris = CollisionGroups::new(DEBRIS, !DEBRIS);
+        assert!(!debris.interacts_with(&debris));
+        assert!(debris.interacts_with(&CollisionGroups::ALL));
+        assert!(CollisionGroups::ALL.interacts_with(&CollisionGroups::ALL));
+        assert!(!CollisionGroups::NONE.interacts_with(&CollisionGroups::ALL));
+        // 单向接受不够，双方都要在对方的 filter 中
+        let one_way = CollisionGroups::new(1, DEBRIS);
+        assert!(!one_way.interacts_with(&CollisionGroups::new(DEBRIS, 2)));
+    }
+}
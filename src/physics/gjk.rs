@@ -0,0 +1,580 @@
+use crate::common::Transform;
+use crate::physics::collision::{Capsule, CollisionInfo, Obb};
+use crate::physics::shape::{ConvexHull, Cylinder, Sphere};
+use cgmath::*;
+
+/// 支撑映射：给定方向，返回凸体在该方向上最远的点（世界坐标）
+///
+/// 任何凸形状只要实现该 trait，即可通过 GJK/EPA 与其他凸形状做碰撞检测。
+pub trait SupportMap {
+    fn support(&self, direction: Vector3<f32>) -> Vector3<f32>;
+}
+
+impl SupportMap for Sphere {
+    fn support(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        self.center + safe_normalize(direction) * self.radius
+    }
+}
+
+impl SupportMap for Cylinder {
+    fn support(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        let axis = self.axis.normalize();
+        let axial = direction.dot(axis);
+        let radial = direction - axis * axial;
+        let sign = if axial >= 0.0 { 1.0 } else { -1.0 };
+
+        self.center + axis * (sign * self.height / 2.0) + safe_normalize(radial) * self.radius
+    }
+}
+
+impl SupportMap for Capsule {
+    fn support(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        let end = if (self.end - self.start).dot(direction) >= 0.0 {
+            self.end
+        } else {
+            self.start
+        };
+        end + safe_normalize(direction) * self.radius
+    }
+}
+
+impl SupportMap for Obb {
+    fn support(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        let mut point = self.center;
+        for axis in 0..3 {
+            let sign = if self.axes[axis].dot(direction) >= 0.0 {
+                1.0
+            } else {
+                -1.0
+            };
+            point += self.axes[axis] * (sign * self.half_extents[axis]);
+        }
+        point
+    }
+}
+
+/// 带变换的凸包，局部顶点经缩放、旋转、平移后得到世界坐标
+pub struct TransformedHull<'a> {
+    pub hull: &'a ConvexHull,
+    pub transform: &'a Transform,
+}
+
+impl SupportMap for TransformedHull<'_> {
+    fn support(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        // 方向转到局部空间；缩放作用在顶点上，等价于对方向逐分量乘以缩放
+        let local = self.transform.rotation.invert() * direction;
+        let scaled = local.mul_element_wise(self.transform.scale);
+        self.transform
+            .transform_point(self.hull.support_local(scaled))
+    }
+}
+
+// 零向量归一化时返回零向量，球面支撑点退化为球心
+fn safe_normalize(v: Vector3<f32>) -> Vector3<f32> {
+    let length2 = v.magnitude2();
+    if length2 > 1e-12 {
+        v / length2.sqrt()
+    } else {
+        Vector3::zero()
+    }
+}
+
+const GJK_MAX_ITERATIONS: usize = 64;
+const GJK_TOLERANCE: f32 = 1e-6;
+const EPA_MAX_ITERATIONS: usize = 64;
+const EPA_TOLERANCE: f32 = 1e-4;
+
+/// Minkowski 差 A - B 上的一点，同时记录它来自 A 和 B 的哪两个支撑点
+#[derive(Clone, Copy)]
+struct SupportPoint {
+    w: Vector3<f32>,
+    a: Vector3<f32>,
+    b: Vector3<f32>,
+}
+
+fn minkowski_support(
+    shape_a: &dyn SupportMap,
+    shape_b: &dyn SupportMap,
+    direction: Vector3<f32>,
+) -> SupportPoint {
+    let a = shape_a.support(direction);
+    let b = shape_b.support(-direction);
+    SupportPoint { w: a - b, a, b }
+}
+
+/// 两凸体分离时的最近点对
+#[derive(Debug, Clone, Copy)]
+pub struct ClosestPoints {
+    pub distance: f32,
+    pub point_a: Vector3<f32>,
+    pub point_b: Vector3<f32>,
+}
+
+enum GjkResult {
+    Separated(ClosestPoints),
+    Intersecting(Vec<SupportPoint>),
+}
+
+/// GJK 距离查询：两凸体分离时返回最近点对，相交时返回 None
+pub fn closest_points(shape_a: &dyn SupportMap, shape_b: &dyn SupportMap) -> Option<ClosestPoints> {
+    match gjk(shape_a, shape_b) {
+        GjkResult::Separated(points) => Some(points),
+        GjkResult::Intersecting(_) => None,
+    }
+}
+
+/// 通用凸体碰撞检测：GJK 判断相交，EPA 求穿透深度与法线
+///
+/// 返回的法线指向 shape_a，与 `collide_bodies` 的约定一致。
+pub fn collide_convex(shape_a: &dyn SupportMap, shape_b: &dyn SupportMap) -> Option<CollisionInfo> {
+    match gjk(shape_a, shape_b) {
+        GjkResult::Separated(_) => None,
+        GjkResult::Intersecting(simplex) => epa(shape_a, shape_b, simplex),
+    }
+}
+
+fn gjk(shape_a: &dyn SupportMap, shape_b: &dyn SupportMap) -> GjkResult {
+    let mut simplex = vec![minkowski_support(shape_a, shape_b, Vector3::unit_x())];
+    let mut weights = vec![1.0];
+    let mut v = simplex[0].w;
+
+    for _ in 0..GJK_MAX_ITERATIONS {
+        let v2 = v.magnitude2();
+        if v2 < GJK_TOLERANCE * GJK_TOLERANCE {
+            // 原点落在单纯形上，视为相交
+            return GjkResult::Intersecting(simplex);
+        }
+
+        let w = minkowski_support(shape_a, shape_b, -v);
+        // 新支撑点不能让 v 明显缩短时收敛
+        let duplicate = simplex
+            .iter()
+            .any(|p| (p.w - w.w).magnitude2() < GJK_TOLERANCE * GJK_TOLERANCE);
+        if duplicate || v2 - v.dot(w.w) <= GJK_TOLERANCE * v2.max(1.0) {
+            break;
+        }
+
+        simplex.push(w);
+        let (closest, reduced, reduced_weights) = closest_on_simplex(&simplex);
+        match closest {
+            None => return GjkResult::Intersecting(simplex),
+            Some(point) => {
+                simplex = reduced;
+                weights = reduced_weights;
+                v = point;
+            }
+        }
+    }
+
+    let point_a = simplex
+        .iter()
+        .zip(&weights)
+        .fold(Vector3::zero(), |acc, (p, &l)| acc + p.a * l);
+    let point_b = simplex
+        .iter()
+        .zip(&weights)
+        .fold(Vector3::zero(), |acc, (p, &l)| acc + p.b * l);
+
+    GjkResult::Separated(ClosestPoints {
+        distance: v.magnitude(),
+        point_a,
+        point_b,
+    })
+}
+
+/// 单纯形上离原点最近的点，并把单纯形缩减为该点所在的最小子单纯形
+///
+/// 返回 (最近点, 子单纯形, 重心坐标)。四面体包含原点时最近点为 None。
+#[allow(clippy::type_complexity)]
+fn closest_on_simplex(
+    simplex: &[SupportPoint],
+) -> (Option<Vector3<f32>>, Vec<SupportPoint>, Vec<f32>) {
+    match simplex.len() {
+        1 => (Some(simplex[0].w), simplex.to_vec(), vec![1.0]),
+        2 => {
+            let (point, indices, weights) = closest_on_segment(simplex[0].w, simplex[1].w);
+            (Some(point), pick(simplex, &indices), weights)
+        }
+        3 => {
+            let (point, indices, weights) =
+                closest_on_triangle(simplex[0].w, simplex[1].w, simplex[2].w);
+            (Some(point), pick(simplex, &indices), weights)
+        }
+        _ => closest_on_tetrahedron(simplex),
+    }
+}
+
+fn pick(simplex: &[SupportPoint], indices: &[usize]) -> Vec<SupportPoint> {
+    indices.iter().map(|&i| simplex[i]).collect()
+}
+
+fn closest_on_segment(a: Vector3<f32>, b: Vector3<f32>) -> (Vector3<f32>, Vec<usize>, Vec<f32>) {
+    let ab = b - a;
+    let t = -a.dot(ab) / ab.magnitude2().max(1e-12);
+    if t <= 0.0 {
+        (a, vec![0], vec![1.0])
+    } else if t >= 1.0 {
+        (b, vec![1], vec![1.0])
+    } else {
+        (a + ab * t, vec![0, 1], vec![1.0 - t, t])
+    }
+}
+
+// 原点到三角形的最近点，按 Voronoi 区域分类；返回的索引即最近点所在的顶点、边或面
+pub(crate) fn closest_on_triangle(
+    a: Vector3<f32>,
+    b: Vector3<f32>,
+    c: Vector3<f32>,
+) -> (Vector3<f32>, Vec<usize>, Vec<f32>) {
+    let ab = b - a;
+    let ac = c - a;
+
+    let d1 = ab.dot(-a);
+    let d2 = ac.dot(-a);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return (a, vec![0], vec![1.0]);
+    }
+
+    let d3 = ab.dot(-b);
+    let d4 = ac.dot(-b);
+    if d3 >= 0.0 && d4 <= d3 {
+        return (b, vec![1], vec![1.0]);
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let t = d1 / (d1 - d3);
+        return (a + ab * t, vec![0, 1], vec![1.0 - t, t]);
+    }
+
+    let d5 = ab.dot(-c);
+    let d6 = ac.dot(-c);
+    if d6 >= 0.0 && d5 <= d6 {
+        return (c, vec![2], vec![1.0]);
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let t = d2 / (d2 - d6);
+        return (a + ac * t, vec![0, 2], vec![1.0 - t, t]);
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        let t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (b + (c - b) * t, vec![1, 2], vec![1.0 - t, t]);
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    (a + ab * v + ac * w, vec![0, 1, 2], vec![1.0 - v - w, v, w])
+}
+
+#[allow(clippy::type_complexity)]
+fn closest_on_tetrahedron(
+    simplex: &[SupportPoint],
+) -> (Option<Vector3<f32>>, Vec<SupportPoint>, Vec<f32>) {
+    // 每个面及其对面的顶点
+    const FACES: [([usize; 3], usize); 4] = [
+        ([0, 1, 2], 3),
+        ([0, 2, 3], 1),
+        ([0, 3, 1], 2),
+        ([1, 3, 2], 0),
+    ];
+
+    let mut best: Option<(f32, Vector3<f32>, Vec<SupportPoint>, Vec<f32>)> = None;
+    for (face, opposite) in FACES {
+        let [i, j, k] = face;
+        let (a, b, c) = (simplex[i].w, simplex[j].w, simplex[k].w);
+        let normal = (b - a).cross(c - a);
+        let side_origin = normal.dot(-a);
+        let side_opposite = normal.dot(simplex[opposite].w - a);
+
+        // 原点与对面顶点位于同侧则不在该面外；四面体退化时所有面都参与比较
+        if side_origin * side_opposite > 0.0 && side_opposite.abs() > 1e-9 {
+            continue;
+        }
+
+        let (point, indices, weights) = closest_on_triangle(a, b, c);
+        let dist2 = point.magnitude2();
+        if best.as_ref().is_none_or(|(d, ..)| dist2 < *d) {
+            let reduced = indices.iter().map(|&n| simplex[face[n]]).collect();
+            best = Some((dist2, point, reduced, weights));
+        }
+    }
+
+    match best {
+        Some((_, point, reduced, weights)) => (Some(point), reduced, weights),
+        None => (None, simplex.to_vec(), Vec::new()),
+    }
+}
+
+/// EPA 多面体的一个三角面，法线朝外
+struct EpaFace {
+    indices: [usize; 3],
+    normal: Vector3<f32>,
+    distance: f32,
+}
+
+fn make_face(vertices: &[SupportPoint], indices: [usize; 3]) -> Option<EpaFace> {
+    let a = vertices[indices[0]].w;
+    let b = vertices[indices[1]].w;
+    let c = vertices[indices[2]].w;
+    let normal = (b - a).cross(c - a);
+    let length = normal.magnitude();
+    if length < 1e-9 {
+        return None;
+    }
+    let normal = normal / length;
+    Some(EpaFace {
+        indices,
+        normal,
+        distance: normal.dot(a),
+    })
+}
+
+/// 把 GJK 终止时的单纯形扩充为包含原点的四面体
+fn blow_up_simplex(
+    shape_a: &dyn SupportMap,
+    shape_b: &dyn SupportMap,
+    mut simplex: Vec<SupportPoint>,
+) -> Option<Vec<SupportPoint>> {
+    let axes = [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()];
+
+    if simplex.len() == 1 {
+        for axis in axes {
+            for direction in [axis, -axis] {
+                let p = minkowski_support(shape_a, shape_b, direction);
+                if (p.w - simplex[0].w).magnitude2() > 1e-9 {
+                    simplex.push(p);
+                    break;
+                }
+            }
+            if simplex.len() == 2 {
+                break;
+            }
+        }
+    }
+
+    if simplex.len() == 2 {
+        let line = simplex[1].w - simplex[0].w;
+        let mut direction = line.cross(Vector3::unit_x());
+        if direction.magnitude2() < 1e-9 {
+            direction = line.cross(Vector3::unit_y());
+        }
+        let rotation = Basis3::from_axis_angle(line.normalize(), Deg(60.0));
+        for _ in 0..6 {
+            let p = minkowski_support(shape_a, shape_b, direction);
+            if (p.w - simplex[0].w).cross(line).magnitude2() > 1e-9 {
+                simplex.push(p);
+                break;
+            }
+            direction = rotation.rotate_vector(direction);
+        }
+    }
+
+    if simplex.len() == 3 {
+        let normal = (simplex[1].w - simplex[0].w).cross(simplex[2].w - simplex[0].w);
+        for direction in [normal, -normal] {
+            let p = minkowski_support(shape_a, shape_b, direction);
+            if (p.w - simplex[0].w).dot(normal).abs() > 1e-9 {
+                simplex.push(p);
+                break;
+            }
+        }
+    }
+
+    (simplex.len() == 4).then_some(simplex)
+}
+
+fn epa(
+    shape_a: &dyn SupportMap,
+    shape_b: &dyn SupportMap,
+    simplex: Vec<SupportPoint>,
+) -> Option<CollisionInfo> {
+    let mut vertices = blow_up_simplex(shape_a, shape_b, simplex)?;
+
+    // 初始四面体，保证各面法线朝外
+    let mut faces = Vec::new();
+    for (face, opposite) in [
+        ([0, 1, 2], 3),
+        ([0, 2, 3], 1),
+        ([0, 3, 1], 2),
+        ([1, 3, 2], 0),
+    ] {
+        let mut face = make_face(&vertices, face)?;
+        if face
+            .normal
+            .dot(vertices[opposite].w - vertices[face.indices[0]].w)
+            > 0.0
+        {
+            face.indices.swap(1, 2);
+            face.normal = -face.normal;
+            face.distance = -face.distance;
+        }
+        faces.push(face);
+    }
+
+    for _ in 0..EPA_MAX_ITERATIONS {
+        let (closest_index, closest) = faces
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.distance.total_cmp(&b.distance))?;
+
+        let p = minkowski_support(shape_a, shape_b, closest.normal);
+        if p.w.dot(closest.normal) - closest.distance < EPA_TOLERANCE {
+            return Some(epa_result(&vertices, &faces[closest_index]));
+        }
+
+        // 删除新点可见的面，收集地平线边（只属于一个被删面的边）
+        let new_index = vertices.len();
+        vertices.push(p);
+        let mut horizon: Vec<(usize, usize)> = Vec::new();
+        faces.retain(|face| {
+            if face.normal.dot(p.w - vertices[face.indices[0]].w) <= 0.0 {
+                return true;
+            }
+            for e in 0..3 {
+                let edge = (face.indices[e], face.indices[(e + 1) % 3]);
+                if let Some(pos) = horizon
+                    .iter()
+                    .position(|&(a, b)| a == edge.1 && b == edge.0)
+                {
+                    horizon.swap_remove(pos);
+                } else {
+                    horizon.push(edge);
+                }
+            }
+            false
+        });
+
+        for (a, b) in horizon {
+            if let Some(face) = make_face(&vertices, [a, b, new_index]) {
+                faces.push(face);
+            }
+        }
+        if faces.is_empty() {
+            return None;
+        }
+    }
+
+    // 迭代次数用尽时取当前最近的面
+    let closest = faces
+        .iter()
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))?;
+    Some(epa_result(&vertices, closest))
+}
+
+fn epa_result(vertices: &[SupportPoint], face: &EpaFace) -> CollisionInfo {
+    let [i, j, k] = face.indices;
+    let projected = face.normal * face.distance;
+    let (l0, l1, l2) = barycentric(projected, vertices[i].w, vertices[j].w, vertices[k].w);
+    let point_a = vertices[i].a * l0 + vertices[j].a * l1 + vertices[k].a * l2;
+    let point_b = vertices[i].b * l0 + vertices[j].b * l1 + vertices[k].b * l2;
+
+    CollisionInfo {
+        contact_point: (point_a + point_b) / 2.0,
+        penetration_depth: face.distance.max(0.0),
+        // 面法线指向 A - B 的外侧，A 沿其反方向移动即可分离
+        normal: -face.normal,
+        feature: 0,
+    }
+}
+
+fn barycentric(
+    p: Vector3<f32>,
+    a: Vector3<f32>,
+    b: Vector3<f32>,
+    c: Vector3<f32>,
+) -> (f32, f32, f32) {
+    let v0 = b - a;
+    let v1 = c - a;
+    let v2 = p - a;
+    let d00 = v0.dot(v0);
+    let d01 = v0.dot(v1);
+    let d11 = v1.dot(v1);
+    let d20 = v2.dot(v0);
+    let d21 = v2.dot(v1);
+    let denom = d00 * d11 - d01 * d01;
+    if denom.abs() < 1e-12 {
+        return (1.0, 0.0, 0.0);
+    }
+    let v = (d11 * d20 - d01 * d21) / denom;
+    let w = (d00 * d21 - d01 * d20) / denom;
+    (1.0 - v - w, v, w)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::collision::collide_box_box;
+
+    fn sphere(x: f32, y: f32, z: f32, radius: f32) -> Sphere {
+        Sphere {
+            center: Vector3::new(x, y, z),
+            radius,
+        }
+    }
+
+    #[test]
+    fn test_gjk_distance_between_spheres() {
+        let points = closest_points(&sphere(0.0, 0.0, 0.0, 1.0), &sphere(3.0, 0.0, 0.0, 0.5))
+            .expect("spheres are separated");
+        assert!((points.distance - 1.5).abs() < 1e-3);
+        assert!((points.point_a - Vector3::new(1.0, 0.0, 0.0)).magnitude() < 1e-2);
+        assert!((points.point_b - Vector3::new(2.5, 0.0, 0.0)).magnitude() < 1e-2);
+
+        assert!(closest_points(&sphere(0.0, 0.0, 0.0, 1.0), &sphere(1.5, 0.0, 0.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn test_epa_matches_analytic_penetration() {
+        // 球与球：穿透深度与法线
+        let info = collide_convex(&sphere(0.0, 1.5, 0.0, 1.0), &sphere(0.0, 0.0, 0.0, 1.0))
+            .expect("spheres overlap");
+        assert!((info.penetration_depth - 0.5).abs() < 1e-2);
+        assert!((info.normal - Vector3::unit_y()).magnitude() < 1e-2);
+
+        // 盒与盒：与分离轴测试结果一致
+        let half = Vector3::new(0.5, 0.5, 0.5);
+        let lower = Obb::new(half, &Transform::identity());
+        let upper = Obb::new(half, &Transform::translation(0.2, 0.9, 0.1));
+        let info = collide_convex(&upper, &lower).expect("boxes overlap");
+        let sat = collide_box_box(&upper, &lower).unwrap();
+        assert!((info.penetration_depth - sat.points[0].penetration_depth).abs() < 1e-3);
+        assert!((info.normal - sat.points[0].normal).magnitude() < 1e-3);
+    }
+
+    #[test]
+    fn test_hull_support_with_transform() {
+        let hull = ConvexHull::new(vec![
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(-1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(0.0, 0.0, 1.0), // 重复点会被去除
+        ]);
+        assert_eq!(hull.points.len(), 6);
+
+        let transform = Transform {
+            translation: Vector3::new(0.0, 2.0, 0.0),
+            rotation: Quaternion::from_angle_z(Deg(90.0)),
+            scale: Vector3::new(2.0, 1.0, 1.0),
+        };
+        let world = TransformedHull {
+            hull: &hull,
+            transform: &transform,
+        };
+        // 局部 x 方向缩放为 2，旋转后指向世界 +y
+        let top = world.support(Vector3::unit_y());
+        assert!((top - Vector3::new(0.0, 4.0, 0.0)).magnitude() < 1e-5);
+
+        // 八面体与球体相交
+        let info = collide_convex(&world, &sphere(0.0, 4.5, 0.0, 0.6)).expect("overlap");
+        assert!((info.penetration_depth - 0.1).abs() < 1e-2);
+        assert!((info.normal + Vector3::unit_y()).magnitude() < 1e-2);
+    }
+}
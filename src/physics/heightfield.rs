@@ -0,0 +1,311 @@
+use crate::common::Transform;
+use crate::physics::aabb::Aabb;
+use crate::physics::collision::ContactManifold;
+use crate::physics::shape::Shape;
+use crate::physics::trimesh::{
+    collide_triangle_set, edge_is_active, feature_on_active_edge, Triangle, TriangleSet,
+};
+use cgmath::*;
+
+/// 高度场的网格视图，对应 `Shape::HeightField` 的各字段
+///
+/// 高度按行优先存放，共 rows × cols 个采样点；第 r 行第 c 列的采样点位于局部坐标
+/// ((c - (cols - 1) / 2) · scale.x, height · scale.y, (r - (rows - 1) / 2) · scale.z)，
+/// 即网格在 XZ 平面上以原点为中心。每个格子沿对角线分成两个三角形，法线朝 +Y。
+/// 渲染用的网格（`resource::generate_heightfield_model`）使用同样的布局与三角划分。
+#[derive(Clone, Copy)]
+pub struct HeightGrid<'a> {
+    pub rows: usize,
+    pub cols: usize,
+    pub heights: &'a [f32],
+    pub scale: Vector3<f32>,
+}
+
+impl<'a> HeightGrid<'a> {
+    /// 检查高度场参数：至少 2×2 个采样点、高度数量为 rows × cols、格子边长为正
+    pub fn is_valid(rows: usize, cols: usize, heights: &[f32], scale: Vector3<f32>) -> bool {
+        rows >= 2 && cols >= 2 && heights.len() == rows * cols && scale.x > 0.0 && scale.z > 0.0
+    }
+
+    pub fn new(rows: usize, cols: usize, heights: &'a [f32], scale: Vector3<f32>) -> Self {
+        assert!(
+            rows >= 2 && cols >= 2,
+            "height field needs at least 2x2 samples"
+        );
+        assert_eq!(
+            heights.len(),
+            rows * cols,
+            "height count must be rows * cols"
+        );
+        assert!(
+            scale.x > 0.0 && scale.z > 0.0,
+            "height field cell size must be positive"
+        );
+        Self {
+            rows,
+            cols,
+            heights,
+            scale,
+        }
+    }
+
+    /// 从形状中取出高度场视图，其他形状返回 None
+    ///
+    /// 参数已在创建形状或物体时检查过，这里不再重复检查。
+    pub fn from_shape(shape: &'a Shape) -> Option<Self> {
+        match shape {
+            Shape::HeightField {
+                rows,
+                cols,
+                heights,
+                scale,
+            } => Some(Self {
+                rows: *rows,
+                cols: *cols,
+                heights,
+                scale: *scale,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn height(&self, row: usize, col: usize) -> f32 {
+        self.heights[row * self.cols + col] * self.scale.y
+    }
+
+    /// 局部坐标下的采样点
+    pub fn vertex(&self, row: usize, col: usize) -> Vector3<f32> {
+        Vector3::new(
+            (col as f32 - (self.cols - 1) as f32 / 2.0) * self.scale.x,
+            self.height(row, col),
+            (row as f32 - (self.rows - 1) as f32 / 2.0) * self.scale.z,
+        )
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        (self.rows - 1) * (self.cols - 1) * 2
+    }
+
+    /// 局部坐标下整个高度场的包围盒
+    pub fn aabb(&self) -> Aabb {
+        let (low, high) = self
+            .heights
+            .iter()
+            .map(|&h| h * self.scale.y)
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), h| {
+                (low.min(h), high.max(h))
+            });
+        let corner = self.vertex(self.rows - 1, self.cols - 1);
+        Aabb::new(
+            Vector3::new(-corner.x, low, -corner.z),
+            Vector3::new(corner.x, high, corner.z),
+        )
+    }
+
+    // 三角形编号 (row * (cols - 1) + col) * 2 + k，k = 0 为格子左上半，k = 1 为右下半
+    fn cell_of(&self, index: usize) -> (usize, usize, usize) {
+        let cell = index / 2;
+        (cell / (self.cols - 1), cell % (self.cols - 1), index % 2)
+    }
+
+    fn triangle_index(&self, row: usize, col: usize, k: usize) -> usize {
+        (row * (self.cols - 1) + col) * 2 + k
+    }
+
+    // 区间 [min, max] 覆盖的格子编号范围，count 为格子数
+    fn cell_range(
+        min: f32,
+        max: f32,
+        origin: f32,
+        step: f32,
+        count: usize,
+    ) -> Option<(usize, usize)> {
+        let first = ((min - origin) / step).floor();
+        let last = ((max - origin) / step).floor();
+        if last < 0.0 || first >= count as f32 {
+            return None;
+        }
+        Some((first.max(0.0) as usize, (last as usize).min(count - 1)))
+    }
+
+    /// 三角形第 edge 条边另一侧的三角形，网格边界上的边没有邻居
+    ///
+    /// 第 0 个三角形为 (r,c)、(r+1,c)、(r,c+1)：边 0 是左边、边 1 是对角线、边 2 是上边；
+    /// 第 1 个三角形为 (r,c+1)、(r+1,c)、(r+1,c+1)：边 0 是对角线、边 1 是下边、边 2 是右边。
+    fn neighbor(&self, index: usize, edge: usize) -> Option<usize> {
+        let (row, col, k) = self.cell_of(index);
+        match (k, edge) {
+            (0, 0) => (col > 0).then(|| self.triangle_index(row, col - 1, 1)),
+            (0, 1) => Some(index + 1),
+            (0, _) => (row > 0).then(|| self.triangle_index(row - 1, col, 1)),
+            (_, 0) => Some(index - 1),
+            (_, 1) => (row + 2 < self.rows).then(|| self.triangle_index(row + 1, col, 0)),
+            _ => (col + 2 < self.cols).then(|| self.triangle_index(row, col + 1, 0)),
+        }
+    }
+}
+
+impl TriangleSet for HeightGrid<'_> {
+    fn triangle(&self, index: usize) -> Triangle {
+        let (row, col, k) = self.cell_of(index);
+        if k == 0 {
+            Triangle {
+                a: self.vertex(row, col),
+                b: self.vertex(row + 1, col),
+                c: self.vertex(row, col + 1),
+            }
+        } else {
+            Triangle {
+                a: self.vertex(row, col + 1),
+                b: self.vertex(row + 1, col),
+                c: self.vertex(row + 1, col + 1),
+            }
+        }
+    }
+
+    /// 直接由区域在 XZ 平面上的投影算出覆盖的格子，再按格子高度范围剔除
+    fn query(&self, region: &Aabb, f: &mut dyn FnMut(usize)) {
+        let origin = self.vertex(0, 0);
+        let Some((col_first, col_last)) = Self::cell_range(
+            region.min.x,
+            region.max.x,
+            origin.x,
+            self.scale.x,
+            self.cols - 1,
+        ) else {
+            return;
+        };
+        let Some((row_first, row_last)) = Self::cell_range(
+            region.min.z,
+            region.max.z,
+            origin.z,
+            self.scale.z,
+            self.rows - 1,
+        ) else {
+            return;
+        };
+
+        for row in row_first..=row_last {
+            for col in col_first..=col_last {
+                let corners = [
+                    self.height(row, col),
+                    self.height(row + 1, col),
+                    self.height(row, col + 1),
+                    self.height(row + 1, col + 1),
+                ];
+                let low = corners.iter().copied().fold(f32::INFINITY, f32::min);
+                let high = corners.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                if high < region.min.y || low > region.max.y {
+                    continue;
+                }
+                f(self.triangle_index(row, col, 0));
+                f(self.triangle_index(row, col, 1));
+            }
+        }
+    }
+
+    fn feature_is_active(&self, triangle: usize, feature: &[usize]) -> bool {
+        let local = self.triangle(triangle);
+        let edges = [0, 1, 2].map(|edge| match self.neighbor(triangle, edge) {
+            Some(other) => edge_is_active(&local, &self.triangle(other)),
+            // 网格边界上的边保持活动
+            None => true,
+        });
+        feature_on_active_edge(&edges, feature)
+    }
+}
+
+/// 检测凸形状与高度场之间的碰撞，返回的法线指向凸形状
+///
+/// 只检测形状包围盒覆盖到的格子；与三角网格相同，高度场为单面，从下方穿入的形状会被忽略。
+pub fn collide_heightfield(
+    shape: &Shape,
+    transform: &Transform,
+    grid: &HeightGrid,
+    grid_transform: &Transform,
+) -> Option<ContactManifold> {
+    collide_triangle_set(shape, transform, grid, grid_transform)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::shape::Sphere;
+
+    // 沿 X 方向起伏的 5×5 高度场，格子边长 1
+    fn wavy_heights() -> Vec<f32> {
+        (0..5)
+            .flat_map(|_| (0..5).map(|c| (c as f32 * 0.8).sin() * 0.5))
+            .collect()
+    }
+
+    #[test]
+    fn test_height_grid_layout_and_query() {
+        let heights = wavy_heights();
+        let grid = HeightGrid::new(5, 5, &heights, Vector3::new(1.0, 1.0, 1.0));
+
+        assert_eq!(grid.triangle_count(), 32);
+        assert_eq!(grid.vertex(0, 0), Vector3::new(-2.0, 0.0, -2.0));
+        for i in 0..grid.triangle_count() {
+            assert!(grid.triangle(i).normal().y > 0.0);
+        }
+
+        // 相邻三角形共享边的两端顶点
+        for i in 0..grid.triangle_count() {
+            for edge in 0..3 {
+                let Some(other) = grid.neighbor(i, edge) else {
+                    continue;
+                };
+                let (t, o) = (grid.triangle(i), grid.triangle(other));
+                let verts = [t.a, t.b, t.c];
+                let shared = [verts[edge], verts[(edge + 1) % 3]];
+                assert!(shared.iter().all(|v| [o.a, o.b, o.c].contains(v)));
+            }
+        }
+
+        // 区域只覆盖中心附近的一个格子
+        let mut hits = Vec::new();
+        let region = Aabb::new(Vector3::new(0.2, -5.0, 0.2), Vector3::new(0.8, 5.0, 0.8));
+        grid.query(&region, &mut |i| hits.push(i));
+        assert_eq!(hits.len(), 2);
+
+        // 高于整个高度场的区域不返回任何三角形
+        let mut hits = Vec::new();
+        let region = Aabb::new(Vector3::new(-1.0, 2.0, -1.0), Vector3::new(1.0, 3.0, 1.0));
+        grid.query(&region, &mut |i| hits.push(i));
+        assert!(hits.is_empty());
+    }
+
+    #[test]
+    fn test_sphere_on_flat_height_field() {
+        let heights = vec![0.0; 16];
+        let grid = HeightGrid::new(4, 4, &heights, Vector3::new(1.0, 1.0, 1.0));
+        let sphere = Shape::Sphere(Sphere {
+            center: Vector3::zero(),
+            radius: 0.5,
+        });
+
+        // 球落在格子对角线与格子边交汇的顶点上，法线仍应竖直向上
+        let transform = Transform::translation(0.5, 0.45, -0.5);
+        let manifold =
+            collide_heightfield(&sphere, &transform, &grid, &Transform::identity()).unwrap();
+        for point in &manifold.points {
+            assert!(point.normal.y > 0.999);
+            assert!((point.penetration_depth - 0.05).abs() < 1e-4);
+        }
+
+        // 高度场外的球没有接触
+        let transform = Transform::translation(3.0, 0.45, 0.0);
+        assert!(collide_heightfield(&sphere, &transform, &grid, &Transform::identity()).is_none());
+    }
+
+    #[test]
+    fn test_height_field_validation() {
+        let scale = Vector3::new(1.0, 1.0, 1.0);
+        assert!(Shape::height_field(5, 5, wavy_heights(), scale).is_some());
+        // 高度数量不符、采样点不足、格子边长为零都不能构造
+        assert!(Shape::height_field(4, 5, wavy_heights(), scale).is_none());
+        assert!(Shape::height_field(1, 4, vec![0.0; 4], scale).is_none());
+        assert!(Shape::height_field(5, 5, wavy_heights(), Vector3::new(0.0, 1.0, 1.0)).is_none());
+    }
+}
@@ -0,0 +1,191 @@
+use cgmath::*;
+
+/// 质点的运动状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotionState {
+    pub position: Vector3<f32>,
+    pub velocity: Vector3<f32>,
+}
+
+/// 数值积分接口：由当前状态和加速度函数求出一步之后的状态
+///
+/// 只负责平移运动；受约束的物体把求解器带来的速度变化作为恒定加速度与外力一起积分，
+/// 姿态始终用四元数半隐式积分。
+pub trait Integrator {
+    fn integrate(
+        &self,
+        state: MotionState,
+        acceleration: &dyn Fn(&MotionState) -> Vector3<f32>,
+        delta_time: f32,
+    ) -> MotionState;
+}
+
+/// 半隐式（辛）欧拉：先更新速度，再用新速度更新位置
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SemiImplicitEuler;
+
+impl Integrator for SemiImplicitEuler {
+    fn integrate(
+        &self,
+        state: MotionState,
+        acceleration: &dyn Fn(&MotionState) -> Vector3<f32>,
+        delta_time: f32,
+    ) -> MotionState {
+        let velocity = state.velocity + acceleration(&state) * delta_time;
+        MotionState {
+            position: state.position + velocity * delta_time,
+            velocity,
+        }
+    }
+}
+
+/// 速度 Verlet：位置用 x + v·dt + a·dt²/2，速度用前后两次加速度的平均
+///
+/// 加速度依赖速度时，末端加速度用预测速度 v + a·dt 求出。
+#[derive(Debug, Default, Clone, Copy)]
+pub struct VelocityVerlet;
+
+impl Integrator for VelocityVerlet {
+    fn integrate(
+        &self,
+        state: MotionState,
+        acceleration: &dyn Fn(&MotionState) -> Vector3<f32>,
+        delta_time: f32,
+    ) -> MotionState {
+        let a0 = acceleration(&state);
+        let position =
+            state.position + state.velocity * delta_time + a0 * (0.5 * delta_time * delta_time);
+        let a1 = acceleration(&MotionState {
+            position,
+            velocity: state.velocity + a0 * delta_time,
+        });
+        MotionState {
+            position,
+            velocity: state.velocity + (a0 + a1) * (0.5 * delta_time),
+        }
+    }
+}
+
+/// 经典四阶 Runge-Kutta
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RungeKutta4;
+
+impl Integrator for RungeKutta4 {
+    fn integrate(
+        &self,
+        state: MotionState,
+        acceleration: &dyn Fn(&MotionState) -> Vector3<f32>,
+        delta_time: f32,
+    ) -> MotionState {
+        // 每个阶段的导数为 (dx, dv) = (v, a)
+        let derivative = |offset: (Vector3<f32>, Vector3<f32>), h: f32| {
+            let probe = MotionState {
+                position: state.position + offset.0 * h,
+                velocity: state.velocity + offset.1 * h,
+            };
+            (probe.velocity, acceleration(&probe))
+        };
+        let k1 = derivative((Vector3::zero(), Vector3::zero()), 0.0);
+        let k2 = derivative(k1, 0.5 * delta_time);
+        let k3 = derivative(k2, 0.5 * delta_time);
+        let k4 = derivative(k3, delta_time);
+
+        let weight = delta_time / 6.0;
+        MotionState {
+            position: state.position + (k1.0 + (k2.0 + k3.0) * 2.0 + k4.0) * weight,
+            velocity: state.velocity + (k1.1 + (k2.1 + k3.1) * 2.0 + k4.1) * weight,
+        }
+    }
+}
+
+/// 可选的积分方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegratorKind {
+    #[default]
+    SemiImplicitEuler,
+    VelocityVerlet,
+    RungeKutta4,
+}
+
+impl IntegratorKind {
+    pub const ALL: [IntegratorKind; 3] = [
+        IntegratorKind::SemiImplicitEuler,
+        IntegratorKind::VelocityVerlet,
+        IntegratorKind::RungeKutta4,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            IntegratorKind::SemiImplicitEuler => "Semi-implicit Euler",
+            IntegratorKind::VelocityVerlet => "Velocity Verlet",
+            IntegratorKind::RungeKutta4 => "Runge-Kutta 4",
+        }
+    }
+
+    /// 积分器都不带状态，直接返回共享实例
+    pub fn integrator(&self) -> &'static dyn Integrator {
+        match self {
+            IntegratorKind::SemiImplicitEuler => &SemiImplicitEuler,
+            IntegratorKind::VelocityVerlet => &VelocityVerlet,
+            IntegratorKind::RungeKutta4 => &RungeKutta4,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 单位质量、单位刚度的弹簧振子积分一个周期后的相对能量误差
+    fn oscillator_energy_drift(kind: IntegratorKind, steps: usize) -> f32 {
+        let delta_time = 2.0 * std::f32::consts::PI / steps as f32;
+        let energy =
+            |state: &MotionState| 0.5 * (state.velocity.magnitude2() + state.position.magnitude2());
+        let mut state = MotionState {
+            position: Vector3::unit_x(),
+            velocity: Vector3::zero(),
+        };
+        let initial = energy(&state);
+        for _ in 0..steps {
+            state = kind
+                .integrator()
+                .integrate(state, &|state| -state.position, delta_time);
+        }
+        (energy(&state) - initial).abs() / initial
+    }
+
+    #[test]
+    fn test_integrators_energy_drift() {
+        let euler = oscillator_energy_drift(IntegratorKind::SemiImplicitEuler, 60);
+        let verlet = oscillator_energy_drift(IntegratorKind::VelocityVerlet, 60);
+        let rk4 = oscillator_energy_drift(IntegratorKind::RungeKutta4, 60);
+        assert!(euler < 0.1, "euler drift {}", euler);
+        assert!(verlet < euler, "verlet drift {} >= {}", verlet, euler);
+        assert!(rk4 < 1e-4, "rk4 drift {}", rk4);
+    }
+
+    #[test]
+    fn test_constant_acceleration() {
+        let gravity = Vector3::new(0.0, -9.8, 0.0);
+        let start = MotionState {
+            position: Vector3::zero(),
+            velocity: Vector3::new(1.0, 5.0, 0.0),
+        };
+        let delta_time = 0.1;
+        let exact = start.position
+            + start.velocity * delta_time
+            + gravity * (0.5 * delta_time * delta_time);
+
+        for kind in IntegratorKind::ALL {
+            let state = kind.integrator().integrate(start, &|_| gravity, delta_time);
+            assert!((state.velocity - (start.velocity + gravity * delta_time)).magnitude() < 1e-5);
+            // 半隐式欧拉每步多走 g·dt²/2，其余方法在匀加速下精确
+            let error = (state.position - exact).magnitude();
+            if kind == IntegratorKind::SemiImplicitEuler {
+                assert!((error - 0.5 * 9.8 * delta_time * delta_time).abs() < 1e-5);
+            } else {
+                assert!(error < 1e-5, "{} error {}", kind.name(), error);
+            }
+        }
+    }
+}
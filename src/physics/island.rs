@@ -0,0 +1,134 @@
+use crate::physics::phymgr::{BodyHandle, BodySet};
+use crate::physics::shape::PhysicsState;
+
+/// 并查集，按路径压缩 + 按秩合并
+pub struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+        }
+    }
+
+    pub fn find(&mut self, mut index: usize) -> usize {
+        let mut root = index;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        while self.parent[index] != root {
+            let next = self.parent[index];
+            self.parent[index] = root;
+            index = next;
+        }
+        root
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        match self.rank[a].cmp(&self.rank[b]) {
+            std::cmp::Ordering::Less => self.parent[a] = b,
+            std::cmp::Ordering::Greater => self.parent[b] = a,
+            std::cmp::Ordering::Equal => {
+                self.parent[b] = a;
+                self.rank[a] += 1;
+            }
+        }
+    }
+}
+
+/// 按接触和关节把动态物体划分为互不相连的岛，每个岛可以整体休眠或唤醒
+///
+/// 静态物体不传递连通性：放在同一地面上的两个物体属于不同的岛。
+/// 没有任何连接的动态物体单独成岛。
+pub fn build_islands(
+    bodies: &BodySet,
+    links: impl IntoIterator<Item = (BodyHandle, BodyHandle)>,
+) -> Vec<Vec<BodyHandle>> {
+    let dynamic = |handle: BodyHandle| {
+        bodies
+            .get(handle)
+            .is_some_and(|body| matches!(body.state, PhysicsState::Dynamic))
+    };
+    let len = bodies
+        .iter()
+        .map(|(handle, _)| handle.index() + 1)
+        .max()
+        .unwrap_or(0);
+
+    let mut sets = UnionFind::new(len);
+    for (handle1, handle2) in links {
+        if dynamic(handle1) && dynamic(handle2) {
+            sets.union(handle1.index(), handle2.index());
+        }
+    }
+
+    // 根节点 -> 岛的序号
+    let mut island_of_root = vec![usize::MAX; len];
+    let mut islands: Vec<Vec<BodyHandle>> = Vec::new();
+    for (handle, _) in bodies.iter().filter(|(handle, _)| dynamic(*handle)) {
+        let root = sets.find(handle.index());
+        if island_of_root[root] == usize::MAX {
+            island_of_root[root] = islands.len();
+            islands.push(Vec::new());
+        }
+        islands[island_of_root[root]].push(handle);
+    }
+    islands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Transform;
+    use crate::physics::shape::{PhysicsBody, Shape, Sphere};
+    use cgmath::*;
+
+    #[test]
+    fn test_build_islands() {
+        let sphere = Shape::Sphere(Sphere {
+            center: Vector3::zero(),
+            radius: 0.5,
+        });
+        let mut bodies = BodySet::default();
+        let ground = bodies.insert(PhysicsBody::new_static(
+            sphere.clone(),
+            Transform::identity(),
+        ));
+        let handles = (0..5)
+            .map(|_| {
+                bodies.insert(PhysicsBody::new_dynamic(
+                    sphere.clone(),
+                    Transform::identity(),
+                    1.0,
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        // 0-1-2 相连，3 和 4 只与地面接触
+        let links = [
+            (handles[0], handles[1]),
+            (handles[2], handles[1]),
+            (handles[3], ground),
+            (ground, handles[4]),
+        ];
+        let mut islands = build_islands(&bodies, links);
+        islands.iter_mut().for_each(|island| island.sort());
+        islands.sort();
+        assert_eq!(
+            islands,
+            vec![
+                vec![handles[0], handles[1], handles[2]],
+                vec![handles[3]],
+                vec![handles[4]],
+            ]
+        );
+    }
+}
@@ -0,0 +1,909 @@
+use crate::common::Transform;
+use crate::physics::collision::any_perpendicular;
+use crate::physics::phymgr::{BodyHandle, BodySet};
+use crate::physics::shape::{skew, PhysicsBody, Shape, Sphere};
+use cgmath::*;
+
+/// 单次位置迭代中关节的最大角度修正量（弧度）
+pub const MAX_ANGULAR_CORRECTION: f32 = 8.0 * std::f32::consts::PI / 180.0;
+
+/// 关节句柄：槽位索引 + 代数，与物理体句柄相同
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JointHandle {
+    index: u32,
+    generation: u32,
+}
+
+/// 铰链电机：驱动相对转速趋向 speed，力矩不超过 max_torque
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointMotor {
+    pub speed: f32, // 目标角速度 (rad/s)
+    pub max_torque: f32,
+}
+
+/// 关节类型
+///
+/// 铰链的角度、滑轨的位移都是 body1 相对 body2 的量，创建时为零；
+/// limits 为 (下限, 上限)。距离约束和弹簧连接两个不同的锚点，作用在两锚点的连线上。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JointKind {
+    /// 球窝关节：两锚点重合，转动不受限
+    BallSocket,
+    /// 铰链：两锚点重合，只能绕轴转动
+    Hinge {
+        limits: Option<(f32, f32)>,
+        motor: Option<JointMotor>,
+    },
+    /// 滑轨：相对姿态固定，只能沿轴平移
+    Slider { limits: Option<(f32, f32)> },
+    /// 固定关节：相对位置与姿态都固定
+    Fixed,
+    /// 距离约束：两锚点间的距离保持在 [min_length, max_length] 内，min_length 为零时相当于绳子
+    Distance { min_length: f32, max_length: f32 },
+    /// 弹簧阻尼器：拉力为 stiffness · (长度 - rest_length) + damping · 伸长速度，
+    /// 按隐式软约束求解，刚度很大时也不会发散
+    Spring {
+        rest_length: f32,
+        stiffness: f32,
+        damping: f32,
+    },
+}
+
+// 累积冲量，跨帧保留用于预热
+#[derive(Debug, Clone, Copy)]
+struct JointImpulse {
+    linear: Vector3<f32>,
+    angular: Vector3<f32>,
+    limit: [f32; 2], // 下限、上限
+    motor: f32,
+    spring: f32,
+}
+
+impl Default for JointImpulse {
+    fn default() -> Self {
+        Self {
+            linear: Vector3::zero(),
+            angular: Vector3::zero(),
+            limit: [0.0; 2],
+            motor: 0.0,
+            spring: 0.0,
+        }
+    }
+}
+
+// 每步 prepare 时计算、速度迭代期间不变的量
+#[derive(Debug, Clone, Copy)]
+struct JointFrame {
+    r1: Vector3<f32>, // 锚点相对质心的偏移
+    r2: Vector3<f32>,
+    axis: Vector3<f32>,      // 铰链或滑轨的世界轴向，距离约束和弹簧为锚点连线方向
+    point_k: Matrix3<f32>,   // 锚点相对速度对冲量的响应
+    angular_k: Matrix3<f32>, // 相对角速度对角冲量的响应
+    position: f32,           // 铰链角度、滑轨位移或锚点距离
+    gamma: f32,              // 弹簧软约束的柔度
+    bias: f32,               // 弹簧软约束的目标速度偏置
+    delta_time: f32,
+}
+
+/// 两个物体（或物体与世界）之间的关节约束
+///
+/// 冲量 `+P` 作用于 body1，`-P` 作用于 body2；body2 为 None 时 body1 与世界相连，
+/// 此时 body2 一侧的锚点和轴向都是世界坐标。
+#[derive(Debug, Clone)]
+pub struct Joint {
+    pub body1: BodyHandle,
+    pub body2: Option<BodyHandle>,
+    pub kind: JointKind,
+    pub collide_connected: bool, // 相连的两个物体之间是否仍做碰撞检测
+    local_anchor1: Vector3<f32>,
+    local_anchor2: Vector3<f32>,
+    local_axis1: Vector3<f32>,
+    local_axis2: Vector3<f32>,
+    reference: Quaternion<f32>, // 创建时 body1 相对 body2 的姿态
+    impulse: JointImpulse,
+    frame: Option<JointFrame>,
+}
+
+impl Joint {
+    /// 在世界坐标 anchor 处连接两个物体，axis 为铰链转轴或滑轨方向（球窝和固定关节忽略）
+    pub fn new(
+        kind: JointKind,
+        body1: (BodyHandle, &PhysicsBody),
+        body2: Option<(BodyHandle, &PhysicsBody)>,
+        anchor: Vector3<f32>,
+        axis: Vector3<f32>,
+    ) -> Self {
+        Self::build(kind, body1, body2, (anchor, anchor), axis)
+    }
+
+    /// 用两个世界坐标锚点连接两个物体，用于距离约束和弹簧
+    pub fn with_anchors(
+        kind: JointKind,
+        body1: (BodyHandle, &PhysicsBody),
+        body2: Option<(BodyHandle, &PhysicsBody)>,
+        anchor1: Vector3<f32>,
+        anchor2: Vector3<f32>,
+    ) -> Self {
+        let offset = anchor1 - anchor2;
+        let axis = if offset.magnitude2() > 0.0 {
+            offset
+        } else {
+            Vector3::unit_y()
+        };
+        Self::build(kind, body1, body2, (anchor1, anchor2), axis)
+    }
+
+    fn build(
+        kind: JointKind,
+        body1: (BodyHandle, &PhysicsBody),
+        body2: Option<(BodyHandle, &PhysicsBody)>,
+        anchors: (Vector3<f32>, Vector3<f32>),
+        axis: Vector3<f32>,
+    ) -> Self {
+        let ground = world_body();
+        let transform2 = body2.map_or(&ground.transform, |(_, body)| &body.transform);
+        let transform1 = &body1.1.transform;
+        let axis = axis.normalize();
+        Self {
+            body1: body1.0,
+            body2: body2.map(|(handle, _)| handle),
+            kind,
+            collide_connected: false,
+            local_anchor1: transform1.inverse_transform_point(anchors.0),
+            local_anchor2: transform2.inverse_transform_point(anchors.1),
+            local_axis1: transform1.rotation.invert() * axis,
+            local_axis2: transform2.rotation.invert() * axis,
+            reference: transform2.rotation.invert() * transform1.rotation,
+            impulse: JointImpulse::default(),
+            frame: None,
+        }
+    }
+
+    /// 两个锚点当前的世界坐标，约束满足时两者重合（滑轨沿轴方向、距离约束和弹簧除外）
+    pub fn world_anchors(&self, bodies: &BodySet) -> Option<(Vector3<f32>, Vector3<f32>)> {
+        let body1 = bodies.get(self.body1)?;
+        let anchor2 = match self.body2 {
+            Some(handle) => bodies
+                .get(handle)?
+                .transform
+                .transform_point(self.local_anchor2),
+            None => self.local_anchor2,
+        };
+        Some((body1.transform.transform_point(self.local_anchor1), anchor2))
+    }
+
+    /// 是否连接了给定的物体
+    pub fn connects(&self, handle: BodyHandle) -> bool {
+        self.body1 == handle || self.body2 == Some(handle)
+    }
+
+    // 约束作用的世界轴向
+    fn world_axis(&self, body1: &PhysicsBody, body2: &PhysicsBody) -> Vector3<f32> {
+        match self.kind {
+            JointKind::Hinge { .. } => body1.transform.rotation * self.local_axis1,
+            JointKind::Distance { .. } | JointKind::Spring { .. } => {
+                let (p1, p2) = self.anchors(body1, body2);
+                let offset = p1 - p2;
+                if offset.magnitude2() > 1e-12 {
+                    offset.normalize()
+                } else {
+                    body2.transform.rotation * self.local_axis2
+                }
+            }
+            _ => body2.transform.rotation * self.local_axis2,
+        }
+    }
+
+    // 铰链角度、滑轨位移或锚点距离
+    fn joint_position(&self, body1: &PhysicsBody, body2: &PhysicsBody) -> f32 {
+        match self.kind {
+            JointKind::Hinge { .. } => {
+                let axis = self.world_axis(body1, body2);
+                let mut error =
+                    body1.transform.rotation * (body2.transform.rotation * self.reference).invert();
+                if error.s < 0.0 {
+                    error = -error;
+                }
+                2.0 * error.v.dot(axis).atan2(error.s)
+            }
+            JointKind::Slider { .. } | JointKind::Distance { .. } | JointKind::Spring { .. } => {
+                let (p1, p2) = self.anchors(body1, body2);
+                (p1 - p2).dot(self.world_axis(body1, body2))
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn anchors(&self, body1: &PhysicsBody, body2: &PhysicsBody) -> (Vector3<f32>, Vector3<f32>) {
+        (
+            body1.transform.transform_point(self.local_anchor1),
+            body2.transform.transform_point(self.local_anchor2),
+        )
+    }
+
+    fn limits(&self) -> Option<(f32, f32)> {
+        match self.kind {
+            JointKind::Hinge { limits, .. } | JointKind::Slider { limits } => limits,
+            JointKind::Distance {
+                min_length,
+                max_length,
+            } => Some((min_length, max_length)),
+            _ => None,
+        }
+    }
+
+    /// 计算本步的约束参数，并按设置施加上一步的累积冲量
+    pub fn prepare(
+        &mut self,
+        body1: &mut PhysicsBody,
+        body2: &mut PhysicsBody,
+        warm_starting: bool,
+        delta_time: f32,
+    ) {
+        let (p1, p2) = self.anchors(body1, body2);
+        let r1 = p1 - body1.position();
+        let r2 = p2 - body2.position();
+        let axis = self.world_axis(body1, body2);
+        let position = self.joint_position(body1, body2);
+        // 软约束参数：γ = 1 / (h (c + h k))，偏置速度 = C h k γ
+        let (gamma, bias) = match self.kind {
+            JointKind::Spring {
+                rest_length,
+                stiffness,
+                damping,
+            } if damping + delta_time * stiffness > 0.0 => {
+                let gamma = 1.0 / (delta_time * (damping + delta_time * stiffness));
+                (
+                    gamma,
+                    (position - rest_length) * delta_time * stiffness * gamma,
+                )
+            }
+            _ => (0.0, 0.0),
+        };
+        let frame = JointFrame {
+            r1,
+            r2,
+            axis,
+            point_k: point_mass_matrix(body1, body2, r1, r2),
+            angular_k: body1.inv_inertia_world() + body2.inv_inertia_world(),
+            position,
+            gamma,
+            bias,
+            delta_time,
+        };
+        self.frame = Some(frame);
+
+        if !warm_starting {
+            self.impulse = JointImpulse::default();
+        }
+        if self.limits().is_none() {
+            self.impulse.limit = [0.0; 2];
+        }
+        if !matches!(self.kind, JointKind::Hinge { motor: Some(_), .. }) {
+            self.impulse.motor = 0.0;
+        }
+        if gamma == 0.0 {
+            self.impulse.spring = 0.0;
+        }
+
+        let impulse = self.impulse;
+        let axial = impulse.limit[0] - impulse.limit[1];
+        let (linear, angular) = match self.kind {
+            JointKind::Hinge { .. } => (
+                impulse.linear,
+                impulse.angular + axis * (axial + impulse.motor),
+            ),
+            JointKind::Slider { .. } | JointKind::Distance { .. } | JointKind::Spring { .. } => (
+                impulse.linear + axis * (axial + impulse.spring),
+                impulse.angular,
+            ),
+            _ => (impulse.linear, impulse.angular),
+        };
+        apply_impulses(body1, body2, &frame, linear, angular);
+    }
+
+    /// 一次速度迭代
+    pub fn solve_velocity(&mut self, body1: &mut PhysicsBody, body2: &mut PhysicsBody) {
+        let Some(frame) = self.frame else {
+            return;
+        };
+        let axis = frame.axis;
+        let relative_velocity = |body1: &PhysicsBody, body2: &PhysicsBody| {
+            body1.velocity + body1.angular_velocity.cross(frame.r1)
+                - body2.velocity
+                - body2.angular_velocity.cross(frame.r2)
+        };
+        let relative_spin = |body1: &PhysicsBody, body2: &PhysicsBody| {
+            body1.angular_velocity - body2.angular_velocity
+        };
+
+        // 电机：相对转速趋向目标值
+        if let JointKind::Hinge {
+            motor: Some(motor), ..
+        } = self.kind
+        {
+            let rate = relative_spin(body1, body2).dot(axis);
+            let lambda = (motor.speed - rate) * mass_from_inv(axis.dot(frame.angular_k * axis));
+            let max_impulse = motor.max_torque * frame.delta_time;
+            let old_impulse = self.impulse.motor;
+            self.impulse.motor = (old_impulse + lambda).clamp(-max_impulse, max_impulse);
+            let angular = axis * (self.impulse.motor - old_impulse);
+            apply_impulses(body1, body2, &frame, Vector3::zero(), angular);
+        }
+
+        // 弹簧：软约束，柔度 γ 使冲量随累积量衰减，相当于隐式积分弹簧力
+        if frame.gamma > 0.0 {
+            let rate = relative_velocity(body1, body2).dot(axis);
+            let mass = mass_from_inv(axis.dot(frame.point_k * axis) + frame.gamma);
+            let lambda = -mass * (rate + frame.bias + frame.gamma * self.impulse.spring);
+            self.impulse.spring += lambda;
+            apply_impulses(body1, body2, &frame, axis * lambda, Vector3::zero());
+        }
+
+        // 限位：超出前允许以剩余距离 / dt 的速度接近，之后累积冲量只能推回
+        if let Some((lower, upper)) = self.limits() {
+            let is_hinge = matches!(self.kind, JointKind::Hinge { .. });
+            let inv_mass = if is_hinge {
+                axis.dot(frame.angular_k * axis)
+            } else {
+                axis.dot(frame.point_k * axis)
+            };
+            for (side, distance, sign) in [
+                (0, frame.position - lower, 1.0),
+                (1, upper - frame.position, -1.0),
+            ] {
+                let rate = if is_hinge {
+                    relative_spin(body1, body2).dot(axis)
+                } else {
+                    relative_velocity(body1, body2).dot(axis)
+                } * sign;
+                let lambda =
+                    -mass_from_inv(inv_mass) * (rate + distance.max(0.0) / frame.delta_time);
+                let old_impulse = self.impulse.limit[side];
+                self.impulse.limit[side] = (old_impulse + lambda).max(0.0);
+                let impulse = axis * (sign * (self.impulse.limit[side] - old_impulse));
+                if is_hinge {
+                    apply_impulses(body1, body2, &frame, Vector3::zero(), impulse);
+                } else {
+                    apply_impulses(body1, body2, &frame, impulse, Vector3::zero());
+                }
+            }
+        }
+
+        // 转动约束
+        let angular = match self.kind {
+            JointKind::Hinge { .. } => {
+                solve_perpendicular(frame.angular_k, axis, -relative_spin(body1, body2))
+            }
+            JointKind::Slider { .. } | JointKind::Fixed => {
+                solve(frame.angular_k, -relative_spin(body1, body2))
+            }
+            JointKind::BallSocket | JointKind::Distance { .. } | JointKind::Spring { .. } => {
+                Vector3::zero()
+            }
+        };
+        self.impulse.angular += angular;
+        apply_impulses(body1, body2, &frame, Vector3::zero(), angular);
+
+        // 锚点约束
+        let linear = match self.kind {
+            JointKind::Slider { .. } => {
+                solve_perpendicular(frame.point_k, axis, -relative_velocity(body1, body2))
+            }
+            JointKind::Distance { .. } | JointKind::Spring { .. } => return,
+            _ => solve(frame.point_k, -relative_velocity(body1, body2)),
+        };
+        self.impulse.linear += linear;
+        apply_impulses(body1, body2, &frame, linear, Vector3::zero());
+    }
+
+    /// 一次位置迭代：直接修正位置与姿态，不改变速度
+    pub fn solve_position(&self, body1: &mut PhysicsBody, body2: &mut PhysicsBody) {
+        if body1.inv_mass + body2.inv_mass <= 0.0 {
+            return;
+        }
+
+        // 限位
+        if let Some((lower, upper)) = self.limits() {
+            let position = self.joint_position(body1, body2);
+            let error = if position < lower {
+                position - lower
+            } else if position > upper {
+                position - upper
+            } else {
+                0.0
+            };
+            if error != 0.0 {
+                let (p1, p2) = self.anchors(body1, body2);
+                let (r1, r2) = (p1 - body1.position(), p2 - body2.position());
+                let axis = self.world_axis(body1, body2);
+                if let JointKind::Hinge { .. } = self.kind {
+                    let k = body1.inv_inertia_world() + body2.inv_inertia_world();
+                    let error = error.clamp(-MAX_ANGULAR_CORRECTION, MAX_ANGULAR_CORRECTION);
+                    let angular = axis * (-error * mass_from_inv(axis.dot(k * axis)));
+                    correct_positions(body1, body2, r1, r2, Vector3::zero(), angular);
+                } else {
+                    let k = point_mass_matrix(body1, body2, r1, r2);
+                    let linear = axis * (-error * mass_from_inv(axis.dot(k * axis)));
+                    correct_positions(body1, body2, r1, r2, linear, Vector3::zero());
+                }
+            }
+        }
+
+        // 姿态
+        let angular_k = body1.inv_inertia_world() + body2.inv_inertia_world();
+        let angular = match self.kind {
+            JointKind::Hinge { .. } => {
+                let axis1 = body1.transform.rotation * self.local_axis1;
+                let axis2 = body2.transform.rotation * self.local_axis2;
+                solve_perpendicular(angular_k, axis1, clamp_angle(axis1.cross(axis2)))
+            }
+            JointKind::Slider { .. } | JointKind::Fixed => {
+                let mut error =
+                    body1.transform.rotation * (body2.transform.rotation * self.reference).invert();
+                if error.s < 0.0 {
+                    error = -error;
+                }
+                solve(angular_k, -clamp_angle(error.v * 2.0))
+            }
+            JointKind::BallSocket | JointKind::Distance { .. } | JointKind::Spring { .. } => {
+                Vector3::zero()
+            }
+        };
+        if angular != Vector3::zero() {
+            correct_positions(
+                body1,
+                body2,
+                Vector3::zero(),
+                Vector3::zero(),
+                Vector3::zero(),
+                angular,
+            );
+        }
+
+        // 锚点；距离约束只修正超限部分，弹簧不做位置修正
+        if let JointKind::Distance { .. } | JointKind::Spring { .. } = self.kind {
+            return;
+        }
+        let (p1, p2) = self.anchors(body1, body2);
+        let (r1, r2) = (p1 - body1.position(), p2 - body2.position());
+        let k = point_mass_matrix(body1, body2, r1, r2);
+        let linear = match self.kind {
+            JointKind::Slider { .. } => {
+                solve_perpendicular(k, self.world_axis(body1, body2), p2 - p1)
+            }
+            _ => solve(k, p2 - p1),
+        };
+        correct_positions(body1, body2, r1, r2, linear, Vector3::zero());
+    }
+}
+
+/// 关节集合：按句柄存取关节
+#[derive(Default)]
+pub struct JointSet {
+    slots: Vec<(u32, Option<Joint>)>, // (代数, 关节)
+    free_slots: Vec<u32>,
+}
+
+impl JointSet {
+    pub fn insert(&mut self, joint: Joint) -> JointHandle {
+        if let Some(index) = self.free_slots.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.1 = Some(joint);
+            JointHandle {
+                index,
+                generation: slot.0,
+            }
+        } else {
+            self.slots.push((0, Some(joint)));
+            JointHandle {
+                index: (self.slots.len() - 1) as u32,
+                generation: 0,
+            }
+        }
+    }
+
+    pub fn remove(&mut self, handle: JointHandle) -> Option<Joint> {
+        let slot = self.slots.get_mut(handle.index as usize)?;
+        if slot.0 != handle.generation {
+            return None;
+        }
+
+        let joint = slot.1.take()?;
+        slot.0 = slot.0.wrapping_add(1);
+        self.free_slots.push(handle.index);
+        Some(joint)
+    }
+
+    pub fn get(&self, handle: JointHandle) -> Option<&Joint> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.0 == handle.generation)
+            .and_then(|slot| slot.1.as_ref())
+    }
+
+    pub fn get_mut(&mut self, handle: JointHandle) -> Option<&mut Joint> {
+        self.slots
+            .get_mut(handle.index as usize)
+            .filter(|slot| slot.0 == handle.generation)
+            .and_then(|slot| slot.1.as_mut())
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free_slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (JointHandle, &Joint)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.1.as_ref().map(|joint| {
+                (
+                    JointHandle {
+                        index: index as u32,
+                        generation: slot.0,
+                    },
+                    joint,
+                )
+            })
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Joint> {
+        self.slots.iter_mut().filter_map(|slot| slot.1.as_mut())
+    }
+
+    /// 对每个关节及其两端物体调用 f，与世界相连的关节 body2 为一个静态的占位物体；
+    /// 两端休眠的关节被跳过
+    pub fn for_each_with_bodies(
+        &mut self,
+        bodies: &mut BodySet,
+        mut f: impl FnMut(&mut Joint, &mut PhysicsBody, &mut PhysicsBody),
+    ) {
+        let mut ground = world_body();
+        for joint in self.iter_mut() {
+            let pair = match joint.body2 {
+                Some(body2) => bodies.get_pair_mut(joint.body1, body2),
+                None => bodies
+                    .get_mut(joint.body1)
+                    .map(|body1| (body1, &mut ground)),
+            };
+            // 关节两端属于同一个岛，休眠时一起跳过
+            if let Some((body1, body2)) = pair {
+                if body1.is_sleeping() || body2.is_sleeping() {
+                    continue;
+                }
+                f(joint, body1, body2);
+            }
+        }
+    }
+}
+
+// 代表世界的静态物体，位于原点且无旋转
+fn world_body() -> PhysicsBody {
+    PhysicsBody::new_static(
+        Shape::Sphere(Sphere {
+            center: Vector3::zero(),
+            radius: 0.0,
+        }),
+        Transform::identity(),
+    )
+}
+
+fn mass_from_inv(k: f32) -> f32 {
+    if k > 0.0 {
+        1.0 / k
+    } else {
+        0.0
+    }
+}
+
+// K = (1/m1 + 1/m2) E + [r1]ᵀ I1⁻¹ [r1] + [r2]ᵀ I2⁻¹ [r2]
+fn point_mass_matrix(
+    body1: &PhysicsBody,
+    body2: &PhysicsBody,
+    r1: Vector3<f32>,
+    r2: Vector3<f32>,
+) -> Matrix3<f32> {
+    let (skew1, skew2) = (skew(r1), skew(r2));
+    Matrix3::identity() * (body1.inv_mass + body2.inv_mass)
+        + skew1.transpose() * body1.inv_inertia_world() * skew1
+        + skew2.transpose() * body2.inv_inertia_world() * skew2
+}
+
+// 求解 K x = rhs，K 奇异（两端都是静态物体）时返回零
+fn solve(k: Matrix3<f32>, rhs: Vector3<f32>) -> Vector3<f32> {
+    k.invert().map_or(Vector3::zero(), |inverse| inverse * rhs)
+}
+
+// 只在与 axis 垂直的平面内求解 K x = rhs，x 也位于该平面内，不影响沿轴方向的自由度
+fn solve_perpendicular(k: Matrix3<f32>, axis: Vector3<f32>, rhs: Vector3<f32>) -> Vector3<f32> {
+    let t1 = any_perpendicular(axis);
+    let t2 = axis.cross(t1);
+    let (k1, k2) = (k * t1, k * t2);
+    let reduced = Matrix2::new(t1.dot(k1), t2.dot(k1), t1.dot(k2), t2.dot(k2));
+    reduced.invert().map_or(Vector3::zero(), |inverse| {
+        let x = inverse * Vector2::new(t1.dot(rhs), t2.dot(rhs));
+        t1 * x.x + t2 * x.y
+    })
+}
+
+fn clamp_angle(rotation: Vector3<f32>) -> Vector3<f32> {
+    let angle = rotation.magnitude();
+    if angle > MAX_ANGULAR_CORRECTION {
+        rotation * (MAX_ANGULAR_CORRECTION / angle)
+    } else {
+        rotation
+    }
+}
+
+// 在锚点施加线冲量 linear，并施加角冲量 angular
+fn apply_impulses(
+    body1: &mut PhysicsBody,
+    body2: &mut PhysicsBody,
+    frame: &JointFrame,
+    linear: Vector3<f32>,
+    angular: Vector3<f32>,
+) {
+    body1.velocity += linear * body1.inv_mass;
+    body1.angular_velocity += body1.inv_inertia_world() * (frame.r1.cross(linear) + angular);
+    body2.velocity -= linear * body2.inv_mass;
+    body2.angular_velocity -= body2.inv_inertia_world() * (frame.r2.cross(linear) + angular);
+}
+
+// 把冲量当作位移施加：与 apply_impulses 相同的分配方式，但直接修改位置与姿态
+fn correct_positions(
+    body1: &mut PhysicsBody,
+    body2: &mut PhysicsBody,
+    r1: Vector3<f32>,
+    r2: Vector3<f32>,
+    linear: Vector3<f32>,
+    angular: Vector3<f32>,
+) {
+    for (body, r, sign) in [(body1, r1, 1.0), (body2, r2, -1.0)] {
+        if body.inv_mass == 0.0 {
+            continue;
+        }
+        body.transform.translation += linear * (sign * body.inv_mass);
+        let rotation = body.inv_inertia_world() * (r.cross(linear) + angular) * sign;
+        let q = body.transform.rotation;
+        body.transform.rotation = (q + Quaternion::from_sv(0.0, rotation) * q * 0.5).normalize();
+        body.update_inertia_world();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::PHYSICS_TIMESTEP;
+    use crate::physics::phymgr::PhyMgr;
+    use crate::physics::shape::Cylinder;
+
+    fn cube(world: &mut PhyMgr, x: f32, y: f32) -> BodyHandle {
+        let shape = Shape::Box {
+            half_extents: Vector3::new(0.5, 0.5, 0.5),
+        };
+        world.create_instance(Transform::translation(x, y, 0.0), shape, 1.0, false)
+    }
+
+    fn run(world: &mut PhyMgr, seconds: f32) {
+        for _ in 0..(seconds / PHYSICS_TIMESTEP) as usize {
+            world.update(PHYSICS_TIMESTEP);
+        }
+    }
+
+    #[test]
+    fn test_hanging_chain_stays_connected() {
+        // 水平伸出的圆柱体链条，一端挂在世界上，松开后摆动下垂
+        let mut world = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        let link = Shape::Cylinder(Cylinder {
+            center: Vector3::zero(),
+            axis: Vector3::unit_y(),
+            radius: 0.2,
+            height: 1.0,
+        });
+        let mut previous = None;
+        let mut links = Vec::new();
+        for i in 0..6 {
+            let transform = Transform {
+                translation: Vector3::new(i as f32 + 0.5, 5.0, 0.0),
+                rotation: Quaternion::from_angle_z(Deg(90.0)),
+                scale: Vector3::new(1.0, 1.0, 1.0),
+            };
+            let handle = world.create_instance(transform, link.clone(), 1.0, false);
+            let anchor = Vector3::new(i as f32, 5.0, 0.0);
+            world
+                .create_joint(
+                    JointKind::BallSocket,
+                    handle,
+                    previous,
+                    anchor,
+                    Vector3::unit_z(),
+                )
+                .unwrap();
+            previous = Some(handle);
+            links.push(handle);
+        }
+
+        run(&mut world, 4.0);
+        let joints = world.joints().map(|(handle, _)| handle).collect::<Vec<_>>();
+        assert_eq!(joints.len(), 6);
+        for handle in joints {
+            let (anchor1, anchor2) = world.joint_anchors(handle).unwrap();
+            assert!((anchor1 - anchor2).magnitude() < 0.05);
+        }
+        // 链条已经摆落，且总长度没有被拉长
+        let last = world.get(*links.last().unwrap()).unwrap().position();
+        assert!(last.y < 3.0, "{:?}", last);
+        assert!((last - Vector3::new(0.0, 5.0, 0.0)).magnitude() < 5.55);
+    }
+
+    #[test]
+    fn test_hinge_limits_and_motor() {
+        // 绕 z 轴的铰链，门板在重力下转到下限后停住
+        let mut world = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        let door = cube(&mut world, 0.5, 0.0);
+        let limits = Some((-0.5, 0.5));
+        let hinge = world
+            .create_joint(
+                JointKind::Hinge {
+                    limits,
+                    motor: None,
+                },
+                door,
+                None,
+                Vector3::zero(),
+                Vector3::unit_z(),
+            )
+            .unwrap();
+        run(&mut world, 2.0);
+        let body = world.get(door).unwrap();
+        let rotation = body.transform.rotation;
+        let angle = 2.0 * rotation.v.z.atan2(rotation.s);
+        assert!((angle + 0.5).abs() < 0.03, "angle = {}", angle);
+        assert!(body.angular_velocity.magnitude() < 0.05);
+        assert!(body.position().z.abs() < 1e-3);
+
+        // 失重时电机带动门板匀速转动，只剩沿轴的角速度
+        world.gravity = Vector3::zero();
+        world.joint_mut(hinge).unwrap().kind = JointKind::Hinge {
+            limits: None,
+            motor: Some(JointMotor {
+                speed: 2.0,
+                max_torque: 100.0,
+            }),
+        };
+        run(&mut world, 1.0);
+        let body = world.get(door).unwrap();
+        assert!((body.angular_velocity - Vector3::new(0.0, 0.0, 2.0)).magnitude() < 0.01);
+        let (anchor1, anchor2) = world.joint_anchors(hinge).unwrap();
+        assert!((anchor1 - anchor2).magnitude() < 0.01);
+    }
+
+    #[test]
+    fn test_slider_and_fixed() {
+        // 斜向重力推动滑块沿 x 轴滑到上限，姿态与另外两个方向的位置不变
+        let mut world = PhyMgr::new(Vector3::new(5.0, -9.8, 2.0));
+        let slider = cube(&mut world, 0.0, 0.0);
+        world
+            .create_joint(
+                JointKind::Slider {
+                    limits: Some((-1.0, 1.0)),
+                },
+                slider,
+                None,
+                Vector3::zero(),
+                Vector3::unit_x(),
+            )
+            .unwrap();
+
+        // 与滑块固定在一起的方块
+        let attached = cube(&mut world, 0.0, 1.5);
+        world
+            .create_joint(
+                JointKind::Fixed,
+                attached,
+                Some(slider),
+                Vector3::new(0.0, 0.75, 0.0),
+                Vector3::unit_x(),
+            )
+            .unwrap();
+
+        run(&mut world, 3.0);
+        let body = world.get(slider).unwrap();
+        assert!(
+            (body.position() - Vector3::new(1.0, 0.0, 0.0)).magnitude() < 0.02,
+            "{:?}",
+            body.position()
+        );
+        assert!((body.transform.rotation.s - 1.0).abs() < 1e-3);
+        let body = world.get(attached).unwrap();
+        assert!(
+            (body.position() - Vector3::new(1.0, 1.5, 0.0)).magnitude() < 0.02,
+            "{:?}",
+            body.position()
+        );
+        assert!((body.transform.rotation.s - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_rope_and_spring() {
+        let mut world = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        let ball = Shape::Sphere(Sphere {
+            center: Vector3::zero(),
+            radius: 0.25,
+        });
+        let anchor = Vector3::new(0.0, 5.0, 0.0);
+
+        // 松弛的绳子：下落到绳长后被拉住
+        let rope_ball = world.create_instance(
+            Transform::translation(0.0, 4.0, 0.0),
+            ball.clone(),
+            1.0,
+            false,
+        );
+        world
+            .create_joint_with_anchors(
+                JointKind::Distance {
+                    min_length: 0.0,
+                    max_length: 2.0,
+                },
+                rope_ball,
+                None,
+                Vector3::new(0.0, 4.0, 0.0),
+                anchor,
+            )
+            .unwrap();
+
+        // 有阻尼的弹簧停在重力与弹力平衡处；刚度很大的弹簧也保持稳定
+        let spring = |world: &mut PhyMgr, x: f32, stiffness: f32| {
+            let body = world.create_instance(
+                Transform::translation(x, 4.0, 0.0),
+                ball.clone(),
+                1.0,
+                false,
+            );
+            world
+                .create_joint_with_anchors(
+                    JointKind::Spring {
+                        rest_length: 1.0,
+                        stiffness,
+                        damping: 5.0,
+                    },
+                    body,
+                    None,
+                    Vector3::new(x, 4.0, 0.0),
+                    Vector3::new(x, 5.0, 0.0),
+                )
+                .unwrap();
+            body
+        };
+        let soft = spring(&mut world, 2.0, 50.0);
+        let stiff = spring(&mut world, 4.0, 1.0e5);
+
+        for _ in 0..240 {
+            world.update(PHYSICS_TIMESTEP);
+            let length = (world.get(rope_ball).unwrap().position() - anchor).magnitude();
+            assert!(length < 2.02, "rope length = {}", length);
+        }
+
+        let body = world.get(rope_ball).unwrap();
+        assert!((body.position().y - 3.0).abs() < 0.02);
+        assert!(body.velocity.magnitude() < 0.05);
+        let body = world.get(soft).unwrap();
+        assert!(
+            (body.position().y - (4.0 - 9.8 / 50.0)).abs() < 0.01,
+            "{:?}",
+            body.position()
+        );
+        let body = world.get(stiff).unwrap();
+        assert!(
+            (body.position().y - 4.0).abs() < 0.01,
+            "{:?}",
+            body.position()
+        );
+        assert!(body.velocity.magnitude() < 0.05);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::common::Transform;
+use cgmath::*;
+
+/// 关键帧路径：按时间在相邻关键帧之间插值平移与旋转，用于驱动运动学物体
+///
+/// 平移线性插值，旋转球面插值；循环播放时最后一帧之后回到第一帧，
+/// 首尾两帧相同可以得到连续的运动。
+#[derive(Debug, Clone)]
+pub struct KinematicPath {
+    keyframes: Vec<(f32, Transform)>, // (时间, 变换)，时间从 0 开始递增
+    pub looping: bool,
+    time: f32, // 当前播放到的时间
+}
+
+impl KinematicPath {
+    pub fn new(keyframes: Vec<(f32, Transform)>, looping: bool) -> Self {
+        assert!(
+            !keyframes.is_empty(),
+            "kinematic path needs at least one keyframe"
+        );
+        assert!(
+            keyframes.windows(2).all(|pair| pair[0].0 < pair[1].0),
+            "keyframe times must be strictly increasing"
+        );
+        Self {
+            keyframes,
+            looping,
+            time: 0.0,
+        }
+    }
+
+    /// 绕过 center 的 axis 匀速旋转一周的循环路径，period 为周期（秒）
+    pub fn rotation(center: Vector3<f32>, axis: Vector3<f32>, period: f32) -> Self {
+        // 每段 90°，球面插值在段内保持匀速
+        let keyframes = (0..=4)
+            .map(|i| {
+                let mut transform = Transform::translation(center.x, center.y, center.z);
+                transform.rotation =
+                    Quaternion::from_axis_angle(axis.normalize(), Deg(90.0 * i as f32));
+                (period * i as f32 / 4.0, transform)
+            })
+            .collect();
+        Self::new(keyframes, true)
+    }
+
+    /// 最后一帧的时间
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().unwrap().0
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// 时间 time 处的变换，超出范围时循环或停在首尾帧
+    pub fn sample(&self, time: f32) -> Transform {
+        let duration = self.duration();
+        let time = if self.looping && duration > 0.0 {
+            time.rem_euclid(duration)
+        } else {
+            time.clamp(0.0, duration)
+        };
+
+        let next = self
+            .keyframes
+            .iter()
+            .position(|&(key_time, _)| key_time > time)
+            .unwrap_or(self.keyframes.len());
+        if next == 0 {
+            return self.keyframes[0].1;
+        }
+        if next == self.keyframes.len() {
+            return self.keyframes[next - 1].1;
+        }
+
+        let (time0, from) = self.keyframes[next - 1];
+        let (time1, to) = self.keyframes[next];
+        let amount = (time - time0) / (time1 - time0);
+        Transform {
+            translation: from.translation.lerp(to.translation, amount),
+            rotation: from.rotation.slerp(to.rotation, amount),
+            scale: from.scale.lerp(to.scale, amount),
+        }
+    }
+
+    /// 推进 delta_time 并返回新时间处的变换
+    pub fn advance(&mut self, delta_time: f32) -> Transform {
+        self.time += delta_time;
+        if self.looping && self.duration() > 0.0 {
+            self.time = self.time.rem_euclid(self.duration());
+        }
+        self.sample(self.time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_sampling() {
+        let path = KinematicPath::new(
+            vec![
+                (0.0, Transform::translation(0.0, 0.0, 0.0)),
+                (2.0, Transform::translation(4.0, 0.0, 0.0)),
+            ],
+            false,
+        );
+        assert!((path.sample(0.5).translation.x - 1.0).abs() < 1e-5);
+        assert!((path.sample(5.0).translation.x - 4.0).abs() < 1e-5);
+        assert!(path.sample(-1.0).translation.x.abs() < 1e-5);
+
+        // 匀速旋转：任意时刻转过的角度与时间成正比
+        let mut path = KinematicPath::rotation(Vector3::unit_y(), Vector3::unit_y(), 4.0);
+        for step in 1..=10 {
+            let transform = path.advance(0.7);
+            let expected = Quaternion::from_angle_y(Deg(90.0 * (0.7 * step as f32 % 4.0)));
+            assert!(
+                transform.rotation.dot(expected).abs() > 1.0 - 1e-5,
+                "step {}",
+                step
+            );
+            assert!((transform.translation - Vector3::unit_y()).magnitude() < 1e-5);
+        }
+    }
+}
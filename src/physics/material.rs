@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// 两个材质的系数组合成一个接触系数的方式
+///
+/// 两个材质的组合方式不同时取优先级高的一方：Average < Min < Multiply < Max。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum CombineMode {
+    #[default]
+    Average,
+    Min,
+    Multiply,
+    Max,
+}
+
+impl CombineMode {
+    pub const ALL: [CombineMode; 4] = [
+        CombineMode::Average,
+        CombineMode::Min,
+        CombineMode::Multiply,
+        CombineMode::Max,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CombineMode::Average => "average",
+            CombineMode::Min => "min",
+            CombineMode::Multiply => "multiply",
+            CombineMode::Max => "max",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    pub fn combine(&self, a: f32, b: f32) -> f32 {
+        match self {
+            CombineMode::Average => (a + b) * 0.5,
+            CombineMode::Min => a.min(b),
+            CombineMode::Multiply => a * b,
+            CombineMode::Max => a.max(b),
+        }
+    }
+}
+
+/// 物理材质：描述物体表面的摩擦与弹性
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicsMaterial {
+    pub static_friction: f32,  // 相对静止时的摩擦系数
+    pub dynamic_friction: f32, // 相对滑动时的摩擦系数
+    pub restitution: f32,      // 弹性系数
+    pub rolling_friction: f32, // 滚动摩擦系数（长度单位），阻碍接触面上的相对转动
+    pub friction_combine: CombineMode,
+    pub restitution_combine: CombineMode,
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self {
+            static_friction: 0.5,
+            dynamic_friction: 0.5,
+            restitution: 0.2,
+            rolling_friction: 0.0,
+            friction_combine: CombineMode::Average,
+            restitution_combine: CombineMode::Max,
+        }
+    }
+}
+
+/// 材质库中的材质编号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct MaterialId(u32);
+
+impl MaterialId {
+    /// 默认材质，新建的物体都使用它
+    pub const DEFAULT: MaterialId = MaterialId(0);
+
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// 组合后作用于一个接触的系数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactMaterial {
+    pub static_friction: f32,
+    pub dynamic_friction: f32,
+    pub restitution: f32,
+    pub rolling_friction: f32,
+}
+
+/// 某一对材质的覆盖系数，为 None 的项仍按组合方式计算
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PairOverride {
+    pub static_friction: Option<f32>,
+    pub dynamic_friction: Option<f32>,
+    pub restitution: Option<f32>,
+    pub rolling_friction: Option<f32>,
+}
+
+/// 材质文件的解析错误，line 从 1 开始
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaterialParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for MaterialParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for MaterialParseError {}
+
+/// 按名称注册的材质，以及材质对之间的覆盖系数
+#[derive(Clone)]
+pub struct MaterialLibrary {
+    materials: Vec<(String, PhysicsMaterial)>,
+    overrides: HashMap<(MaterialId, MaterialId), PairOverride>, // 键的两个编号按升序排列
+}
+
+impl Default for MaterialLibrary {
+    fn default() -> Self {
+        Self {
+            materials: vec![("default".to_string(), PhysicsMaterial::default())],
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+fn pair_key(a: MaterialId, b: MaterialId) -> (MaterialId, MaterialId) {
+    (a.min(b), a.max(b))
+}
+
+impl MaterialLibrary {
+    /// 注册材质并返回编号，同名材质会被替换且保留原编号
+    pub fn register(&mut self, name: &str, material: PhysicsMaterial) -> MaterialId {
+        if let Some(id) = self.id(name) {
+            self.materials[id.index()].1 = material;
+            return id;
+        }
+        self.materials.push((name.to_string(), material));
+        MaterialId((self.materials.len() - 1) as u32)
+    }
+
+    pub fn id(&self, name: &str) -> Option<MaterialId> {
+        self.materials
+            .iter()
+            .position(|(other, _)| other == name)
+            .map(|index| MaterialId(index as u32))
+    }
+
+    pub fn get(&self, id: MaterialId) -> Option<&PhysicsMaterial> {
+        self.materials.get(id.index()).map(|(_, material)| material)
+    }
+
+    pub fn get_mut(&mut self, id: MaterialId) -> Option<&mut PhysicsMaterial> {
+        self.materials
+            .get_mut(id.index())
+            .map(|(_, material)| material)
+    }
+
+    pub fn name(&self, id: MaterialId) -> Option<&str> {
+        self.materials
+            .get(id.index())
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// 按注册顺序遍历所有材质
+    pub fn iter(&self) -> impl Iterator<Item = (MaterialId, &str, &PhysicsMaterial)> {
+        self.materials
+            .iter()
+            .enumerate()
+            .map(|(index, (name, material))| (MaterialId(index as u32), name.as_str(), material))
+    }
+
+    /// 为一对材质（顺序无关，可以是同一材质）设置覆盖系数
+    pub fn set_pair_override(&mut self, a: MaterialId, b: MaterialId, pair: PairOverride) {
+        self.overrides.insert(pair_key(a, b), pair);
+    }
+
+    pub fn pair_override(&self, a: MaterialId, b: MaterialId) -> Option<&PairOverride> {
+        self.overrides.get(&pair_key(a, b))
+    }
+
+    pub fn remove_pair_override(&mut self, a: MaterialId, b: MaterialId) -> Option<PairOverride> {
+        self.overrides.remove(&pair_key(a, b))
+    }
+
+    /// 两个材质接触时使用的系数，无效编号按默认材质处理
+    pub fn combine(&self, a: MaterialId, b: MaterialId) -> ContactMaterial {
+        let default = &self.materials[MaterialId::DEFAULT.index()].1;
+        let material1 = self.get(a).unwrap_or(default);
+        let material2 = self.get(b).unwrap_or(default);
+        let friction = material1.friction_combine.max(material2.friction_combine);
+        let restitution = material1
+            .restitution_combine
+            .max(material2.restitution_combine);
+
+        let mut combined = ContactMaterial {
+            static_friction: friction.combine(material1.static_friction, material2.static_friction),
+            dynamic_friction: friction
+                .combine(material1.dynamic_friction, material2.dynamic_friction),
+            restitution: restitution.combine(material1.restitution, material2.restitution),
+            // 滚动摩擦沿用摩擦的组合方式
+            rolling_friction: friction
+                .combine(material1.rolling_friction, material2.rolling_friction),
+        };
+        if let Some(pair) = self.pair_override(a, b) {
+            combined.static_friction = pair.static_friction.unwrap_or(combined.static_friction);
+            combined.dynamic_friction = pair.dynamic_friction.unwrap_or(combined.dynamic_friction);
+            combined.restitution = pair.restitution.unwrap_or(combined.restitution);
+            combined.rolling_friction = pair.rolling_friction.unwrap_or(combined.rolling_friction);
+        }
+        combined
+    }
+
+    /// 从场景文件中加载材质与材质对的覆盖系数
+    ///
+    /// 文件由若干段组成，`#` 之后为注释：
+    ///
+    /// ```text
+    /// [material ice]
+    /// static_friction = 0.1
+    /// dynamic_friction = 0.05
+    /// friction_combine = min
+    ///
+    /// [pair ice rubber]
+    /// dynamic_friction = 0.3
+    /// ```
+    ///
+    /// 材质段未给出的属性取 `PhysicsMaterial::default()` 的值；材质对段引用的材质必须已经注册
+    /// （在库中或在文件的前面）。出错时不修改材质库。
+    pub fn load(&mut self, source: &str) -> Result<(), MaterialParseError> {
+        enum Section {
+            Material(String, PhysicsMaterial),
+            Pair(String, String, PairOverride),
+        }
+
+        let mut sections: Vec<(usize, Section)> = Vec::new();
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let error = |message: String| MaterialParseError {
+                line: line_number,
+                message,
+            };
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header
+                    .strip_suffix(']')
+                    .ok_or_else(|| error(format!("unterminated section header '{}'", line)))?;
+                let words: Vec<&str> = header.split_whitespace().collect();
+                let section = match words.as_slice() {
+                    ["material", name] => {
+                        Section::Material(name.to_string(), PhysicsMaterial::default())
+                    }
+                    ["pair", a, b] => {
+                        Section::Pair(a.to_string(), b.to_string(), PairOverride::default())
+                    }
+                    _ => return Err(error(format!("unknown section '{}'", header))),
+                };
+                sections.push((line_number, section));
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected 'key = value', got '{}'", line)))?;
+            let (key, value) = (key.trim(), value.trim());
+            let number = || {
+                value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|number| number.is_finite() && *number >= 0.0)
+                    .ok_or_else(|| error(format!("invalid value '{}' for '{}'", value, key)))
+            };
+            let mode = || {
+                CombineMode::from_name(value)
+                    .ok_or_else(|| error(format!("unknown combine mode '{}'", value)))
+            };
+
+            match sections.last_mut() {
+                Some((_, Section::Material(_, material))) => match key {
+                    "static_friction" => material.static_friction = number()?,
+                    "dynamic_friction" => material.dynamic_friction = number()?,
+                    "restitution" => material.restitution = number()?,
+                    "rolling_friction" => material.rolling_friction = number()?,
+                    "friction_combine" => material.friction_combine = mode()?,
+                    "restitution_combine" => material.restitution_combine = mode()?,
+                    _ => return Err(error(format!("unknown material property '{}'", key))),
+                },
+                Some((_, Section::Pair(_, _, pair))) => match key {
+                    "static_friction" => pair.static_friction = Some(number()?),
+                    "dynamic_friction" => pair.dynamic_friction = Some(number()?),
+                    "restitution" => pair.restitution = Some(number()?),
+                    "rolling_friction" => pair.rolling_friction = Some(number()?),
+                    _ => return Err(error(format!("unknown pair property '{}'", key))),
+                },
+                None => return Err(error("property outside of a section".to_string())),
+            }
+        }
+
+        // 先检查材质对引用的名称，全部有效后再写入
+        let mut names: Vec<&str> = self
+            .materials
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        for (line, section) in &sections {
+            match section {
+                Section::Material(name, _) => names.push(name),
+                Section::Pair(a, b, _) => {
+                    if let Some(unknown) = [a, b]
+                        .into_iter()
+                        .find(|name| !names.contains(&name.as_str()))
+                    {
+                        return Err(MaterialParseError {
+                            line: *line,
+                            message: format!("unknown material '{}'", unknown),
+                        });
+                    }
+                }
+            }
+        }
+
+        for (_, section) in sections {
+            match section {
+                Section::Material(name, material) => {
+                    self.register(&name, material);
+                }
+                Section::Pair(a, b, pair) => {
+                    let (a, b) = (self.id(&a).unwrap(), self.id(&b).unwrap());
+                    self.set_pair_override(a, b, pair);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combine_modes_and_overrides() {
+        let mut library = MaterialLibrary::default();
+        let ice = library.register(
+            "ice",
+            PhysicsMaterial {
+                static_friction: 0.1,
+                dynamic_friction: 0.05,
+                restitution: 0.0,
+                friction_combine: CombineMode::Min,
+                ..Default::default()
+            },
+        );
+        let rubber = library.register(
+            "rubber",
+            PhysicsMaterial {
+                static_friction: 1.0,
+                dynamic_friction: 0.8,
+                restitution: 0.8,
+                ..Default::default()
+            },
+        );
+
+        // Min 的优先级高于 Average，restitution 都是 Max
+        let combined = library.combine(ice, rubber);
+        assert_eq!(combined.static_friction, 0.1);
+        assert_eq!(combined.dynamic_friction, 0.05);
+        assert_eq!(combined.restitution, 0.8);
+        let combined = library.combine(rubber, MaterialId::DEFAULT);
+        assert!((combined.static_friction - 0.75).abs() < 1e-6);
+
+        library.set_pair_override(
+            rubber,
+            ice,
+            PairOverride {
+                dynamic_friction: Some(0.3),
+                ..Default::default()
+            },
+        );
+        let combined = library.combine(ice, rubber);
+        assert_eq!(combined.dynamic_friction, 0.3);
+        assert_eq!(combined.static_friction, 0.1);
+
+        // 重新注册同名材质保留编号
+        assert_eq!(library.register("ice", PhysicsMaterial::default()), ice);
+    }
+
+    #[test]
+    fn test_load_materials() {
+        let mut library = MaterialLibrary::default();
+        library
+            .load(
+                "# 材质\n\
+                 [material ice]\n\
+                 static_friction = 0.1 # 冰面\n\
+                 friction_combine = multiply\n\
+                 \n\
+                 [material rubber]\n\
+                 restitution = 0.9\n\
+                 [pair ice rubber]\n\
+                 restitution = 0.0\n",
+            )
+            .unwrap();
+        let ice = library.id("ice").unwrap();
+        let rubber = library.id("rubber").unwrap();
+        let material = library.get(ice).unwrap();
+        assert_eq!(material.static_friction, 0.1);
+        assert_eq!(material.dynamic_friction, 0.5);
+        assert_eq!(material.friction_combine, CombineMode::Multiply);
+        assert_eq!(library.combine(rubber, ice).restitution, 0.0);
+
+        let error = library
+            .load("[material glass]\nrestitution = 0.3\n[pair glass wood]\n")
+            .unwrap_err();
+        assert_eq!(error.line, 3);
+        // 出错时材质库保持不变
+        assert!(library.id("glass").is_none());
+        let error = library.load("[material mud]\nfriction = 1\n").unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+}
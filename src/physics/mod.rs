@@ -1,3 +1,20 @@
+pub mod aabb;
+pub mod aabbtree;
+pub mod broadphase;
 pub mod collision;
+pub mod diagnostics;
+pub mod events;
+pub mod filter;
+pub mod gjk;
+pub mod heightfield;
+pub mod integrator;
+pub mod island;
+pub mod joint;
+pub mod kinematic;
+pub mod material;
+pub mod mousejoint;
 pub mod phymgr;
+pub mod query;
 pub mod shape;
+pub mod solver;
+pub mod trimesh;
@@ -0,0 +1,73 @@
+use crate::physics::phymgr::BodyHandle;
+use crate::physics::shape::PhysicsBody;
+use cgmath::*;
+
+/// 鼠标拖拽约束：把物体上的一点用软弹簧拉向目标点
+///
+/// 按 Box2D 的 mouse joint 实现，刚度与阻尼由频率和阻尼比给出，与物体质量无关；
+/// 每一步在速度求解前施加一次冲量，单步冲量不超过 max_force · dt。
+#[derive(Debug, Clone, Copy)]
+pub struct MouseJoint {
+    pub body: BodyHandle,
+    pub local_anchor: Vector3<f32>, // 物体局部坐标中的拖拽点
+    pub target: Vector3<f32>,       // 世界坐标中的目标点
+    pub frequency: f32,             // 弹簧固有频率 (Hz)
+    pub damping_ratio: f32,         // 阻尼比，1 为临界阻尼
+    pub max_force: f32,             // 相对于物体重量的最大拉力倍数
+    pub angular_damping: f32,       // 拖拽期间额外的角速度阻尼，避免物体绕拖拽点长时间摆动
+}
+
+impl MouseJoint {
+    /// 从物体上的世界坐标点 point 开始拖拽，目标点初始与拖拽点重合
+    pub fn new(handle: BodyHandle, body: &PhysicsBody, point: Vector3<f32>) -> Self {
+        Self {
+            body: handle,
+            local_anchor: body.transform.inverse_transform_point(point),
+            target: point,
+            frequency: 5.0,
+            damping_ratio: 0.7,
+            max_force: 50.0,
+            angular_damping: 5.0,
+        }
+    }
+
+    /// 拖拽点当前的世界坐标
+    pub fn world_anchor(&self, body: &PhysicsBody) -> Vector3<f32> {
+        body.transform.transform_point(self.local_anchor)
+    }
+
+    /// 施加一步的约束冲量，gravity 用于换算最大拉力
+    pub fn apply(&self, body: &mut PhysicsBody, gravity: Vector3<f32>, delta_time: f32) {
+        if body.inv_mass == 0.0 {
+            return;
+        }
+
+        let mass = body.mass;
+        let omega = 2.0 * std::f32::consts::PI * self.frequency;
+        let damping = 2.0 * mass * self.damping_ratio * omega;
+        let stiffness = mass * omega * omega;
+        // 软约束参数：gamma 为柔度，beta 为位置误差修正系数
+        let gamma = 1.0 / (delta_time * (damping + delta_time * stiffness));
+        let beta = delta_time * stiffness * gamma;
+
+        body.angular_velocity *= 1.0 / (1.0 + self.angular_damping * delta_time);
+
+        let anchor = self.world_anchor(body);
+        let r = anchor - body.position();
+        let skew = Matrix3::new(0.0, r.z, -r.y, -r.z, 0.0, r.x, r.y, -r.x, 0.0);
+        // K = m⁻¹ E + [r]ᵀ I⁻¹ [r] + γ E
+        let k = Matrix3::from_diagonal(Vector3::from_value(body.inv_mass + gamma))
+            + skew.transpose() * body.inv_inertia_world() * skew;
+        let Some(effective_mass) = k.invert() else {
+            return;
+        };
+
+        let error = anchor - self.target;
+        let mut impulse = effective_mass * -(body.velocity_at_point(anchor) + error * beta);
+        let max_impulse = self.max_force * mass * gravity.magnitude().max(1.0) * delta_time;
+        if impulse.magnitude() > max_impulse {
+            impulse = impulse.normalize_to(max_impulse);
+        }
+        body.apply_impulse(impulse, anchor);
+    }
+}
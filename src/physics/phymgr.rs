@@ -1,106 +1,2202 @@
-use cgmath::InnerSpace;
-
-type Vector3 = cgmath::Vector3<f32>;
-type Vector4 = cgmath::Vector4<f32>;
-type Quaternion = cgmath::Quaternion<f32>;
-type Matrix4 = cgmath::Matrix4<f32>;
-type Decomposed = cgmath::Decomposed<Vector4, Quaternion>;
-
-struct PhyMgr {
-    dynamic_objs: Vec<Instance>,
-    static_objs: Vec<Instance>,
-    gravity: Vector3,
-    air_friction: f32, // acceleration
+use crate::common::Transform;
+use crate::physics::aabb::Aabb;
+use crate::physics::broadphase::{BroadPhase, BroadPhaseKind};
+use crate::physics::collision::{collide_bodies, support_map};
+use crate::physics::diagnostics::Diagnostics;
+use crate::physics::events::{EventTracker, PhysicsEvent};
+use crate::physics::filter::{can_collide, QueryFilter};
+use crate::physics::integrator::{IntegratorKind, MotionState};
+use crate::physics::island::build_islands;
+use crate::physics::joint::{Joint, JointHandle, JointKind, JointSet};
+use crate::physics::kinematic::KinematicPath;
+use crate::physics::material::MaterialLibrary;
+use crate::physics::mousejoint::MouseJoint;
+use crate::physics::query::{raycast_shape, shape_cast_shape, RayHit};
+use crate::physics::shape::{PhysicsBody, PhysicsState, Shape};
+use crate::physics::solver::{Contact, ContactSolver, PENETRATION_SLOP};
+use cgmath::*;
+use std::collections::HashSet;
+
+/// 物理体句柄：槽位索引 + 代数，槽位被复用后旧句柄自动失效
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BodyHandle {
+    index: u32,
+    generation: u32,
+}
+
+impl BodyHandle {
+    /// 槽位索引，可用于以句柄为键的外部数组
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+}
+
+struct BodySlot {
+    generation: u32,
+    body: Option<PhysicsBody>,
+}
+
+/// 物理体集合：按句柄存取物理体，供世界管理器和求解器共享
+#[derive(Default)]
+pub struct BodySet {
+    slots: Vec<BodySlot>,
+    free_slots: Vec<u32>,
+}
+
+impl BodySet {
+    pub fn insert(&mut self, body: PhysicsBody) -> BodyHandle {
+        if let Some(index) = self.free_slots.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.body = Some(body);
+            BodyHandle {
+                index,
+                generation: slot.generation,
+            }
+        } else {
+            self.slots.push(BodySlot {
+                generation: 0,
+                body: Some(body),
+            });
+            BodyHandle {
+                index: (self.slots.len() - 1) as u32,
+                generation: 0,
+            }
+        }
+    }
+
+    pub fn remove(&mut self, handle: BodyHandle) -> Option<PhysicsBody> {
+        let slot = self.slots.get_mut(handle.index())?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+
+        let body = slot.body.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free_slots.push(handle.index);
+        Some(body)
+    }
+
+    pub fn get(&self, handle: BodyHandle) -> Option<&PhysicsBody> {
+        self.slots
+            .get(handle.index())
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.body.as_ref())
+    }
+
+    pub fn get_mut(&mut self, handle: BodyHandle) -> Option<&mut PhysicsBody> {
+        self.slots
+            .get_mut(handle.index())
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.body.as_mut())
+    }
+
+    /// 同时可变借用两个不同的物理体
+    pub fn get_pair_mut(
+        &mut self,
+        handle1: BodyHandle,
+        handle2: BodyHandle,
+    ) -> Option<(&mut PhysicsBody, &mut PhysicsBody)> {
+        if handle1.index == handle2.index {
+            return None;
+        }
+
+        let (first, second, swapped) = if handle1.index < handle2.index {
+            (handle1, handle2, false)
+        } else {
+            (handle2, handle1, true)
+        };
+
+        let (left, right) = self.slots.split_at_mut(second.index());
+        let slot1 = &mut left[first.index()];
+        let slot2 = &mut right[0];
+        if slot1.generation != first.generation || slot2.generation != second.generation {
+            return None;
+        }
+
+        let body1 = slot1.body.as_mut()?;
+        let body2 = slot2.body.as_mut()?;
+        if swapped {
+            Some((body2, body1))
+        } else {
+            Some((body1, body2))
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free_slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (BodyHandle, &PhysicsBody)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.body.as_ref().map(|body| {
+                (
+                    BodyHandle {
+                        index: index as u32,
+                        generation: slot.generation,
+                    },
+                    body,
+                )
+            })
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (BodyHandle, &mut PhysicsBody)> {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let generation = slot.generation;
+                slot.body.as_mut().map(|body| {
+                    (
+                        BodyHandle {
+                            index: index as u32,
+                            generation,
+                        },
+                        body,
+                    )
+                })
+            })
+    }
+}
+
+/// 物理世界参数
+#[derive(Debug, Clone, Copy)]
+pub struct WorldSettings {
+    pub velocity_iterations: u32,    // 速度约束迭代次数
+    pub position_iterations: u32,    // 位置修正迭代次数
+    pub warm_starting: bool,         // 是否使用上一帧的累积冲量预热
+    pub broad_phase: BroadPhaseKind, // 粗检测算法，修改后在下一步模拟时生效
+    pub gyroscopic: bool,            // 是否计算陀螺效应（自由旋转物体的进动）
+    pub integrator: IntegratorKind,  // 外力下平移运动的积分方法
+    pub sleeping: bool,              // 是否让静止的岛休眠，关闭时唤醒所有物体
 }
 
-struct Instance {
-    transform: Decomposed,
-    shape: Shape,
-    mass: f32,
-    velocity: Vector3,
-    accel: Vector3,
+impl Default for WorldSettings {
+    fn default() -> Self {
+        Self {
+            velocity_iterations: 8,
+            position_iterations: 3,
+            warm_starting: true,
+            broad_phase: BroadPhaseKind::default(),
+            gyroscopic: false,
+            integrator: IntegratorKind::default(),
+            sleeping: true,
+        }
+    }
 }
 
-// todo
-// struct Transform2 {
-//     position: Vector4,
-//     rotation: Quaternion,
-//     scale: Vector4,
-// }
+// CCD 截断位移时允许嵌入表面的深度，保证下一步能检测到接触
+const CCD_PENETRATION: f32 = 2.0 * PENETRATION_SLOP;
 
-#[derive(Debug)]
-enum Shape {
-    Sphere { radius: f32 },
+// 线速度和角速度都低于阈值并持续 TIME_TO_SLEEP 秒的物体可以休眠
+pub const SLEEP_LINEAR_VELOCITY: f32 = 0.05;
+pub const SLEEP_ANGULAR_VELOCITY: f32 = 0.05;
+pub const TIME_TO_SLEEP: f32 = 0.5;
+
+/// 物理世界管理器：持有所有物理体，负责积分与碰撞处理
+pub struct PhyMgr {
+    bodies: BodySet,
+    joints: JointSet,
+    broad_phase: Box<dyn BroadPhase>,
+    broad_phase_kind: BroadPhaseKind, // 当前 broad_phase 的算法
+    solver: ContactSolver,
+    pub settings: WorldSettings,
+    pub materials: MaterialLibrary, // 物体按 material 编号引用的材质
+    pub mouse_joint: Option<MouseJoint>, // 鼠标拖拽约束，同一时间最多一个
+    diagnostics: Diagnostics,       // 最近一步结束时的能量与动量
+    events: Vec<PhysicsEvent>,      // 尚未取出的事件
+    event_tracker: EventTracker,
+    kinematic_paths: Vec<(BodyHandle, KinematicPath)>, // 由关键帧路径驱动的运动学物体
+    pub gravity: Vector3<f32>,
+    pub air_friction: f32, // 线性阻尼系数，每秒衰减的速度比例
 }
 
 impl PhyMgr {
+    pub fn new(gravity: Vector3<f32>) -> Self {
+        Self {
+            bodies: BodySet::default(),
+            joints: JointSet::default(),
+            broad_phase: BroadPhaseKind::default().create(),
+            broad_phase_kind: BroadPhaseKind::default(),
+            solver: ContactSolver::default(),
+            settings: WorldSettings::default(),
+            materials: MaterialLibrary::default(),
+            mouse_joint: None,
+            diagnostics: Diagnostics::default(),
+            events: Vec::new(),
+            event_tracker: EventTracker::default(),
+            kinematic_paths: Vec::new(),
+            gravity,
+            air_friction: 0.0,
+        }
+    }
+
+    /// 创建运动学物体，位置由 set_kinematic_target 或关键帧路径驱动
+    pub fn create_kinematic(&mut self, transform: Transform, shape: Shape) -> BodyHandle {
+        self.add_body(PhysicsBody::new_kinematic(shape, transform))
+    }
+
+    /// 让运动学物体沿关键帧路径运动，每步模拟推进路径时间；不是运动学物体时返回 false
+    pub fn set_kinematic_path(&mut self, handle: BodyHandle, path: KinematicPath) -> bool {
+        if !self
+            .bodies
+            .get(handle)
+            .is_some_and(|body| matches!(body.state, PhysicsState::Kinematic))
+        {
+            return false;
+        }
+        self.kinematic_paths.retain(|(other, _)| *other != handle);
+        self.kinematic_paths.push((handle, path));
+        true
+    }
+
+    /// 停止路径驱动并返回路径，物体之后按最后的速度匀速运动
+    pub fn remove_kinematic_path(&mut self, handle: BodyHandle) -> Option<KinematicPath> {
+        let index = self
+            .kinematic_paths
+            .iter()
+            .position(|(other, _)| *other == handle)?;
+        Some(self.kinematic_paths.remove(index).1)
+    }
+
+    /// 按形状创建物理体，静态物体的质量会被忽略
     pub fn create_instance(
         &mut self,
-        transform: Decomposed,
+        transform: Transform,
         shape: Shape,
         mass: f32,
         is_static: bool,
-    ) {
-        let instance = Instance {
-            transform,
-            shape,
-            mass,
-            velocity: Vector3::new(0.0, 0.0, 0.0),
-            accel: Vector3::new(0.0, 0.0, 0.0),
+    ) -> BodyHandle {
+        let body = if is_static {
+            PhysicsBody::new_static(shape, transform)
+        } else {
+            PhysicsBody::new_dynamic(shape, transform, mass)
         };
 
-        let insert_arr = if is_static {
-            &mut self.static_objs
-        } else {
-            &mut self.dynamic_objs
+        self.add_body(body)
+    }
+
+    /// 添加一个已构造好的物理体
+    pub fn add_body(&mut self, body: PhysicsBody) -> BodyHandle {
+        self.bodies.insert(body)
+    }
+
+    /// 移除物理体，连在它上面的关节一并移除；句柄无效时返回 None
+    ///
+    /// 与它相连或包围盒重叠的休眠物体会被唤醒，避免悬停在空中。
+    pub fn remove_instance(&mut self, handle: BodyHandle) -> Option<PhysicsBody> {
+        if self.mouse_joint.is_some_and(|joint| joint.body == handle) {
+            self.mouse_joint = None;
+        }
+        self.kinematic_paths.retain(|(other, _)| *other != handle);
+        let attached = self
+            .joints
+            .iter()
+            .filter(|(_, joint)| joint.connects(handle))
+            .map(|(joint_handle, _)| joint_handle)
+            .collect::<Vec<_>>();
+        let mut neighbours = Vec::new();
+        for joint_handle in attached {
+            if let Some(joint) = self.joints.remove(joint_handle) {
+                neighbours.push(joint.body1);
+                neighbours.extend(joint.body2);
+            }
+        }
+
+        let body = self.bodies.remove(handle)?;
+        neighbours.extend(self.query_aabb(&body.aabb()));
+        for neighbour in neighbours {
+            if let Some(other) = self.bodies.get_mut(neighbour) {
+                other.wake_up();
+            }
+        }
+        Some(body)
+    }
+
+    /// 在世界坐标 anchor 处用关节连接 body1 与 body2（None 表示世界），
+    /// axis 为铰链转轴或滑轨方向；物体句柄无效时返回 None
+    pub fn create_joint(
+        &mut self,
+        kind: JointKind,
+        body1: BodyHandle,
+        body2: Option<BodyHandle>,
+        anchor: Vector3<f32>,
+        axis: Vector3<f32>,
+    ) -> Option<JointHandle> {
+        let (first, second) = self.joint_bodies(body1, body2)?;
+        let joint = Joint::new(kind, first, second, anchor, axis);
+        Some(self.joints.insert(joint))
+    }
+
+    /// 用两个世界坐标锚点连接 body1 与 body2（None 表示世界），用于距离约束和弹簧
+    pub fn create_joint_with_anchors(
+        &mut self,
+        kind: JointKind,
+        body1: BodyHandle,
+        body2: Option<BodyHandle>,
+        anchor1: Vector3<f32>,
+        anchor2: Vector3<f32>,
+    ) -> Option<JointHandle> {
+        let (first, second) = self.joint_bodies(body1, body2)?;
+        let joint = Joint::with_anchors(kind, first, second, anchor1, anchor2);
+        Some(self.joints.insert(joint))
+    }
+
+    // 关节两端的物体，句柄无效或两端是同一物体时返回 None
+    #[allow(clippy::type_complexity)]
+    fn joint_bodies(
+        &self,
+        body1: BodyHandle,
+        body2: Option<BodyHandle>,
+    ) -> Option<(
+        (BodyHandle, &PhysicsBody),
+        Option<(BodyHandle, &PhysicsBody)>,
+    )> {
+        let first = (body1, self.bodies.get(body1)?);
+        let second = match body2 {
+            Some(handle) if handle != body1 => Some((handle, self.bodies.get(handle)?)),
+            Some(_) => return None,
+            None => None,
         };
+        Some((first, second))
+    }
+
+    pub fn remove_joint(&mut self, handle: JointHandle) -> Option<Joint> {
+        self.joints.remove(handle)
+    }
+
+    pub fn joint(&self, handle: JointHandle) -> Option<&Joint> {
+        self.joints.get(handle)
+    }
+
+    /// 可变借用关节，同时唤醒两端的物体，使修改后的参数（如电机）立即生效
+    pub fn joint_mut(&mut self, handle: JointHandle) -> Option<&mut Joint> {
+        let joint = self.joints.get_mut(handle)?;
+        for body in [Some(joint.body1), joint.body2].into_iter().flatten() {
+            if let Some(body) = self.bodies.get_mut(body) {
+                body.wake_up();
+            }
+        }
+        Some(joint)
+    }
+
+    /// 遍历所有关节
+    pub fn joints(&self) -> impl Iterator<Item = (JointHandle, &Joint)> {
+        self.joints.iter()
+    }
+
+    /// 关节两端锚点当前的世界坐标
+    pub fn joint_anchors(&self, handle: JointHandle) -> Option<(Vector3<f32>, Vector3<f32>)> {
+        self.joints.get(handle)?.world_anchors(&self.bodies)
+    }
+
+    /// 设置两个物体之间是否忽略碰撞，句柄无效时返回 false
+    pub fn set_pair_ignored(
+        &mut self,
+        handle1: BodyHandle,
+        handle2: BodyHandle,
+        ignored: bool,
+    ) -> bool {
+        let Some((body1, body2)) = self.bodies.get_pair_mut(handle1, handle2) else {
+            return false;
+        };
+        if ignored {
+            body1.ignored_bodies.insert(handle2);
+        } else {
+            body1.ignored_bodies.remove(&handle2);
+            body2.ignored_bodies.remove(&handle1);
+        }
+        // 恢复碰撞时两者可能已经重叠，需要重新参与模拟
+        body1.wake_up();
+        body2.wake_up();
+        true
+    }
+
+    pub fn contains(&self, handle: BodyHandle) -> bool {
+        self.get(handle).is_some()
+    }
 
-        insert_arr.push(instance);
+    pub fn get(&self, handle: BodyHandle) -> Option<&PhysicsBody> {
+        self.bodies.get(handle)
     }
 
+    pub fn get_mut(&mut self, handle: BodyHandle) -> Option<&mut PhysicsBody> {
+        self.bodies.get_mut(handle)
+    }
+
+    /// 物理体数量
+    pub fn len(&self) -> usize {
+        self.bodies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bodies.is_empty()
+    }
+
+    /// 遍历所有物理体
+    pub fn iter(&self) -> impl Iterator<Item = (BodyHandle, &PhysicsBody)> {
+        self.bodies.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (BodyHandle, &mut PhysicsBody)> {
+        self.bodies.iter_mut()
+    }
+
+    /// 移除所有物理体
+    pub fn clear(&mut self) {
+        let handles = self.iter().map(|(handle, _)| handle).collect::<Vec<_>>();
+        for handle in handles {
+            self.remove_instance(handle);
+        }
+    }
+
+    /// 推进一次模拟：运动学物体求速度 -> 碰撞检测与唤醒 -> 积分外力 -> 速度求解
+    /// -> 位置积分 -> 位置修正 -> 休眠
     pub fn update(&mut self, delta_time: f32) {
-        let dynamic_objs_len = self.dynamic_objs.len();
-        let static_objs_len = self.static_objs.len();
-
-        // calculate accelerate
-        for i in 0..dynamic_objs_len {
-            let dyo = &mut self.dynamic_objs[i];
-
-            // calculate accelerate
-            dyo.accel = self.gravity;
-
-            // update velocity
-            dyo.velocity += dyo.accel * delta_time;
-
-            // move step
-            dyo.transform.disp += (dyo.velocity * delta_time).extend(0.0);
-        }
-
-        // check collision, cal the collision normal, squeeze out and reverse velocity by the collision
-        for i in 0..dynamic_objs_len {
-            for j in (i + 1)..dynamic_objs_len {
-                let obj1 = &self.dynamic_objs[i];
-                let obj2 = &self.dynamic_objs[j];
-                let pos1 = &obj1.transform.disp;
-                let pos2 = &obj2.transform.disp;
-                let collision_normal = pos2 - pos1;
-                let dist2 = collision_normal.magnitude2();
-                let r1 = match obj1.shape {
-                    Shape::Sphere { radius } => radius,
+        // 运动学物体由目标位姿反推本步速度，碰撞检测与求解据此推动接触的物体
+        for (handle, path) in &mut self.kinematic_paths {
+            if let Some(body) = self.bodies.get_mut(*handle) {
+                body.set_kinematic_target(path.advance(delta_time));
+            }
+        }
+        let mut kinematic_moves = Vec::new(); // (句柄, 目标位姿)
+        for (handle, body) in self.bodies.iter_mut() {
+            if let PhysicsState::Kinematic = body.state {
+                kinematic_moves.push((handle, body.take_kinematic_target(delta_time)));
+            }
+        }
+
+        if !self.settings.sleeping {
+            for (_, body) in self.bodies.iter_mut() {
+                if body.is_sleeping() {
+                    body.wake_up();
+                }
+            }
+        }
+        // 被拖拽的物体保持唤醒，休眠计时也不会累积
+        if let Some(joint) = self.mouse_joint {
+            if let Some(body) = self.bodies.get_mut(joint.body) {
+                body.wake_up();
+            }
+        }
+
+        // 碰撞检测只依赖位置，放在积分之前，使本步被唤醒的物体立即参与积分和求解
+        let (contacts, overlaps, islands) = self.find_contacts();
+
+        // 两侧都没醒的物体对本步没有检测，事件按上一步的状态处理
+        let bodies = &self.bodies;
+        self.event_tracker.update(
+            contacts
+                .iter()
+                .map(|contact| (contact.body1, contact.body2))
+                .collect(),
+            overlaps.into_iter().collect(),
+            |handle1, handle2| match (bodies.get(handle1), bodies.get(handle2)) {
+                (Some(body1), Some(body2)) => !is_active(body1) && !is_active(body2),
+                _ => false,
+            },
+            &mut self.events,
+        );
+
+        let gravity = self.gravity;
+        let air_friction = self.air_friction;
+        let angular_damping = (1.0 - air_friction * delta_time).max(0.0);
+        let integrator = self.settings.integrator.integrator();
+        let acceleration = |state: &MotionState| gravity - state.velocity * air_friction;
+
+        // 外力下的自由运动由积分器求出，速度交给求解器加上接触冲量，位置在速度求解之后再确定
+        let mut free_motions = Vec::new(); // (句柄, 初始状态, 自由位移, 自由速度)
+        for (handle, body) in self.bodies.iter_mut() {
+            // 物体的姿态可能在两步之间被外部修改
+            body.update_inertia_world();
+            if matches!(body.state, PhysicsState::Dynamic) && !body.is_sleeping() {
+                let start = MotionState {
+                    position: body.position(),
+                    velocity: body.velocity,
+                };
+                let end = integrator.integrate(start, &acceleration, delta_time);
+                free_motions.push((handle, start, end.position - start.position, end.velocity));
+                body.velocity = end.velocity;
+                body.angular_velocity *= angular_damping;
+                if self.settings.gyroscopic {
+                    body.apply_gyroscopic(delta_time);
+                }
+            }
+        }
+
+        if let Some(joint) = self.mouse_joint {
+            if let Some(body) = self.bodies.get_mut(joint.body) {
+                joint.apply(body, gravity, delta_time);
+            }
+        }
+
+        // 本步受约束（接触、关节、鼠标拖拽）的物体，速度在积分之后还会被求解器修改
+        let mut constrained: HashSet<BodyHandle> = contacts
+            .iter()
+            .flat_map(|contact| [contact.body1, contact.body2])
+            .collect();
+        constrained.extend(
+            self.joints
+                .iter()
+                .flat_map(|(_, joint)| std::iter::once(joint.body1).chain(joint.body2)),
+        );
+        constrained.extend(self.mouse_joint.map(|joint| joint.body));
+
+        // 关节与接触在同一组迭代中交替求解
+        self.solver
+            .prepare(&mut self.bodies, contacts, &self.materials, &self.settings);
+        let warm_starting = self.settings.warm_starting;
+        self.joints
+            .for_each_with_bodies(&mut self.bodies, |joint, body1, body2| {
+                joint.prepare(body1, body2, warm_starting, delta_time);
+            });
+        for _ in 0..self.settings.velocity_iterations {
+            self.joints
+                .for_each_with_bodies(&mut self.bodies, |joint, body1, body2| {
+                    joint.solve_velocity(body1, body2);
+                });
+            self.solver.solve_velocities(&mut self.bodies);
+        }
+
+        // 不受约束的物体直接使用积分器给出的位移；受约束的物体把求解器带来的速度变化
+        // 视为本步内的恒定加速度，与外力一起重新积分，所选积分方法对接触中的物体同样生效。
+        // 开启 CCD 的物体只前进到与静态物体首次接触处
+        let motions = free_motions
+            .into_iter()
+            .filter_map(|(handle, start, displacement, free_velocity)| {
+                let body = self.bodies.get(handle)?;
+                let motion = if constrained.contains(&handle) {
+                    let constraint_acceleration = (body.velocity - free_velocity) / delta_time;
+                    let end = integrator.integrate(
+                        start,
+                        &|state: &MotionState| acceleration(state) + constraint_acceleration,
+                        delta_time,
+                    );
+                    end.position - start.position
+                } else {
+                    displacement
                 };
-                let r2 = match obj2.shape {
-                    Shape::Sphere { radius } => radius,
+                let motion = if body.ccd_enabled {
+                    self.ccd_motion(handle, body, motion).unwrap_or(motion)
+                } else {
+                    motion
                 };
-                let rab2 = (r1 + r2) * (r1 + r2);
-                if dist2 < rab2 {
-                    // collision
+                Some((handle, motion))
+            })
+            .collect::<Vec<_>>();
+
+        for (handle, motion) in motions {
+            if let Some(body) = self.bodies.get_mut(handle) {
+                integrate_transform(body, motion, delta_time);
+            }
+        }
+
+        // 运动学物体直接到达目标位姿，没有目标时按速度匀速运动
+        for (handle, target) in kinematic_moves {
+            let Some(body) = self.bodies.get_mut(handle) else {
+                continue;
+            };
+            match target {
+                Some(target) => {
+                    body.transform.translation = target.translation;
+                    body.transform.rotation = target.rotation;
+                }
+                None => {
+                    let motion = body.velocity * delta_time;
+                    integrate_transform(body, motion, delta_time);
+                }
+            }
+        }
+
+        for _ in 0..self.settings.position_iterations {
+            self.joints
+                .for_each_with_bodies(&mut self.bodies, |joint, body1, body2| {
+                    joint.solve_position(body1, body2);
+                });
+            self.solver.solve_positions(&mut self.bodies);
+        }
+
+        self.solver.store_impulses();
+        self.update_sleep(&islands, delta_time);
+
+        // 让两次模拟之间的场景查询用上积分后的包围盒
+        self.update_broad_phase();
+
+        self.diagnostics = self.measure_diagnostics();
+    }
+
+    /// 取出并清空事件队列，应用每帧调用一次；不取出时事件会一直累积
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, PhysicsEvent> {
+        self.events.drain(..)
+    }
+
+    /// 最近一步模拟结束时的能量与动量，尚未模拟时全为零
+    pub fn diagnostics(&self) -> Diagnostics {
+        self.diagnostics
+    }
+
+    /// 按物体当前状态重新统计能量与动量
+    pub fn measure_diagnostics(&self) -> Diagnostics {
+        Diagnostics::measure(self.iter().map(|(_, body)| body), self.gravity)
+    }
+
+    /// 当前使用的粗检测，可用于比较不同算法的候选对数量等
+    pub fn broad_phase(&self) -> &dyn BroadPhase {
+        self.broad_phase.as_ref()
+    }
+
+    /// 按当前物体位置刷新粗检测；模拟每一步都会自动调用，
+    /// 在两次模拟之间增删或移动物体后，若要立即做场景查询需手动调用
+    pub fn update_broad_phase(&mut self) {
+        if self.settings.broad_phase != self.broad_phase_kind {
+            self.broad_phase = self.settings.broad_phase.create();
+            self.broad_phase_kind = self.settings.broad_phase;
+        }
+        self.broad_phase.update(&self.bodies);
+    }
+
+    /// 包围盒与给定区域相交的所有物体
+    ///
+    /// 先用粗检测筛选，再与物体当前的包围盒精确比较；粗检测刷新之后新加入的物体不会被找到。
+    pub fn query_aabb(&self, region: &Aabb) -> Vec<BodyHandle> {
+        let mut result = Vec::new();
+        self.broad_phase.query_aabb(region, &mut |handle| {
+            if self
+                .bodies
+                .get(handle)
+                .is_some_and(|body| body.aabb().intersects(region))
+            {
+                result.push(handle);
+            }
+        });
+        result.sort_unstable();
+        result
+    }
+
+    /// 沿射线 origin + t · direction（0 ≤ t ≤ max_t）找最近的命中物体，t 以 direction 归一化后的长度计
+    ///
+    /// 起点位于某个物体内部时该物体以 t = 0 命中；不满足 filter 的物体被跳过。
+    /// direction 为零向量（或含 NaN）时没有射线方向，返回 None。
+    pub fn raycast(
+        &self,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        max_t: f32,
+        filter: &QueryFilter,
+    ) -> Option<RayHit> {
+        let direction = unit_direction(direction)?;
+        let mut best: Option<RayHit> = None;
+        self.broad_phase
+            .query_ray(origin, direction, max_t, &mut |handle| {
+                let limit = best.map_or(max_t, |hit| hit.t);
+                if let Some(hit) = self.raycast_body(handle, origin, direction, limit, filter) {
+                    if best.is_none_or(|best| hit.t < best.t) {
+                        best = Some(hit);
+                    }
+                }
+                // 之后只需检查比当前命中更近的物体
+                best.map_or(max_t, |hit| hit.t)
+            });
+        best
+    }
+
+    /// 射线穿过的所有物体，按 t 从近到远排序
+    pub fn raycast_all(
+        &self,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        max_t: f32,
+        filter: &QueryFilter,
+    ) -> Vec<RayHit> {
+        let Some(direction) = unit_direction(direction) else {
+            return Vec::new();
+        };
+        let mut hits = Vec::new();
+        self.broad_phase
+            .query_ray(origin, direction, max_t, &mut |handle| {
+                hits.extend(self.raycast_body(handle, origin, direction, max_t, filter));
+                max_t
+            });
+        hits.sort_by(|a, b| a.t.total_cmp(&b.t));
+        hits
+    }
+
+    /// 凸形状（球、胶囊体等）从 transform 处沿 direction 平移扫掠，返回最先碰到的物体
+    ///
+    /// max_t 可以为 f32::INFINITY，表示不限距离；point 为首次接触时的接触点，normal 为被碰物体的表面法线。
+    /// 平面、三角网格等非凸形状不能作为扫掠形状，direction 为零向量时没有扫掠方向，都返回 None。
+    pub fn shape_cast(
+        &self,
+        shape: &Shape,
+        transform: &Transform,
+        direction: Vector3<f32>,
+        max_t: f32,
+        filter: &QueryFilter,
+    ) -> Option<RayHit> {
+        let direction = unit_direction(direction)?;
+        let moving = support_map(shape, transform)?;
+
+        // 起始包围盒沿扫掠方向逐轴拉伸，max_t 为无穷大时不会出现 0 · ∞
+        let mut swept = Aabb::from_support(moving.as_ref());
+        for axis in 0..3 {
+            if direction[axis] > 0.0 {
+                swept.max[axis] += direction[axis] * max_t;
+            } else if direction[axis] < 0.0 {
+                swept.min[axis] += direction[axis] * max_t;
+            }
+        }
+        let mut best: Option<RayHit> = None;
+        self.broad_phase.query_aabb(&swept, &mut |handle| {
+            let Some(body) = self.bodies.get(handle) else {
+                return;
+            };
+            if !filter.accepts(handle, body) {
+                return;
+            }
+            let limit = best.map_or(max_t, |hit| hit.t);
+            if let Some((t, normal, point)) = shape_cast_shape(
+                moving.as_ref(),
+                &body.shape,
+                &body.transform,
+                direction,
+                limit,
+            ) {
+                if best.is_none_or(|best| t < best.t) {
+                    best = Some(RayHit {
+                        body: handle,
+                        point,
+                        normal,
+                        t,
+                    });
                 }
             }
-            for _j in 0..static_objs_len {}
+        });
+        best
+    }
+
+    /// 连续碰撞检测：物体沿本步位移 motion 扫掠，碰到静态物体时返回截断后的位移
+    ///
+    /// 只扫掠平移、忽略旋转。物体停在接触点之后略微嵌入表面，使下一步能生成接触、
+    /// 由求解器处理反弹与摩擦；本步位移小于自身尺寸一半的物体不会穿透，直接跳过。
+    fn ccd_motion(
+        &self,
+        handle: BodyHandle,
+        body: &PhysicsBody,
+        motion: Vector3<f32>,
+    ) -> Option<Vector3<f32>> {
+        let distance = motion.magnitude();
+        let aabb = body.aabb();
+        let size = aabb.max - aabb.min;
+        if distance < size.x.min(size.y).min(size.z) / 2.0 {
+            return None;
         }
 
-        // update all dynamics
+        let direction = motion / distance;
+        let moving = support_map(&body.shape, &body.transform)?;
+        let swept = Aabb::new(aabb.min + motion, aabb.max + motion).union(&aabb);
+        let mut time_of_impact: Option<f32> = None;
+        self.broad_phase.query_aabb(&swept, &mut |other| {
+            let Some(target) = self.bodies.get(other) else {
+                return;
+            };
+            if other == handle
+                || target.inv_mass > 0.0
+                || target.is_sensor
+                || !can_collide((handle, body), (other, target))
+            {
+                return;
+            }
+            let limit = time_of_impact.unwrap_or(distance);
+            if let Some((t, _, _)) = shape_cast_shape(
+                moving.as_ref(),
+                &target.shape,
+                &target.transform,
+                direction,
+                limit,
+            ) {
+                time_of_impact = Some(time_of_impact.map_or(t, |best: f32| best.min(t)));
+            }
+        });
+
+        let t = time_of_impact?;
+        Some(direction * (t + CCD_PENETRATION).min(distance))
     }
 
-    // todo: braod phase and narrow phase
+    fn raycast_body(
+        &self,
+        handle: BodyHandle,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        max_t: f32,
+        filter: &QueryFilter,
+    ) -> Option<RayHit> {
+        let body = self.bodies.get(handle)?;
+        if !filter.accepts(handle, body) {
+            return None;
+        }
+        let (t, normal) = raycast_shape(&body.shape, &body.transform, origin, direction, max_t)?;
+        Some(RayHit {
+            body: handle,
+            point: origin + direction * t,
+            normal,
+            t,
+        })
+    }
+
+    /// 粗检测筛出包围盒重叠的物体对，再逐对做窄相检测，返回接触点、
+    /// 与传感器重叠的物体对（传感器在前）和按接触与关节划分的岛
+    ///
+    /// 由关节相连的物体默认不碰撞；两侧都没有醒着的动态物体的物体对不做检测。
+    /// 含有醒着物体的岛整体唤醒，再补上新唤醒物体此前被跳过的物体对，直到没有物体被唤醒。
+    #[allow(clippy::type_complexity)]
+    fn find_contacts(
+        &mut self,
+    ) -> (
+        Vec<Contact>,
+        Vec<(BodyHandle, BodyHandle)>,
+        Vec<Vec<BodyHandle>>,
+    ) {
+        self.update_broad_phase();
+
+        let connected = self
+            .joints
+            .iter()
+            .filter(|(_, joint)| !joint.collide_connected)
+            .filter_map(|(_, joint)| {
+                let body2 = joint.body2?;
+                Some((joint.body1.min(body2), joint.body1.max(body2)))
+            })
+            .collect::<HashSet<_>>();
+
+        let mut contacts = Vec::new();
+        let mut overlaps = Vec::new();
+        let mut woken: Option<HashSet<BodyHandle>> = None; // 上一轮被唤醒的物体，首轮为 None
+        loop {
+            for &(handle1, handle2) in self.broad_phase.pairs() {
+                if connected.contains(&(handle1, handle2)) {
+                    continue;
+                }
+                let (Some(body1), Some(body2)) =
+                    (self.bodies.get(handle1), self.bodies.get(handle2))
+                else {
+                    continue;
+                };
+                let checked = match &woken {
+                    None => is_active(body1) || is_active(body2),
+                    Some(woken) => {
+                        let was_awake = |handle, body| is_active(body) && !woken.contains(&handle);
+                        !(was_awake(handle1, body1) || was_awake(handle2, body2))
+                            && (woken.contains(&handle1) || woken.contains(&handle2))
+                    }
+                };
+                if !checked {
+                    continue;
+                }
+
+                // 传感器只记录重叠，不产生接触
+                if body1.is_sensor || body2.is_sensor {
+                    if collide_bodies(body1, body2).is_some() {
+                        overlaps.push(if body1.is_sensor {
+                            (handle1, handle2)
+                        } else {
+                            (handle2, handle1)
+                        });
+                    }
+                    continue;
+                }
+
+                // 两侧都不可动时无需求解接触；传感器在此之前处理，静态传感器也能感知运动学物体
+                if body1.inv_mass + body2.inv_mass <= 0.0 {
+                    continue;
+                }
+                if let Some(manifold) = collide_bodies(body1, body2) {
+                    contacts.extend(manifold.points.into_iter().map(|info| Contact {
+                        body1: handle1,
+                        body2: handle2,
+                        info,
+                    }));
+                }
+            }
+
+            let links = contacts
+                .iter()
+                .map(|contact| (contact.body1, contact.body2))
+                .chain(
+                    self.joints
+                        .iter()
+                        .filter_map(|(_, joint)| Some((joint.body1, joint.body2?))),
+                )
+                .collect::<Vec<_>>();
+            let islands = build_islands(&self.bodies, links.iter().copied());
+            let newly_woken = self.wake_islands(&islands, &links);
+            if newly_woken.is_empty() {
+                return (contacts, overlaps, islands);
+            }
+            woken = Some(newly_woken);
+        }
+    }
+
+    // 唤醒含有醒着物体的岛，以及与运动中的运动学物体接触或相连的物体，返回被唤醒的物体；
+    // 后者所在岛的其余物体在下一轮唤醒
+    fn wake_islands(
+        &mut self,
+        islands: &[Vec<BodyHandle>],
+        links: &[(BodyHandle, BodyHandle)],
+    ) -> HashSet<BodyHandle> {
+        let mut woken = HashSet::new();
+        for &(handle1, handle2) in links {
+            for (kinematic, other) in [(handle1, handle2), (handle2, handle1)] {
+                let moving = self.bodies.get(kinematic).is_some_and(|body| {
+                    matches!(body.state, PhysicsState::Kinematic) && is_active(body)
+                });
+                if let Some(body) = self.bodies.get_mut(other).filter(|_| moving) {
+                    if body.is_sleeping() {
+                        body.wake_up();
+                        woken.insert(other);
+                    }
+                }
+            }
+        }
+        for island in islands {
+            let active = island.iter().any(|&handle| {
+                self.bodies
+                    .get(handle)
+                    .is_some_and(|body| !body.is_sleeping())
+            });
+            if !active {
+                continue;
+            }
+            for &handle in island {
+                if let Some(body) = self.bodies.get_mut(handle) {
+                    if body.is_sleeping() {
+                        body.wake_up();
+                        woken.insert(handle);
+                    }
+                }
+            }
+        }
+        woken
+    }
+
+    // 按本步结束时的速度更新休眠计时，岛内所有物体都静止足够久时整个岛休眠
+    fn update_sleep(&mut self, islands: &[Vec<BodyHandle>], delta_time: f32) {
+        for (_, body) in self.bodies.iter_mut() {
+            if matches!(body.state, PhysicsState::Dynamic) && !body.is_sleeping() {
+                body.update_sleep_time(SLEEP_LINEAR_VELOCITY, SLEEP_ANGULAR_VELOCITY, delta_time);
+            }
+        }
+        if !self.settings.sleeping {
+            return;
+        }
+
+        for island in islands {
+            let resting = island.iter().all(|&handle| {
+                self.bodies
+                    .get(handle)
+                    .is_none_or(|body| body.is_sleeping() || body.sleep_time() >= TIME_TO_SLEEP)
+            });
+            if resting {
+                for &handle in island {
+                    if let Some(body) = self.bodies.get_mut(handle) {
+                        body.sleep();
+                    }
+                }
+            }
+        }
+    }
+}
+
+// 醒着的动态物体或正在运动的运动学物体，只有包含这类物体的物体对才需要检测碰撞
+// 查询方向归一化，零向量或含 NaN 的方向返回 None，免得 NaN 传进粗检测
+fn unit_direction(direction: Vector3<f32>) -> Option<Vector3<f32>> {
+    (direction.magnitude2() > 1e-12).then(|| direction.normalize())
+}
+
+fn is_active(body: &PhysicsBody) -> bool {
+    match body.state {
+        PhysicsState::Dynamic => !body.is_sleeping(),
+        PhysicsState::Kinematic => {
+            body.velocity != Vector3::zero() || body.angular_velocity != Vector3::zero()
+        }
+        PhysicsState::Static => false,
+    }
+}
+
+// 按位移平移物体，并用角速度积分姿态
+fn integrate_transform(body: &mut PhysicsBody, motion: Vector3<f32>, delta_time: f32) {
+    body.transform.translation += motion;
+
+    // 四元数积分 q' = q + 0.5 * (0, ω) * q * dt
+    let rotation = body.transform.rotation;
+    let spin = Quaternion::from_sv(0.0, body.angular_velocity) * rotation;
+    body.transform.rotation = (rotation + spin * (0.5 * delta_time)).normalize();
+    body.update_inertia_world();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::PHYSICS_TIMESTEP;
+    use crate::physics::filter::CollisionGroups;
+    use crate::physics::kinematic::KinematicPath;
+    use crate::physics::material::PhysicsMaterial;
+    use crate::physics::shape::{ConvexHull, Cylinder, Plane, Sphere};
+    use crate::physics::trimesh::TriMesh;
+    use std::sync::Arc;
+
+    fn ground() -> Shape {
+        Shape::Plane(Plane {
+            normal: Vector3::unit_y(),
+            distance: 0.0,
+        })
+    }
+
+    fn cylinder() -> Shape {
+        Shape::Cylinder(Cylinder {
+            center: Vector3::zero(),
+            axis: Vector3::unit_y(),
+            radius: 0.5,
+            height: 1.0,
+        })
+    }
+
+    fn unit_box() -> Shape {
+        Shape::Box {
+            half_extents: Vector3::new(0.5, 0.5, 0.5),
+        }
+    }
+
+    #[test]
+    fn test_handles_after_remove() {
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        let a = mgr.create_instance(Transform::identity(), cylinder(), 1.0, false);
+        let b = mgr.create_instance(Transform::identity(), cylinder(), 1.0, false);
+        assert_eq!(mgr.len(), 2);
+
+        assert!(mgr.remove_instance(a).is_some());
+        assert!(mgr.get(a).is_none());
+        assert!(mgr.remove_instance(a).is_none());
+
+        // 槽位被复用，但旧句柄不能访问新物体
+        let c = mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+        assert_eq!(c.index(), a.index());
+        assert_ne!(c, a);
+        assert!(mgr.get(a).is_none());
+        assert!(mgr.get(b).is_some());
+        assert_eq!(mgr.iter().count(), 2);
+    }
+
+    #[test]
+    fn test_cylinder_rests_on_plane() {
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+        let handle = mgr.create_instance(
+            Transform::translation(0.0, 3.0, 0.0),
+            cylinder(),
+            1.0,
+            false,
+        );
+
+        for _ in 0..600 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+
+        // 圆柱体半高为 0.5，最终应停在平面上方
+        let y = mgr.get(handle).unwrap().position().y;
+        assert!((y - 0.5).abs() < 0.05, "cylinder center y = {}", y);
+    }
+
+    #[test]
+    fn test_friction_stops_sliding_cylinder() {
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+        let handle = mgr.create_instance(
+            Transform::translation(0.0, 0.5, 0.0),
+            cylinder(),
+            1.0,
+            false,
+        );
+        mgr.get_mut(handle).unwrap().velocity = Vector3::new(3.0, 0.0, 0.0);
+
+        for _ in 0..180 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+
+        let body = mgr.get(handle).unwrap();
+        assert!(
+            body.velocity.magnitude() < 0.1,
+            "velocity {:?}",
+            body.velocity
+        );
+        assert!(body.position().x > 0.5 && body.position().x < 2.0);
+    }
+
+    #[test]
+    fn test_cylinder_stack_comes_to_rest() {
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+        let stack = (0..3)
+            .map(|i| {
+                mgr.create_instance(
+                    Transform::translation(0.0, 0.5 + i as f32 * 1.05, 0.0),
+                    cylinder(),
+                    1.0,
+                    false,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for _ in 0..600 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+
+        for (i, &handle) in stack.iter().enumerate() {
+            let body = mgr.get(handle).unwrap();
+            assert!(
+                body.velocity.magnitude() < 0.01,
+                "velocity {:?}",
+                body.velocity
+            );
+            assert!(
+                (body.position().y - (0.5 + i as f32)).abs() < 0.02,
+                "cylinder {} y = {}",
+                i,
+                body.position().y
+            );
+        }
+    }
+
+    #[test]
+    fn test_box_stack_rests_flat() {
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+        let stack = (0..2)
+            .map(|i| {
+                mgr.create_instance(
+                    Transform::translation(0.0, 0.6 + i as f32 * 1.1, 0.0),
+                    unit_box(),
+                    1.0,
+                    false,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for _ in 0..600 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+
+        for (i, &handle) in stack.iter().enumerate() {
+            let body = mgr.get(handle).unwrap();
+            assert!(
+                body.velocity.magnitude() < 0.01 && body.angular_velocity.magnitude() < 0.01,
+                "box {} still moving",
+                i
+            );
+            assert!(
+                (body.position().y - (0.5 + i as f32)).abs() < 0.02,
+                "box {} y = {}",
+                i,
+                body.position().y
+            );
+            // 多点接触下盒子不应翻倒
+            let up = body.transform.rotation * Vector3::unit_y();
+            assert!(up.y > 0.999, "box {} tilted: {:?}", i, up);
+        }
+    }
+
+    #[test]
+    fn test_lying_capsule_rests_on_plane() {
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+        let handle = mgr.create_instance(
+            Transform {
+                translation: Vector3::new(0.0, 1.0, 0.0),
+                rotation: Quaternion::from_angle_z(Deg(90.0)),
+                scale: Vector3::new(1.0, 1.0, 1.0),
+            },
+            Shape::Capsule {
+                radius: 0.25,
+                half_height: 0.5,
+            },
+            1.0,
+            false,
+        );
+
+        for _ in 0..600 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+
+        // 两端同时支撑，胶囊体保持水平
+        let body = mgr.get(handle).unwrap();
+        assert!(
+            (body.position().y - 0.25).abs() < 0.02,
+            "y = {}",
+            body.position().y
+        );
+        let axis = body.transform.rotation * Vector3::unit_y();
+        assert!(axis.y.abs() < 0.01, "capsule tilted: {:?}", axis);
+        assert!(body.velocity.magnitude() < 0.01);
+    }
+
+    #[test]
+    fn test_convex_hull_rests_on_plane() {
+        let corners = (0..8).map(|i| {
+            Vector3::new(
+                if i & 1 != 0 { 0.5 } else { -0.5 },
+                if i & 2 != 0 { 0.5 } else { -0.5 },
+                if i & 4 != 0 { 0.5 } else { -0.5 },
+            )
+        });
+
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+        let hull = mgr.create_instance(
+            Transform::translation(0.0, 1.0, 0.0),
+            Shape::ConvexHull(ConvexHull::new(corners)),
+            1.0,
+            false,
+        );
+        // 凸包与盒子之间走 GJK/EPA
+        let top = mgr.create_instance(
+            Transform::translation(0.0, 2.5, 0.0),
+            unit_box(),
+            1.0,
+            false,
+        );
+
+        for _ in 0..600 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+
+        let body = mgr.get(hull).unwrap();
+        assert!(
+            (body.position().y - 0.5).abs() < 0.02,
+            "hull y = {}",
+            body.position().y
+        );
+        let up = body.transform.rotation * Vector3::unit_y();
+        assert!(up.y > 0.99, "hull tilted: {:?}", up);
+
+        let body = mgr.get(top).unwrap();
+        assert!(
+            (body.position().y - 1.5).abs() < 0.05,
+            "box y = {}",
+            body.position().y
+        );
+    }
+
+    #[test]
+    fn test_box_slides_smoothly_across_mesh_seams() {
+        // 4x4 格的平坦三角网格地面，每格两个三角形
+        let n = 4;
+        let positions = (0..=n)
+            .flat_map(|i| (0..=n).map(move |j| Vector3::new(i as f32 - 2.0, 0.0, j as f32 - 2.0)))
+            .collect::<Vec<_>>();
+        let stride = n + 1;
+        let indices = (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .flat_map(|(i, j)| {
+                let v0 = i * stride + j;
+                [
+                    v0,
+                    v0 + 1,
+                    v0 + stride,
+                    v0 + stride,
+                    v0 + 1,
+                    v0 + stride + 1,
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        mgr.create_instance(
+            Transform::identity(),
+            Shape::TriMesh(Arc::new(TriMesh::new(positions, indices))),
+            0.0,
+            true,
+        );
+        let handle = mgr.create_instance(
+            Transform::translation(-1.2, 0.49, 0.1),
+            unit_box(),
+            1.0,
+            false,
+        );
+        mgr.get_mut(handle).unwrap().velocity = Vector3::new(3.0, 0.0, 0.0);
+
+        // 滑过多条内部边时不应被边“绊”起
+        for _ in 0..120 {
+            mgr.update(PHYSICS_TIMESTEP);
+            let body = mgr.get(handle).unwrap();
+            assert!(body.velocity.y.abs() < 0.1, "bumped: {:?}", body.velocity);
+            assert!(body.angular_velocity.magnitude() < 0.1);
+        }
+
+        let body = mgr.get(handle).unwrap();
+        assert!(
+            (body.position().y - 0.5).abs() < 0.02,
+            "y = {}",
+            body.position().y
+        );
+        assert!(body.position().x > -0.5);
+    }
+
+    #[test]
+    fn test_box_rests_on_height_field() {
+        // 6×6 的平坦高度场，高度缩放后位于 y = 0.5，再整体下移 1，表面在 y = -0.5
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        mgr.create_instance(
+            Transform::translation(0.0, -1.0, 0.0),
+            Shape::HeightField {
+                rows: 6,
+                cols: 6,
+                heights: vec![0.25; 36].into(),
+                scale: Vector3::new(1.0, 2.0, 1.0),
+            },
+            0.0,
+            true,
+        );
+        // 盒子压在多个格子的接缝上
+        let handle = mgr.create_instance(
+            Transform::translation(0.0, 0.2, 0.0),
+            unit_box(),
+            1.0,
+            false,
+        );
+
+        for _ in 0..180 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+
+        let body = mgr.get(handle).unwrap();
+        assert!(body.position().y.abs() < 0.02, "y = {}", body.position().y);
+        assert!(body.velocity.magnitude() < 0.05);
+        assert!(body.angular_velocity.magnitude() < 0.05);
+    }
+
+    #[test]
+    fn test_broad_phases_produce_same_simulation() {
+        // 一堆散落的球和盒子落到地面上，两种粗检测得到的模拟结果应完全一致
+        let simulate = |kind: BroadPhaseKind| {
+            let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+            mgr.settings.broad_phase = kind;
+            mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+            let handles = (0..40)
+                .map(|i| {
+                    let shape = if i % 2 == 0 {
+                        unit_box()
+                    } else {
+                        Shape::Sphere(Sphere {
+                            center: Vector3::zero(),
+                            radius: 0.5,
+                        })
+                    };
+                    let position = Vector3::new(
+                        (i % 4) as f32 * 0.8,
+                        1.0 + (i / 4) as f32 * 1.2,
+                        (i % 3) as f32 * 0.7,
+                    );
+                    mgr.create_instance(
+                        Transform::translation(position.x, position.y, position.z),
+                        shape,
+                        1.0,
+                        false,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            for _ in 0..120 {
+                mgr.update(PHYSICS_TIMESTEP);
+            }
+
+            // 区域查询与逐个比较包围盒的结果一致
+            let region = Aabb::new(Vector3::new(-1.0, 0.0, -1.0), Vector3::new(1.0, 2.0, 1.0));
+            let expected = mgr
+                .iter()
+                .filter(|(_, body)| body.aabb().intersects(&region))
+                .map(|(handle, _)| handle)
+                .collect::<Vec<_>>();
+            assert_eq!(mgr.query_aabb(&region), expected);
+
+            handles
+                .iter()
+                .map(|&handle| mgr.get(handle).unwrap().position())
+                .collect::<Vec<_>>()
+        };
+
+        let sweep_and_prune = simulate(BroadPhaseKind::SweepAndPrune);
+        let aabb_tree = simulate(BroadPhaseKind::AabbTree);
+        assert_eq!(sweep_and_prune, aabb_tree);
+    }
+
+    #[test]
+    fn test_world_raycast_and_shape_cast() {
+        for kind in BroadPhaseKind::ALL {
+            let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+            mgr.settings.broad_phase = kind;
+            let floor = mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+            let near =
+                mgr.create_instance(Transform::translation(0.0, 2.0, 0.0), unit_box(), 1.0, true);
+            let far =
+                mgr.create_instance(Transform::translation(0.0, 5.0, 0.0), cylinder(), 1.0, true);
+            mgr.create_instance(Transform::translation(4.0, 2.0, 0.0), unit_box(), 1.0, true);
+            mgr.update_broad_phase();
+
+            // 竖直向下的射线依次穿过圆柱、盒子和地面，最近的是圆柱
+            let origin = Vector3::new(0.0, 10.0, 0.0);
+            let down = Vector3::new(0.0, -2.0, 0.0);
+            let hit = mgr
+                .raycast(origin, down, 100.0, &QueryFilter::default())
+                .unwrap();
+            assert_eq!(hit.body, far);
+            assert!((hit.t - 4.5).abs() < 1e-4);
+            assert!((hit.point - Vector3::new(0.0, 5.5, 0.0)).magnitude() < 1e-4);
+            assert!((hit.normal - Vector3::unit_y()).magnitude() < 1e-4);
+
+            let all = mgr.raycast_all(origin, down, 100.0, &QueryFilter::default());
+            assert_eq!(
+                all.iter().map(|hit| hit.body).collect::<Vec<_>>(),
+                vec![far, near, floor]
+            );
+            assert!(mgr
+                .raycast(origin, down, 4.0, &QueryFilter::default())
+                .is_none());
+
+            // 球从侧面扫向盒子，离开最近物体的方向什么也碰不到
+            let sphere = Shape::Sphere(Sphere {
+                center: Vector3::zero(),
+                radius: 0.25,
+            });
+            let start = Transform::translation(-3.0, 2.0, 0.0);
+            let hit = mgr
+                .shape_cast(
+                    &sphere,
+                    &start,
+                    Vector3::unit_x(),
+                    10.0,
+                    &QueryFilter::default(),
+                )
+                .unwrap();
+            assert_eq!(hit.body, near);
+            assert!((hit.t - 2.25).abs() < 1e-3);
+            assert!((hit.normal + Vector3::unit_x()).magnitude() < 1e-2);
+            assert!(mgr
+                .shape_cast(
+                    &sphere,
+                    &start,
+                    -Vector3::unit_x(),
+                    10.0,
+                    &QueryFilter::default()
+                )
+                .is_none());
+
+            // 不限距离的查询
+            let hit = mgr
+                .shape_cast(
+                    &sphere,
+                    &start,
+                    Vector3::unit_x(),
+                    f32::INFINITY,
+                    &QueryFilter::default(),
+                )
+                .unwrap();
+            assert_eq!(hit.body, near);
+            assert!((hit.t - 2.25).abs() < 1e-3);
+            let hit = mgr
+                .shape_cast(
+                    &sphere,
+                    &Transform::translation(-3.0, 10.0, 0.0),
+                    -Vector3::unit_y(),
+                    f32::INFINITY,
+                    &QueryFilter::default(),
+                )
+                .unwrap();
+            assert_eq!(hit.body, floor);
+            assert!((hit.t - 9.75).abs() < 1e-3);
+            assert!(mgr
+                .shape_cast(
+                    &sphere,
+                    &start,
+                    Vector3::unit_y(),
+                    f32::INFINITY,
+                    &QueryFilter::default()
+                )
+                .is_none());
+            assert_eq!(
+                mgr.raycast(origin, down, f32::INFINITY, &QueryFilter::default())
+                    .map(|hit| hit.body),
+                Some(far)
+            );
+
+            // 零方向没有射线，即使起点在物体内部也不命中
+            let inside = Vector3::new(0.0, 2.0, 0.0);
+            assert!(mgr
+                .raycast(inside, Vector3::zero(), 100.0, &QueryFilter::default())
+                .is_none());
+            assert!(mgr
+                .raycast_all(inside, Vector3::zero(), 100.0, &QueryFilter::default())
+                .is_empty());
+            assert!(mgr
+                .shape_cast(
+                    &sphere,
+                    &start,
+                    Vector3::zero(),
+                    10.0,
+                    &QueryFilter::default()
+                )
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn test_mouse_joint_drags_body_to_target() {
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+        let cube = mgr.create_instance(
+            Transform::translation(0.0, 0.5, 0.0),
+            unit_box(),
+            2.0,
+            false,
+        );
+
+        // 抓住盒子顶面的一角，拉到空中
+        let grab = Vector3::new(0.4, 1.0, 0.4);
+        let mut joint = MouseJoint::new(cube, mgr.get(cube).unwrap(), grab);
+        joint.target = Vector3::new(1.0, 3.0, 0.0);
+        mgr.mouse_joint = Some(joint);
+        for _ in 0..600 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+
+        // 拖拽点因重力略低于目标点，盒子挂在拖拽点下方，摆动逐渐停止
+        let body = mgr.get(cube).unwrap();
+        let anchor = joint.world_anchor(body);
+        assert!(
+            (anchor - joint.target).magnitude() < 0.1,
+            "anchor = {:?}",
+            anchor
+        );
+        assert!(body.position().y < anchor.y);
+        assert!(body.velocity.magnitude() < 0.1);
+
+        // 移除物体时约束一起失效
+        mgr.remove_instance(cube);
+        assert!(mgr.mouse_joint.is_none());
+    }
+
+    #[test]
+    fn test_mouse_joint_damps_spin() {
+        // 无重力，抓住质心拖拽：约束不产生力矩，转动只由角速度阻尼消耗
+        let mut mgr = PhyMgr::new(Vector3::zero());
+        let cube = mgr.create_instance(Transform::identity(), unit_box(), 1.0, false);
+        mgr.get_mut(cube).unwrap().angular_velocity = Vector3::new(0.0, 0.0, 5.0);
+        mgr.mouse_joint = Some(MouseJoint::new(
+            cube,
+            mgr.get(cube).unwrap(),
+            Vector3::zero(),
+        ));
+        for _ in 0..60 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+
+        // 一秒内转速降到初始的 2% 以下
+        let spin = mgr.get(cube).unwrap().angular_velocity.magnitude();
+        assert!(spin < 0.1, "spin = {}", spin);
+    }
+
+    #[test]
+    fn test_ccd_stops_fast_bodies_at_surface() {
+        // 子弹每步移动 5 米，返回第一步之后与 60 步之后包围盒的最低点；不反弹，便于检查停留位置
+        let fire = |target: Shape, target_transform: Transform, shape: Shape, ccd_enabled: bool| {
+            let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+            let inelastic = mgr.materials.register(
+                "inelastic",
+                PhysicsMaterial {
+                    restitution: 0.0,
+                    ..Default::default()
+                },
+            );
+            let target = mgr.create_instance(target_transform, target, 0.0, true);
+            mgr.get_mut(target).unwrap().material = inelastic;
+            let bullet =
+                mgr.create_instance(Transform::translation(0.0, 2.0, 0.0), shape, 0.1, false);
+            let body = mgr.get_mut(bullet).unwrap();
+            body.velocity = Vector3::new(5.0, -300.0, 0.0);
+            body.material = inelastic;
+            body.ccd_enabled = ccd_enabled;
+
+            mgr.update(PHYSICS_TIMESTEP);
+            let first = mgr.get(bullet).unwrap().aabb().min.y;
+            for _ in 0..60 {
+                mgr.update(PHYSICS_TIMESTEP);
+            }
+            (first, mgr.get(bullet).unwrap().aabb().min.y)
+        };
+
+        let bullet = || {
+            Shape::Sphere(Sphere {
+                center: Vector3::zero(),
+                radius: 0.05,
+            })
+        };
+        let small_cylinder = || {
+            Shape::Cylinder(Cylinder {
+                center: Vector3::zero(),
+                axis: Vector3::unit_y(),
+                radius: 0.05,
+                height: 0.1,
+            })
+        };
+        // 顶面在 y = 0 的薄板
+        let thin_slab = Shape::Box {
+            half_extents: Vector3::new(50.0, 0.05, 50.0),
+        };
+        let slab_transform = Transform::translation(0.0, -0.05, 0.0);
+
+        for shape in [bullet(), small_cylinder()] {
+            // 不开 CCD 时：第一步就深深陷入地面，薄板则被直接穿过
+            let (first, _) = fire(ground(), Transform::identity(), shape.clone(), false);
+            assert!(first < -1.0);
+            let (_, last) = fire(thin_slab.clone(), slab_transform, shape.clone(), false);
+            assert!(last < -1.0);
+
+            // 开启 CCD 后停在表面
+            let (first, last) = fire(ground(), Transform::identity(), shape.clone(), true);
+            assert!(first.abs() < 0.02, "first = {}", first);
+            assert!(last.abs() < 0.02, "last = {}", last);
+            let (first, last) = fire(thin_slab.clone(), slab_transform, shape, true);
+            assert!(first.abs() < 0.02, "first = {}", first);
+            assert!(last.abs() < 0.02, "last = {}", last);
+        }
+    }
+
+    #[test]
+    fn test_gyroscopic_effect_conserves_angular_momentum() {
+        // 失重环境中绕非主轴自由旋转的扁盒子
+        let spin = |gyroscopic: bool| {
+            let mut mgr = PhyMgr::new(Vector3::zero());
+            mgr.settings.gyroscopic = gyroscopic;
+            let shape = Shape::Box {
+                half_extents: Vector3::new(1.0, 0.2, 0.5),
+            };
+            let handle = mgr.create_instance(Transform::identity(), shape, 1.0, false);
+            mgr.get_mut(handle).unwrap().angular_velocity = Vector3::new(1.0, 5.0, 0.5);
+
+            let momentum = |mgr: &PhyMgr| {
+                let body = mgr.get(handle).unwrap();
+                body.inv_inertia_world().invert().unwrap() * body.angular_velocity
+            };
+            let initial = momentum(&mgr);
+            let mut max_error: f32 = 0.0;
+            for _ in 0..240 {
+                mgr.update(PHYSICS_TIMESTEP);
+                max_error = max_error.max((momentum(&mgr) - initial).magnitude());
+            }
+            max_error / initial.magnitude()
+        };
+
+        // 隐式积分带有少量数值耗散，角动量仍基本守恒；忽略陀螺项时角速度不变，角动量随姿态转动
+        let with = spin(true);
+        let without = spin(false);
+        assert!(with < 0.05, "error with gyroscopic = {}", with);
+        assert!(without > 0.1, "error without gyroscopic = {}", without);
+    }
+
+    #[test]
+    fn test_integrators_in_world() {
+        for kind in IntegratorKind::ALL {
+            let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+            mgr.settings.integrator = kind;
+            let ground = Shape::Plane(Plane {
+                normal: Vector3::unit_y(),
+                distance: 0.0,
+            });
+            mgr.create_instance(Transform::identity(), ground, 0.0, true);
+            let sphere = Shape::Sphere(Sphere {
+                center: Vector3::zero(),
+                radius: 0.5,
+            });
+            let mut transform = Transform::identity();
+            transform.translation = Vector3::new(0.0, 10.0, 0.0);
+            let handle = mgr.create_instance(transform, sphere, 1.0, false);
+
+            // 自由落体 0.5 秒
+            for _ in 0..30 {
+                mgr.update(PHYSICS_TIMESTEP);
+            }
+            let height = mgr.get(handle).unwrap().position().y;
+            let error = (height - (10.0 - 0.5 * 9.8 * 0.25)).abs();
+            if kind == IntegratorKind::SemiImplicitEuler {
+                assert!(
+                    error < 9.8 * PHYSICS_TIMESTEP * 0.5,
+                    "{} error {}",
+                    kind.name(),
+                    error
+                );
+            } else {
+                assert!(error < 1e-3, "{} error {}", kind.name(), error);
+            }
+
+            // 接触冲量对所有积分方法都生效
+            for _ in 0..240 {
+                mgr.update(PHYSICS_TIMESTEP);
+            }
+            let body = mgr.get(handle).unwrap();
+            assert!((body.position().y - 0.5).abs() < 0.02, "{}", kind.name());
+            assert!(body.velocity.magnitude() < 0.05, "{}", kind.name());
+        }
+    }
+
+    #[test]
+    fn test_integrators_in_contact() {
+        // 无摩擦的球贴着地面滑行，受空气阻尼减速：x(t) = v0 (1 - e^(-kt)) / k
+        let slide = |kind: IntegratorKind| {
+            let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+            mgr.settings.integrator = kind;
+            mgr.air_friction = 1.0;
+            let ice = mgr.materials.register(
+                "ice",
+                PhysicsMaterial {
+                    static_friction: 0.0,
+                    dynamic_friction: 0.0,
+                    ..Default::default()
+                },
+            );
+            let ground = mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+            mgr.get_mut(ground).unwrap().material = ice;
+            let sphere = Shape::Sphere(Sphere {
+                center: Vector3::zero(),
+                radius: 0.5,
+            });
+            let ball =
+                mgr.create_instance(Transform::translation(0.0, 0.5, 0.0), sphere, 1.0, false);
+            let body = mgr.get_mut(ball).unwrap();
+            body.material = ice;
+            body.velocity = Vector3::new(5.0, 0.0, 0.0);
+
+            for _ in 0..60 {
+                mgr.update(PHYSICS_TIMESTEP);
+            }
+            let position = mgr.get(ball).unwrap().position();
+            // 整个过程都与地面接触
+            assert!(
+                (position.y - 0.5).abs() < 0.02,
+                "{} y = {}",
+                kind.name(),
+                position.y
+            );
+            position.x
+        };
+
+        let expected = 5.0 * (1.0 - (-1.0f32).exp());
+        let errors = IntegratorKind::ALL.map(|kind| (slide(kind) - expected).abs());
+        // 接触中的物体同样按所选方法积分，三种方法的结果各不相同，高阶方法误差更小
+        assert!(errors[0] > 1e-3, "errors = {:?}", errors);
+        assert!(errors[1] < errors[0] * 0.5, "errors = {:?}", errors);
+        assert!(errors[2] < errors[0] * 0.5, "errors = {:?}", errors);
+        assert!(
+            (errors[1] - errors[2]).abs() > 1e-5,
+            "errors = {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_conservation() {
+        // 失重环境中两球斜碰：完全弹性时动量、角动量与动能都守恒
+        let mut mgr = PhyMgr::new(Vector3::zero());
+        let sphere = Shape::Sphere(Sphere {
+            center: Vector3::zero(),
+            radius: 0.5,
+        });
+        let mut transform = Transform::identity();
+        transform.translation = Vector3::new(-2.0, 0.0, 0.0);
+        let left = mgr.create_instance(transform, sphere.clone(), 1.0, false);
+        transform.translation = Vector3::new(2.0, 0.4, 0.0);
+        let right = mgr.create_instance(transform, sphere, 2.0, false);
+        let elastic = mgr.materials.register(
+            "elastic",
+            PhysicsMaterial {
+                static_friction: 0.0,
+                dynamic_friction: 0.0,
+                restitution: 1.0,
+                ..Default::default()
+            },
+        );
+        for (handle, velocity) in [(left, 3.0), (right, -1.0)] {
+            let body = mgr.get_mut(handle).unwrap();
+            body.velocity = Vector3::new(velocity, 0.0, 0.0);
+            body.material = elastic;
+        }
+
+        let initial = mgr.measure_diagnostics();
+        for _ in 0..120 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+        let after = mgr.diagnostics();
+        // 确实发生了碰撞
+        assert!(mgr.get(left).unwrap().velocity.y.abs() > 0.1);
+        assert!((after.linear_momentum - initial.linear_momentum).magnitude() < 1e-3);
+        assert!((after.angular_momentum - initial.angular_momentum).magnitude() < 1e-2);
+        assert!(
+            (after.total_energy() - initial.total_energy()).abs() < 0.05 * initial.total_energy()
+        );
+
+        // 自由落体时机械能守恒
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        mgr.settings.integrator = IntegratorKind::VelocityVerlet;
+        let sphere = Shape::Sphere(Sphere {
+            center: Vector3::zero(),
+            radius: 0.5,
+        });
+        mgr.create_instance(Transform::identity(), sphere, 1.0, false);
+        let initial = mgr.measure_diagnostics();
+        for _ in 0..60 {
+            mgr.update(PHYSICS_TIMESTEP);
+            assert!((mgr.diagnostics().total_energy() - initial.total_energy()).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_collision_filtering() {
+        const DEBRIS: u32 = 1 << 1;
+        let sphere = Shape::Sphere(Sphere {
+            center: Vector3::zero(),
+            radius: 0.5,
+        });
+        for kind in BroadPhaseKind::ALL {
+            let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+            mgr.settings.broad_phase = kind;
+            mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+            // 两块碎片叠放，互相穿过后都落到地面上
+            let debris = [0.5, 0.9].map(|y| {
+                let handle = mgr.create_instance(
+                    Transform::translation(0.0, y, 0.0),
+                    sphere.clone(),
+                    1.0,
+                    false,
+                );
+                mgr.get_mut(handle).unwrap().collision_groups =
+                    CollisionGroups::new(DEBRIS, !DEBRIS);
+                handle
+            });
+            // 被忽略的一对同样互相穿过
+            let pair = [0.5, 0.9].map(|y| {
+                mgr.create_instance(
+                    Transform::translation(3.0, y, 0.0),
+                    sphere.clone(),
+                    1.0,
+                    false,
+                )
+            });
+            assert!(mgr.set_pair_ignored(pair[1], pair[0], true));
+
+            mgr.update_broad_phase();
+            assert!(mgr.broad_phase().pairs().iter().all(|&(handle1, handle2)| {
+                (handle1, handle2) != (debris[0], debris[1])
+                    && (handle1, handle2) != (pair[0], pair[1])
+            }));
+            for _ in 0..60 {
+                mgr.update(PHYSICS_TIMESTEP);
+            }
+            for handle in debris.into_iter().chain(pair) {
+                let y = mgr.get(handle).unwrap().position().y;
+                assert!((y - 0.5).abs() < 0.02, "{}: y = {}", kind.name(), y);
+            }
+
+            // 射线只命中与查询分组匹配的物体，也可以排除指定物体
+            let origin = Vector3::new(0.0, 5.0, 0.0);
+            let down = -Vector3::unit_y();
+            let solid = QueryFilter::new(CollisionGroups::new(u32::MAX, !DEBRIS));
+            let hit = mgr.raycast(origin, down, 10.0, &solid).unwrap();
+            assert!(hit.point.y.abs() < 1e-3);
+            let hit = mgr
+                .raycast(origin, down, 10.0, &QueryFilter::default())
+                .unwrap();
+            assert!(debris.contains(&hit.body));
+            let origin = Vector3::new(3.0, 5.0, 0.0);
+            let all = mgr.raycast_all(
+                origin,
+                down,
+                10.0,
+                &QueryFilter::default().excluding(pair[0]),
+            );
+            assert!(all.iter().all(|hit| hit.body != pair[0]) && all.len() == 2);
+
+            // 取消忽略后重新发生碰撞，两个球被推开
+            mgr.set_pair_ignored(pair[0], pair[1], false);
+            for _ in 0..60 {
+                mgr.update(PHYSICS_TIMESTEP);
+            }
+            let (a, b) = (mgr.get(pair[0]).unwrap(), mgr.get(pair[1]).unwrap());
+            assert!((a.position() - b.position()).magnitude() > 0.9);
+        }
+    }
+
+    #[test]
+    fn test_sensor_and_contact_events() {
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        let ground = mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+        let sensor =
+            mgr.create_instance(Transform::translation(0.0, 3.0, 0.0), unit_box(), 0.0, true);
+        mgr.get_mut(sensor).unwrap().is_sensor = true;
+        let ball = mgr.create_instance(
+            Transform::translation(0.0, 5.0, 0.0),
+            Shape::Sphere(Sphere {
+                center: Vector3::zero(),
+                radius: 0.5,
+            }),
+            1.0,
+            false,
+        );
+        // 不反弹，只接触一次
+        let inelastic = mgr.materials.register(
+            "inelastic",
+            PhysicsMaterial {
+                restitution: 0.0,
+                ..Default::default()
+            },
+        );
+        for handle in [ground, ball] {
+            mgr.get_mut(handle).unwrap().material = inelastic;
+        }
+
+        let mut events = Vec::new();
+        for _ in 0..120 {
+            mgr.update(PHYSICS_TIMESTEP);
+            events.extend(mgr.drain_events());
+        }
+        // 球穿过传感器落到地面上
+        assert!((mgr.get(ball).unwrap().position().y - 0.5).abs() < 0.02);
+        let count = |event: PhysicsEvent| events.iter().filter(|&&e| e == event).count();
+        let enter = PhysicsEvent::TriggerEnter {
+            sensor,
+            other: ball,
+        };
+        let exit = PhysicsEvent::TriggerExit {
+            sensor,
+            other: ball,
+        };
+        assert_eq!((count(enter), count(exit)), (1, 1));
+        assert!(
+            count(PhysicsEvent::TriggerStay {
+                sensor,
+                other: ball
+            }) > 0
+        );
+        let position = |event: PhysicsEvent| events.iter().position(|&e| e == event);
+        assert!(position(enter) < position(exit));
+        let started = PhysicsEvent::ContactStarted {
+            body1: ground,
+            body2: ball,
+        };
+        assert_eq!(count(started), 1);
+        assert!(mgr.get(ball).unwrap().is_sleeping());
+
+        // 休眠不会结束接触，移除物体才会
+        for _ in 0..60 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+        assert!(mgr.drain_events().next().is_none());
+        mgr.remove_instance(ball);
+        mgr.update(PHYSICS_TIMESTEP);
+        assert_eq!(
+            mgr.drain_events().collect::<Vec<_>>(),
+            vec![PhysicsEvent::ContactEnded {
+                body1: ground,
+                body2: ball,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_static_sensor_detects_kinematic_body() {
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        let sensor =
+            mgr.create_instance(Transform::translation(0.0, 0.5, 0.0), unit_box(), 0.0, true);
+        mgr.get_mut(sensor).unwrap().is_sensor = true;
+        // 运动学物体以 4 m/s 沿 x 轴穿过传感器
+        let mover = mgr.create_kinematic(Transform::translation(-3.0, 0.5, 0.0), unit_box());
+
+        let mut events = Vec::new();
+        for _ in 0..90 {
+            let mut target = mgr.get(mover).unwrap().transform;
+            target.translation.x += 4.0 * PHYSICS_TIMESTEP;
+            mgr.get_mut(mover).unwrap().set_kinematic_target(target);
+            mgr.update(PHYSICS_TIMESTEP);
+            events.extend(mgr.drain_events());
+        }
+        assert!(mgr.get(mover).unwrap().position().x > 2.0);
+        let count = |event: PhysicsEvent| events.iter().filter(|&&e| e == event).count();
+        let enter = PhysicsEvent::TriggerEnter {
+            sensor,
+            other: mover,
+        };
+        let exit = PhysicsEvent::TriggerExit {
+            sensor,
+            other: mover,
+        };
+        assert_eq!((count(enter), count(exit)), (1, 1));
+        let position = |event: PhysicsEvent| events.iter().position(|&e| e == event);
+        assert!(position(enter) < position(exit));
+        // 传感器不产生接触
+        assert!(events.iter().all(|event| matches!(
+            event,
+            PhysicsEvent::TriggerEnter { .. }
+                | PhysicsEvent::TriggerStay { .. }
+                | PhysicsEvent::TriggerExit { .. }
+        )));
+    }
+
+    #[test]
+    fn test_kinematic_bodies() {
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+        // 悬空的旋转平台，每 4 秒绕 y 轴转一周，上面放一个偏离转轴的盒子
+        let platform = mgr.create_kinematic(
+            Transform::translation(0.0, 2.0, 0.0),
+            Shape::Box {
+                half_extents: Vector3::new(3.0, 0.25, 3.0),
+            },
+        );
+        assert!(mgr.set_kinematic_path(
+            platform,
+            KinematicPath::rotation(Vector3::new(0.0, 2.0, 0.0), Vector3::unit_y(), 4.0)
+        ));
+        let rider = mgr.create_instance(
+            Transform::translation(1.5, 2.75, 0.0),
+            unit_box(),
+            1.0,
+            false,
+        );
+        let grippy = mgr.materials.register(
+            "grippy",
+            PhysicsMaterial {
+                static_friction: 1.0,
+                dynamic_friction: 1.0,
+                ..Default::default()
+            },
+        );
+        mgr.get_mut(rider).unwrap().material = grippy;
+        // 地面上静止的球等平台外的推杆扫过时被推开
+        let ball = mgr.create_instance(
+            Transform::translation(0.0, 0.5, 3.0),
+            Shape::Sphere(Sphere {
+                center: Vector3::zero(),
+                radius: 0.5,
+            }),
+            1.0,
+            false,
+        );
+        let pusher = mgr.create_kinematic(
+            Transform::translation(-3.0, 0.5, 3.0),
+            Shape::Box {
+                half_extents: Vector3::new(0.5, 0.5, 0.5),
+            },
+        );
+
+        for step in 0..120 {
+            if step >= 60 {
+                // 后一秒由用户代码逐步设置目标，推杆以 4 m/s 沿 x 轴前进
+                let mut target = mgr.get(pusher).unwrap().transform;
+                target.translation.x += 4.0 * PHYSICS_TIMESTEP;
+                mgr.get_mut(pusher).unwrap().set_kinematic_target(target);
+            }
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+
+        // 平台转过半周，不受重力影响，速度由位移反推
+        let body = mgr.get(platform).unwrap();
+        assert!((body.position() - Vector3::new(0.0, 2.0, 0.0)).magnitude() < 1e-4);
+        let expected = std::f32::consts::TAU / 4.0;
+        assert!((body.angular_velocity - Vector3::new(0.0, expected, 0.0)).magnitude() < 1e-3);
+        // 盒子被摩擦带着转到了转轴另一侧
+        let rider = mgr.get(rider).unwrap();
+        assert!(rider.position().x < -1.2, "rider at {:?}", rider.position());
+        assert!((rider.position().y - 2.75).abs() < 0.05);
+
+        // 推杆以无限大质量推动球，自身按目标匀速前进
+        let pusher = mgr.get(pusher).unwrap();
+        assert!((pusher.velocity - Vector3::new(4.0, 0.0, 0.0)).magnitude() < 1e-3);
+        assert!((pusher.position().x - 1.0).abs() < 1e-3);
+        let ball = mgr.get(ball).unwrap();
+        assert!(ball.position().x > pusher.position().x + 0.9);
+    }
+
+    #[test]
+    fn test_islands_sleep_and_wake() {
+        let mut mgr = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+        mgr.create_instance(Transform::identity(), ground(), 0.0, true);
+        let stack = (0..2)
+            .map(|i| {
+                mgr.create_instance(
+                    Transform::translation(0.0, 0.5 + i as f32, 0.0),
+                    unit_box(),
+                    1.0,
+                    false,
+                )
+            })
+            .collect::<Vec<_>>();
+        // 远处单独静止的盒子自成一岛
+        let lonely = mgr.create_instance(
+            Transform::translation(5.0, 0.5, 0.0),
+            unit_box(),
+            1.0,
+            false,
+        );
+
+        for _ in 0..120 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+        for &handle in stack.iter().chain([&lonely]) {
+            assert!(mgr.get(handle).unwrap().is_sleeping());
+        }
+
+        // 落下的球只唤醒它所在的岛
+        let ball = mgr.create_instance(
+            Transform::translation(0.0, 3.0, 0.0),
+            Shape::Sphere(Sphere {
+                center: Vector3::zero(),
+                radius: 0.5,
+            }),
+            1.0,
+            false,
+        );
+        let mut woken = false;
+        for _ in 0..30 {
+            mgr.update(PHYSICS_TIMESTEP);
+            woken |= stack
+                .iter()
+                .all(|&handle| !mgr.get(handle).unwrap().is_sleeping());
+        }
+        assert!(woken);
+        assert!(mgr.get(lonely).unwrap().is_sleeping());
+        // 休眠期间没有穿透
+        assert!((mgr.get(stack[1]).unwrap().position().y - 1.5).abs() < 0.05);
+
+        for _ in 0..180 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+        assert!(mgr.get(ball).unwrap().is_sleeping());
+
+        // 移除下面的盒子后，上面的盒子被唤醒并落下
+        mgr.remove_instance(stack[0]);
+        assert!(!mgr.get(stack[1]).unwrap().is_sleeping());
+        for _ in 0..120 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+        assert!((mgr.get(stack[1]).unwrap().position().y - 0.5).abs() < 0.05);
+
+        // 冲量唤醒休眠的物体
+        mgr.update(PHYSICS_TIMESTEP);
+        let body = mgr.get_mut(lonely).unwrap();
+        body.apply_impulse(Vector3::new(2.0, 0.0, 0.0), body.position());
+        for _ in 0..10 {
+            mgr.update(PHYSICS_TIMESTEP);
+        }
+        assert!(mgr.get(lonely).unwrap().position().x > 5.1);
+    }
 }
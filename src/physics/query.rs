@@ -0,0 +1,651 @@
+use crate::common::Transform;
+use crate::physics::aabb::Aabb;
+use crate::physics::collision::{
+    closest_point_on_segment, support_map, transform_cylinder, transform_plane, transform_sphere,
+    Capsule, Obb,
+};
+use crate::physics::gjk::{closest_points, SupportMap, TransformedHull};
+use crate::physics::heightfield::HeightGrid;
+use crate::physics::phymgr::BodyHandle;
+use crate::physics::shape::{Cylinder, Plane, Shape, Sphere};
+use crate::physics::trimesh::{Triangle, TriangleSet};
+use cgmath::*;
+
+/// 射线检测或形状扫掠的命中结果
+#[derive(Debug, Clone, Copy)]
+pub struct RayHit {
+    pub body: BodyHandle,
+    pub point: Vector3<f32>, // 命中点（世界坐标），形状扫掠时为两形状的接触点
+    pub normal: Vector3<f32>, // 被命中物体在命中点的表面法线，背向射线
+    pub t: f32,              // 沿单位方向走过的距离
+}
+
+// 保守推进的距离容差与最大迭代次数
+const CAST_TOLERANCE: f32 = 1e-4;
+const CAST_MAX_ITERATIONS: usize = 64;
+
+/// 射线 origin + t · direction（direction 为单位向量，0 ≤ t ≤ max_t）与单个形状的精确求交
+///
+/// 返回 (t, 命中处法线)。起点已在形状内部时 t = 0，法线为 -direction。
+/// 三角网格与高度场按双面处理。
+pub fn raycast_shape(
+    shape: &Shape,
+    transform: &Transform,
+    origin: Vector3<f32>,
+    direction: Vector3<f32>,
+    max_t: f32,
+) -> Option<(f32, Vector3<f32>)> {
+    // 先用包围盒剔除，同时把射线截断到包围盒内
+    let (_, max_t) = shape
+        .aabb(transform)
+        .ray_interval(origin, direction, max_t)?;
+
+    match shape {
+        Shape::Sphere(sphere) => ray_sphere(
+            &transform_sphere(sphere, transform),
+            origin,
+            direction,
+            max_t,
+        ),
+        Shape::Plane(plane) => {
+            ray_plane(&transform_plane(plane, transform), origin, direction, max_t)
+        }
+        Shape::Cylinder(cylinder) => ray_cylinder(
+            &transform_cylinder(cylinder, transform),
+            origin,
+            direction,
+            max_t,
+        ),
+        Shape::Box { half_extents } => ray_obb(
+            &Obb::new(*half_extents, transform),
+            origin,
+            direction,
+            max_t,
+        ),
+        Shape::Capsule {
+            radius,
+            half_height,
+        } => ray_capsule(
+            &Capsule::new(*radius, *half_height, transform),
+            origin,
+            direction,
+            max_t,
+        ),
+        Shape::ConvexHull(hull) => {
+            let point = Sphere {
+                center: origin,
+                radius: 0.0,
+            };
+            cast_convex(
+                &point,
+                &TransformedHull { hull, transform },
+                direction,
+                max_t,
+            )
+            .map(|(t, normal, _)| (t, normal))
+        }
+        Shape::TriMesh(mesh) => {
+            ray_triangle_set(mesh.as_ref(), transform, origin, direction, max_t)
+        }
+        Shape::HeightField { .. } => ray_triangle_set(
+            &HeightGrid::from_shape(shape)?,
+            transform,
+            origin,
+            direction,
+            max_t,
+        ),
+    }
+}
+
+/// 凸形状沿 direction（单位向量）平移扫掠，求与目标形状首次接触时走过的距离
+///
+/// moving 为扫掠起点处的凸形状（如由 `collision::support_map` 得到的球、胶囊体），
+/// 返回 (t, 目标表面法线, 接触点)。起点已与目标相交时 t = 0，法线为 -direction。
+pub fn shape_cast_shape(
+    moving: &dyn SupportMap,
+    shape: &Shape,
+    transform: &Transform,
+    direction: Vector3<f32>,
+    max_t: f32,
+) -> Option<(f32, Vector3<f32>, Vector3<f32>)> {
+    // 目标包围盒按扫掠形状的尺寸外扩后，与扫掠形状中心的射线求交，快速剔除
+    let start = Aabb::from_support(moving);
+    let half = (start.max - start.min) / 2.0;
+    let target = shape.aabb(transform);
+    let (_, max_t) = Aabb::new(target.min - half, target.max + half).ray_interval(
+        start.center(),
+        direction,
+        max_t,
+    )?;
+
+    match shape {
+        Shape::Plane(plane) => {
+            let plane = transform_plane(plane, transform);
+            let deepest = moving.support(-plane.normal);
+            let distance = deepest.dot(plane.normal) - plane.distance;
+            if distance <= 0.0 {
+                return Some((0.0, -direction, deepest));
+            }
+            let approach = -direction.dot(plane.normal);
+            if approach <= 1e-6 {
+                return None;
+            }
+            let t = distance / approach;
+            (t <= max_t).then(|| (t, plane.normal, deepest + direction * t))
+        }
+        Shape::TriMesh(mesh) => {
+            cast_triangle_set(moving, mesh.as_ref(), transform, direction, max_t)
+        }
+        Shape::HeightField { .. } => cast_triangle_set(
+            moving,
+            &HeightGrid::from_shape(shape)?,
+            transform,
+            direction,
+            max_t,
+        ),
+        _ => cast_convex(
+            moving,
+            support_map(shape, transform)?.as_ref(),
+            direction,
+            max_t,
+        ),
+    }
+}
+
+fn ray_sphere(
+    sphere: &Sphere,
+    origin: Vector3<f32>,
+    direction: Vector3<f32>,
+    max_t: f32,
+) -> Option<(f32, Vector3<f32>)> {
+    let m = origin - sphere.center;
+    let c = m.magnitude2() - sphere.radius * sphere.radius;
+    if c <= 0.0 {
+        return Some((0.0, -direction));
+    }
+    let b = m.dot(direction);
+    let discriminant = b * b - c;
+    if b > 0.0 || discriminant < 0.0 {
+        return None;
+    }
+
+    let t = -b - discriminant.sqrt();
+    (t <= max_t).then(|| (t, (origin + direction * t - sphere.center).normalize()))
+}
+
+// 平面按半空间处理：法线背面为实体内部
+fn ray_plane(
+    plane: &Plane,
+    origin: Vector3<f32>,
+    direction: Vector3<f32>,
+    max_t: f32,
+) -> Option<(f32, Vector3<f32>)> {
+    let distance = origin.dot(plane.normal) - plane.distance;
+    if distance <= 0.0 {
+        return Some((0.0, -direction));
+    }
+    let approach = -direction.dot(plane.normal);
+    if approach <= 1e-6 {
+        return None;
+    }
+
+    let t = distance / approach;
+    (t <= max_t).then_some((t, plane.normal))
+}
+
+// 侧面与两个端面分别求交，取最近的
+fn ray_cylinder(
+    cylinder: &Cylinder,
+    origin: Vector3<f32>,
+    direction: Vector3<f32>,
+    max_t: f32,
+) -> Option<(f32, Vector3<f32>)> {
+    let axis = cylinder.axis;
+    let half_height = cylinder.height / 2.0;
+    let radius2 = cylinder.radius * cylinder.radius;
+    let radial = |p: Vector3<f32>| {
+        let offset = p - cylinder.center;
+        offset - axis * offset.dot(axis)
+    };
+    let along = |p: Vector3<f32>| (p - cylinder.center).dot(axis);
+
+    if along(origin).abs() <= half_height && radial(origin).magnitude2() <= radius2 {
+        return Some((0.0, -direction));
+    }
+
+    let mut best: Option<(f32, Vector3<f32>)> = None;
+    let mut consider = |t: f32, normal: Vector3<f32>| {
+        if (0.0..=max_t).contains(&t) && best.is_none_or(|(best_t, _)| t < best_t) {
+            best = Some((t, normal));
+        }
+    };
+
+    // 侧面：去掉轴向分量后是二维的射线与圆求交
+    let m = radial(origin);
+    let d = direction - axis * direction.dot(axis);
+    let a = d.magnitude2();
+    if a > 1e-12 {
+        let b = m.dot(d);
+        let c = m.magnitude2() - radius2;
+        let discriminant = b * b - a * c;
+        if discriminant >= 0.0 {
+            let t = (-b - discriminant.sqrt()) / a;
+            let point = origin + direction * t;
+            if along(point).abs() <= half_height {
+                consider(t, radial(point).normalize());
+            }
+        }
+    }
+
+    // 端面
+    for sign in [1.0f32, -1.0] {
+        let normal = axis * sign;
+        let approach = direction.dot(normal);
+        if approach >= 0.0 {
+            continue;
+        }
+        let cap_center = cylinder.center + normal * half_height;
+        let t = (cap_center - origin).dot(normal) / approach;
+        if radial(origin + direction * t).magnitude2() <= radius2 {
+            consider(t, normal);
+        }
+    }
+
+    best
+}
+
+// 在盒子的局部坐标中做 slab 测试，进入的面给出法线
+fn ray_obb(
+    obb: &Obb,
+    origin: Vector3<f32>,
+    direction: Vector3<f32>,
+    max_t: f32,
+) -> Option<(f32, Vector3<f32>)> {
+    let offset = origin - obb.center;
+    let mut t_min = 0.0f32;
+    let mut t_max = max_t;
+    let mut normal = -direction;
+
+    for i in 0..3 {
+        let axis = obb.axes[i];
+        let o = offset.dot(axis);
+        let d = direction.dot(axis);
+        let half = obb.half_extents[i];
+        if d.abs() < 1e-12 {
+            if o.abs() > half {
+                return None;
+            }
+            continue;
+        }
+
+        let t1 = (-half - o) / d;
+        let t2 = (half - o) / d;
+        let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+        if near > t_min {
+            t_min = near;
+            // 从负方向的面进入时 d > 0
+            normal = if d > 0.0 { -axis } else { axis };
+        }
+        t_max = t_max.min(far);
+        if t_min > t_max {
+            return None;
+        }
+    }
+
+    Some((t_min, normal))
+}
+
+// 胶囊体 = 两端的球 + 中段圆柱侧面
+fn ray_capsule(
+    capsule: &Capsule,
+    origin: Vector3<f32>,
+    direction: Vector3<f32>,
+    max_t: f32,
+) -> Option<(f32, Vector3<f32>)> {
+    let radius2 = capsule.radius * capsule.radius;
+    if (origin - closest_point_on_segment(origin, capsule.start, capsule.end)).magnitude2()
+        <= radius2
+    {
+        return Some((0.0, -direction));
+    }
+
+    let mut best = [capsule.start, capsule.end]
+        .iter()
+        .filter_map(|&center| {
+            let sphere = Sphere {
+                center,
+                radius: capsule.radius,
+            };
+            ray_sphere(&sphere, origin, direction, max_t)
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+
+    let axis = capsule.end - capsule.start;
+    let length2 = axis.magnitude2();
+    if length2 > 1e-12 {
+        let axis = axis / length2.sqrt();
+        let m = origin - capsule.start;
+        let m = m - axis * m.dot(axis);
+        let d = direction - axis * direction.dot(axis);
+        let a = d.magnitude2();
+        if a > 1e-12 {
+            let b = m.dot(d);
+            let c = m.magnitude2() - radius2;
+            let discriminant = b * b - a * c;
+            if discriminant >= 0.0 {
+                let t = (-b - discriminant.sqrt()) / a;
+                let point = origin + direction * t;
+                let along = (point - capsule.start).dot(axis);
+                if (0.0..=max_t).contains(&t)
+                    && (0.0..=length2.sqrt()).contains(&along)
+                    && best.is_none_or(|(best_t, _)| t < best_t)
+                {
+                    let on_axis = capsule.start + axis * along;
+                    best = Some((t, (point - on_axis).normalize()));
+                }
+            }
+        }
+    }
+
+    best
+}
+
+// 射线变换到网格局部坐标后逐个三角形求交；仿射变换下参数 t 不变
+fn ray_triangle_set(
+    set: &dyn TriangleSet,
+    transform: &Transform,
+    origin: Vector3<f32>,
+    direction: Vector3<f32>,
+    max_t: f32,
+) -> Option<(f32, Vector3<f32>)> {
+    let local_origin = transform.inverse_transform_point(origin);
+    let local_direction = transform.inverse_transform_point(origin + direction) - local_origin;
+
+    let mut best: Option<(f32, usize)> = None;
+    set.query_ray(local_origin, local_direction, max_t, &mut |index| {
+        let limit = best.map_or(max_t, |(t, _)| t);
+        if let Some(t) = set
+            .triangle(index)
+            .ray_intersection(local_origin, local_direction, limit)
+        {
+            if best.is_none_or(|(best_t, _)| t < best_t) {
+                best = Some((t, index));
+            }
+        }
+    });
+
+    let (t, index) = best?;
+    // 法线的变换需除以缩放（逆转置）
+    let n = set.triangle(index).normal();
+    let scale = transform.scale;
+    let mut normal = (transform.rotation
+        * Vector3::new(n.x / scale.x, n.y / scale.y, n.z / scale.z))
+    .normalize();
+    if normal.dot(direction) > 0.0 {
+        normal = -normal;
+    }
+    Some((t, normal))
+}
+
+// 平移后的凸形状
+struct Translated<'a> {
+    shape: &'a dyn SupportMap,
+    offset: Vector3<f32>,
+}
+
+impl SupportMap for Translated<'_> {
+    fn support(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        self.shape.support(direction) + self.offset
+    }
+}
+
+/// 保守推进：每次沿方向前进“当前距离 / 接近速度”，这一步内两凸体一定不会穿透，
+/// 距离小于容差时视为接触
+fn cast_convex(
+    moving: &dyn SupportMap,
+    target: &dyn SupportMap,
+    direction: Vector3<f32>,
+    max_t: f32,
+) -> Option<(f32, Vector3<f32>, Vector3<f32>)> {
+    let mut t = 0.0;
+    // 上一步的分离法线与目标上的最近点
+    let mut last: Option<(Vector3<f32>, Vector3<f32>)> = None;
+    for _ in 0..CAST_MAX_ITERATIONS {
+        let swept = Translated {
+            shape: moving,
+            offset: direction * t,
+        };
+        let Some(closest) = closest_points(&swept, target) else {
+            // 恰好推进到接触时 GJK 可能判为相交，沿用上一步的法线与接触点
+            return Some(match last {
+                Some((normal, point)) => (t, normal, point),
+                None => (0.0, -direction, swept.support(direction)),
+            });
+        };
+
+        let normal = (closest.point_a - closest.point_b) / closest.distance.max(1e-12);
+        if closest.distance < CAST_TOLERANCE {
+            return Some((t, normal, closest.point_b));
+        }
+        let approach = -direction.dot(normal);
+        if approach <= 1e-6 {
+            return None;
+        }
+        t += closest.distance / approach;
+        if t > max_t {
+            return None;
+        }
+        last = Some((normal, closest.point_b));
+    }
+    None
+}
+
+// 只检测扫掠区域覆盖到的三角形，三角形变换到世界坐标后逐个做保守推进
+fn cast_triangle_set(
+    moving: &dyn SupportMap,
+    set: &dyn TriangleSet,
+    transform: &Transform,
+    direction: Vector3<f32>,
+    max_t: f32,
+) -> Option<(f32, Vector3<f32>, Vector3<f32>)> {
+    let start = Aabb::from_support(moving);
+    let end = Aabb::new(start.min + direction * max_t, start.max + direction * max_t);
+    let region = start.union(&end).inverse_transformed(transform);
+
+    let mut best: Option<(f32, Vector3<f32>, Vector3<f32>)> = None;
+    set.query(&region, &mut |index| {
+        let local = set.triangle(index);
+        let triangle = Triangle {
+            a: transform.transform_point(local.a),
+            b: transform.transform_point(local.b),
+            c: transform.transform_point(local.c),
+        };
+        let limit = best.map_or(max_t, |(t, _, _)| t);
+        if let Some(hit) = cast_convex(moving, &triangle, direction, limit) {
+            if best.is_none_or(|(t, _, _)| hit.0 < t) {
+                best = Some(hit);
+            }
+        }
+    });
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::shape::ConvexHull;
+
+    fn assert_hit(hit: Option<(f32, Vector3<f32>)>, t: f32, normal: Vector3<f32>) {
+        let (hit_t, hit_normal) = hit.expect("expected a hit");
+        assert!((hit_t - t).abs() < 1e-3, "t = {}, expected {}", hit_t, t);
+        assert!(
+            (hit_normal - normal).magnitude() < 1e-3,
+            "normal = {:?}, expected {:?}",
+            hit_normal,
+            normal
+        );
+    }
+
+    #[test]
+    fn test_raycast_every_shape() {
+        // 每种形状都放在原点上方，射线从 y = 10 竖直向下
+        let origin = Vector3::new(0.0, 10.0, 0.0);
+        let down = -Vector3::unit_y();
+        let up = Vector3::unit_y();
+        let at = |y: f32| Transform::translation(0.0, y, 0.0);
+
+        let sphere = Shape::Sphere(Sphere {
+            center: Vector3::zero(),
+            radius: 1.0,
+        });
+        assert_hit(
+            raycast_shape(&sphere, &at(2.0), origin, down, 100.0),
+            7.0,
+            up,
+        );
+        assert!(raycast_shape(&sphere, &at(2.0), origin, down, 5.0).is_none());
+
+        let plane = Shape::Plane(Plane {
+            normal: up,
+            distance: 0.0,
+        });
+        assert_hit(
+            raycast_shape(&plane, &at(1.0), origin, down, 100.0),
+            9.0,
+            up,
+        );
+        assert!(raycast_shape(&plane, &at(1.0), origin, up, 100.0).is_none());
+
+        let cylinder = Shape::Cylinder(Cylinder {
+            center: Vector3::zero(),
+            axis: up,
+            radius: 0.5,
+            height: 2.0,
+        });
+        assert_hit(
+            raycast_shape(&cylinder, &at(0.0), origin, down, 100.0),
+            9.0,
+            up,
+        );
+        // 从侧面水平射入
+        let side = raycast_shape(
+            &cylinder,
+            &at(0.0),
+            Vector3::new(-5.0, 0.5, 0.0),
+            Vector3::unit_x(),
+            100.0,
+        );
+        assert_hit(side, 4.5, -Vector3::unit_x());
+
+        let cube = Shape::Box {
+            half_extents: Vector3::new(1.0, 0.5, 1.0),
+        };
+        assert_hit(raycast_shape(&cube, &at(0.0), origin, down, 100.0), 9.5, up);
+
+        let capsule = Shape::Capsule {
+            radius: 0.5,
+            half_height: 1.0,
+        };
+        assert_hit(
+            raycast_shape(&capsule, &at(0.0), origin, down, 100.0),
+            8.5,
+            up,
+        );
+        let side = raycast_shape(
+            &capsule,
+            &at(0.0),
+            Vector3::new(-5.0, 0.5, 0.0),
+            Vector3::unit_x(),
+            100.0,
+        );
+        assert_hit(side, 4.5, -Vector3::unit_x());
+
+        let hull = Shape::ConvexHull(ConvexHull::new([
+            Vector3::new(-1.0, 0.0, -1.0),
+            Vector3::new(1.0, 0.0, -1.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(0.0, 2.0, 0.0),
+        ]));
+        assert_hit(
+            raycast_shape(&hull, &at(0.0), origin, down, 100.0),
+            8.0,
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+
+        let heights = vec![0.0; 9];
+        let terrain = Shape::HeightField {
+            rows: 3,
+            cols: 3,
+            heights: heights.into(),
+            scale: Vector3::new(1.0, 1.0, 1.0),
+        };
+        assert_hit(
+            raycast_shape(&terrain, &at(1.0), origin, down, 100.0),
+            9.0,
+            up,
+        );
+        assert!(raycast_shape(
+            &terrain,
+            &at(1.0),
+            Vector3::new(5.0, 10.0, 0.0),
+            down,
+            100.0
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_shape_cast_sphere_and_capsule() {
+        let ground = Shape::Plane(Plane {
+            normal: Vector3::unit_y(),
+            distance: 0.0,
+        });
+        let cube = Shape::Box {
+            half_extents: Vector3::new(1.0, 1.0, 1.0),
+        };
+
+        // 半径 0.5 的球从 y = 5 向下，落到地面时球心在 y = 0.5
+        let sphere = Sphere {
+            center: Vector3::new(0.0, 5.0, 0.0),
+            radius: 0.5,
+        };
+        let (t, normal, point) = shape_cast_shape(
+            &sphere,
+            &ground,
+            &Transform::identity(),
+            -Vector3::unit_y(),
+            100.0,
+        )
+        .unwrap();
+        assert!((t - 4.5).abs() < 1e-3);
+        assert!((normal - Vector3::unit_y()).magnitude() < 1e-3);
+        assert!(point.y.abs() < 1e-3);
+
+        // 平躺的胶囊体水平扫向盒子，端点球先碰到盒子的侧面
+        let capsule = Capsule {
+            start: Vector3::new(-6.0, 0.0, 0.0),
+            end: Vector3::new(-4.0, 0.0, 0.0),
+            radius: 0.5,
+        };
+        let (t, normal, _) = shape_cast_shape(
+            &capsule,
+            &cube,
+            &Transform::identity(),
+            Vector3::unit_x(),
+            100.0,
+        )
+        .unwrap();
+        assert!((t - 2.5).abs() < 1e-3, "t = {}", t);
+        assert!((normal + Vector3::unit_x()).magnitude() < 1e-2);
+
+        // 扫掠方向背离盒子时没有命中
+        assert!(shape_cast_shape(
+            &capsule,
+            &cube,
+            &Transform::identity(),
+            -Vector3::unit_x(),
+            100.0
+        )
+        .is_none());
+    }
+}
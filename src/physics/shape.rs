@@ -1,5 +1,14 @@
 use crate::common::Transform; // 明确导入Transform以避免歧义
+use crate::physics::aabb::Aabb;
+use crate::physics::collision::{support_map, transform_plane};
+use crate::physics::filter::CollisionGroups;
+use crate::physics::heightfield::HeightGrid;
+use crate::physics::material::MaterialId;
+use crate::physics::phymgr::BodyHandle;
+use crate::physics::trimesh::TriMesh;
 use cgmath::*;
+use std::collections::HashSet;
+use std::sync::Arc;
 
 #[derive(Clone, Copy)]
 pub enum PhysicsState {
@@ -11,26 +20,282 @@ pub enum PhysicsState {
 // 基础形状定义
 #[derive(Clone, Copy)]
 pub struct Cylinder {
-    pub center: Vector3<f32>, // 底面中心点
+    pub center: Vector3<f32>, // 中心点
     pub axis: Vector3<f32>,   // 高度方向（单位向量）
     pub radius: f32,
     pub height: f32,
 }
 
+#[derive(Clone, Copy)]
+pub struct Sphere {
+    pub center: Vector3<f32>, // 球心
+    pub radius: f32,
+}
+
 #[derive(Clone, Copy)]
 pub struct Plane {
     pub normal: Vector3<f32>, // 单位法向量
     pub distance: f32,        // 到原点的距离（沿法线方向）
 }
 
+/// 凸包：由点云定义，形状为这些点的凸包（局部坐标，原点为质心）
+///
+/// 碰撞检测只依赖支撑映射，因此不需要显式求出凸包的面，内部点也不影响结果。
+#[derive(Clone)]
+pub struct ConvexHull {
+    pub points: Vec<Vector3<f32>>,
+}
+
+impl ConvexHull {
+    /// 由点云构造，去除重复的点（OBJ 顶点常因法线、纹理坐标不同而重复）
+    pub fn new(points: impl IntoIterator<Item = Vector3<f32>>) -> Self {
+        let mut unique: Vec<Vector3<f32>> = Vec::new();
+        for point in points {
+            if !unique.iter().any(|p| (p - point).magnitude2() < 1e-10) {
+                unique.push(point);
+            }
+        }
+        assert!(!unique.is_empty(), "convex hull needs at least one point");
+
+        Self { points: unique }
+    }
+
+    /// 局部空间中沿给定方向最远的点
+    pub fn support_local(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        *self
+            .points
+            .iter()
+            .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+            .unwrap()
+    }
+
+    /// 凸包表面的三角形，顶点逆时针排列（法线朝外）
+    ///
+    /// 枚举三点确定的平面，所有点都在其一侧的即为凸包的面；共面的点按角度排序后扇形三角化。
+    /// 复杂度为点数的四次方，只在构造物理体时调用一次，适用于几十个点以内的凸包。
+    pub fn faces(&self) -> Vec<[Vector3<f32>; 3]> {
+        let points = &self.points;
+        let scale = points.iter().map(|p| p.magnitude()).fold(0.0, f32::max);
+        let epsilon = 1e-4 * scale.max(1e-6);
+
+        let mut planes: Vec<(Vector3<f32>, f32)> = Vec::new();
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                for k in j + 1..points.len() {
+                    let normal = (points[j] - points[i]).cross(points[k] - points[i]);
+                    if normal.magnitude2() < epsilon * epsilon * epsilon * epsilon {
+                        continue;
+                    }
+                    let mut normal = normal.normalize();
+                    let mut offset = normal.dot(points[i]);
+                    if points.iter().all(|p| normal.dot(*p) - offset >= -epsilon) {
+                        normal = -normal;
+                        offset = -offset;
+                    } else if !points.iter().all(|p| normal.dot(*p) - offset <= epsilon) {
+                        continue;
+                    }
+                    let duplicate = planes.iter().any(|(n, d)| {
+                        (n - normal).magnitude2() < 1e-6 && (d - offset).abs() < epsilon
+                    });
+                    if !duplicate {
+                        planes.push((normal, offset));
+                    }
+                }
+            }
+        }
+
+        let mut faces = Vec::new();
+        for (normal, offset) in planes {
+            let polygon = points
+                .iter()
+                .copied()
+                .filter(|p| (normal.dot(*p) - offset).abs() < epsilon)
+                .collect::<Vec<_>>();
+            let center = polygon.iter().sum::<Vector3<f32>>() / polygon.len() as f32;
+            let u = (polygon[0] - center).normalize();
+            let v = normal.cross(u);
+            let mut angles = polygon
+                .iter()
+                .map(|p| {
+                    let d = p - center;
+                    (d.dot(v).atan2(d.dot(u)), *p)
+                })
+                .collect::<Vec<_>>();
+            angles.sort_by(|a, b| a.0.total_cmp(&b.0));
+            for i in 0..angles.len() {
+                faces.push([center, angles[i].1, angles[(i + 1) % angles.len()].1]);
+            }
+        }
+        faces
+    }
+}
+
 // 形状枚举
 #[derive(Clone)]
 pub enum Shape {
     Cylinder(Cylinder),
     Plane(Plane),
+    Sphere(Sphere),
+    Box {
+        half_extents: Vector3<f32>,
+    }, // 以物体原点为中心的长方体，半边长
+    Capsule {
+        radius: f32,
+        half_height: f32,
+    }, // 沿局部 Y 轴的胶囊体，half_height 为中段线段的半长
+    ConvexHull(ConvexHull),
+    TriMesh(Arc<TriMesh>), // 三角网格，只能用于静态物体；多个物体可共享同一网格
+    // 高度场地形，只能用于静态物体；heights 按行优先存放 rows × cols 个采样点，
+    // scale 的 x、z 为格子边长，y 为高度缩放，网格布局见 heightfield::HeightGrid
+    HeightField {
+        rows: usize,
+        cols: usize,
+        heights: Arc<[f32]>,
+        scale: Vector3<f32>,
+    },
     // 后续可添加其他形状
 }
 
+impl Shape {
+    /// 构造高度场形状，参数不合法（见 `HeightGrid::is_valid`）时返回 None
+    pub fn height_field(
+        rows: usize,
+        cols: usize,
+        heights: impl Into<Arc<[f32]>>,
+        scale: Vector3<f32>,
+    ) -> Option<Self> {
+        let heights = heights.into();
+        HeightGrid::is_valid(rows, cols, &heights, scale).then_some(Shape::HeightField {
+            rows,
+            cols,
+            heights,
+            scale,
+        })
+    }
+
+    /// 形状在给定变换下的世界空间包围盒
+    ///
+    /// 平面按半空间处理：法线与坐标轴对齐时只在该轴的一侧有界，否则包围盒无界。
+    pub fn aabb(&self, transform: &Transform) -> Aabb {
+        match self {
+            Shape::Plane(plane) => {
+                let plane = transform_plane(plane, transform);
+                let mut aabb = Aabb::new(
+                    Vector3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+                    Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+                );
+                for axis in 0..3 {
+                    if plane.normal[axis] > 0.9999 {
+                        aabb.max[axis] = plane.distance;
+                    } else if plane.normal[axis] < -0.9999 {
+                        aabb.min[axis] = -plane.distance;
+                    }
+                }
+                aabb
+            }
+            Shape::TriMesh(mesh) => mesh.aabb().transformed(transform),
+            Shape::HeightField {
+                rows,
+                cols,
+                heights,
+                scale,
+            } => HeightGrid {
+                rows: *rows,
+                cols: *cols,
+                heights,
+                scale: *scale,
+            }
+            .aabb()
+            .transformed(transform),
+            _ => Aabb::from_support(support_map(self, transform).unwrap().as_ref()),
+        }
+    }
+}
+
+// 各形状质量分布关于原点的二阶矩 ∫ x xᵀ dm（局部坐标、均匀密度），由它可得惯性张量
+fn second_moment(shape: &Shape, mass: f32) -> Matrix3<f32> {
+    let offset = |center: Vector3<f32>| mass * outer(center, center);
+    match shape {
+        Shape::Sphere(sphere) => {
+            Matrix3::identity() * (mass * sphere.radius * sphere.radius / 5.0)
+                + offset(sphere.center)
+        }
+        Shape::Box { half_extents } => {
+            let h = half_extents;
+            Matrix3::from_diagonal(Vector3::new(h.x * h.x, h.y * h.y, h.z * h.z)) * (mass / 3.0)
+        }
+        Shape::Cylinder(cylinder) => {
+            let axis = cylinder.axis.normalize();
+            let along = outer(axis, axis);
+            (Matrix3::identity() - along) * (mass * cylinder.radius * cylinder.radius / 4.0)
+                + along * (mass * cylinder.height * cylinder.height / 12.0)
+                + offset(cylinder.center)
+        }
+        Shape::Capsule {
+            radius,
+            half_height,
+        } => {
+            // 按体积分配中段圆柱与两端半球的质量
+            let (r, h) = (*radius, *half_height);
+            let cylinder_volume = 2.0 * h * r * r;
+            let sphere_volume = 4.0 / 3.0 * r * r * r;
+            let total = cylinder_volume + sphere_volume;
+            let cylinder_mass = mass * cylinder_volume / total;
+            let sphere_mass = mass * sphere_volume / total;
+
+            let radial = cylinder_mass * r * r / 4.0 + sphere_mass * r * r / 5.0;
+            // 半球关于自身底面中心 ∫y² = m r²/5、∫y = 3mr/8，平移到胶囊体中心
+            let axial = cylinder_mass * (2.0 * h) * (2.0 * h) / 12.0
+                + sphere_mass * (r * r / 5.0 + 3.0 * r * h / 4.0 + h * h);
+            Matrix3::from_diagonal(Vector3::new(radial, axial, radial))
+        }
+        Shape::ConvexHull(hull) => {
+            // 以原点为公共顶点把凸包分成四面体，四面体 (0, a, b, c) 的二阶矩为
+            // V / 20 · (Σ vᵢvᵢᵀ + s sᵀ)，s = a + b + c
+            let mut volume = 0.0;
+            let mut moment = Matrix3::zero();
+            for [a, b, c] in hull.faces() {
+                let tetra_volume = a.dot(b.cross(c)) / 6.0;
+                let sum = a + b + c;
+                moment += (outer(a, a) + outer(b, b) + outer(c, c) + outer(sum, sum))
+                    * (tetra_volume / 20.0);
+                volume += tetra_volume;
+            }
+            if volume > 1e-9 {
+                moment * (mass / volume)
+            } else {
+                // 退化为平面或线段的点集，按包围盒近似
+                let aabb = Aabb::from_points(hull.points.iter().copied());
+                let h = (aabb.max - aabb.min) / 2.0;
+                Matrix3::from_diagonal(Vector3::new(h.x * h.x, h.y * h.y, h.z * h.z)) * (mass / 3.0)
+                    + offset(aabb.center())
+            }
+        }
+        Shape::Plane(_) | Shape::TriMesh(_) | Shape::HeightField { .. } => Matrix3::zero(),
+    }
+}
+
+fn outer(a: Vector3<f32>, b: Vector3<f32>) -> Matrix3<f32> {
+    Matrix3::from_cols(a * b.x, a * b.y, a * b.z)
+}
+
+/// 叉乘矩阵 [v]，满足 [v] x = v × x
+pub fn skew(v: Vector3<f32>) -> Matrix3<f32> {
+    Matrix3::new(0.0, v.z, -v.y, -v.z, 0.0, v.x, v.y, -v.x, 0.0)
+}
+
+impl Shape {
+    /// 质量为 mass 的均匀物体关于局部原点的惯性张量
+    ///
+    /// scale 为物体变换的缩放，按缩放后的质量分布计算；平面、三角网格、高度场只用于静态物体，返回零矩阵。
+    pub fn inertia_tensor(&self, mass: f32, scale: Vector3<f32>) -> Matrix3<f32> {
+        let scale = Matrix3::from_diagonal(scale);
+        let moment = scale * second_moment(self, mass) * scale;
+        // I = tr(C) E - C
+        Matrix3::identity() * (moment.x.x + moment.y.y + moment.z.z) - moment
+    }
+}
+
 // 物理体
 #[derive(Clone)]
 pub struct PhysicsBody {
@@ -38,35 +303,70 @@ pub struct PhysicsBody {
     pub transform: Transform,
     pub velocity: Vector3<f32>,
     pub angular_velocity: Vector3<f32>,
-    pub mass: f32,     // dynmaic 质量必须大于0，static质量为无限大
-    pub inv_mass: f32, // 质量倒数（0 表示无穷大，即静态）
-    pub inertia_tensor: Matrix3<f32>,
+    pub mass: f32,                    // dynmaic 质量必须大于0，static质量为无限大
+    pub inv_mass: f32,                // 质量倒数（0 表示无穷大，即静态）
+    pub inertia_tensor: Matrix3<f32>, // 局部坐标下的惯性张量，修改后需调用 update_inertia_world
+    inv_inertia_world: Matrix3<f32>,  // 世界坐标下惯性张量的逆，随姿态每步更新
     pub state: PhysicsState,
-    pub friction: f32,
-    pub restitution: f32, // 弹性系数
+    pub material: MaterialId, // 材质库中的表面材质，决定摩擦与弹性
+    pub ccd_enabled: bool,    // 是否做连续碰撞检测，用于快速运动的小物体
+    pub is_sensor: bool,      // 传感器只报告重叠事件，不产生接触响应
+    pub collision_groups: CollisionGroups,
+    pub ignored_bodies: HashSet<BodyHandle>, // 不与之碰撞的物体，任一方记录即可
+    kinematic_target: Option<Transform>,     // 运动学物体下一步要到达的位姿
+    sleeping: bool,                          // 休眠的物体不参与积分与求解
+    sleep_time: f32,                         // 速度持续低于休眠阈值的时间
 }
 
 impl PhysicsBody {
     pub fn new_dynamic(shape: Shape, transform: Transform, mass: f32) -> Self {
         assert!(mass > 0.0);
+        assert!(
+            !matches!(shape, Shape::TriMesh(_) | Shape::HeightField { .. }),
+            "triangle meshes and height fields can only be used by static bodies"
+        );
 
         let inv_mass = if mass > 0.0 { 1.0 / mass } else { 0.0 };
+        let inertia_tensor = shape.inertia_tensor(mass, transform.scale);
 
-        Self {
+        let mut body = Self {
             shape,
             transform,
             velocity: Vector3::new(0.0, 0.0, 0.0),
             angular_velocity: Vector3::new(0.0, 0.0, 0.0),
             mass,
             inv_mass,
-            inertia_tensor: Matrix3::from_diagonal(Vector3::new(1.0, 1.0, 1.0)), // 单位矩阵
+            inertia_tensor,
+            inv_inertia_world: Matrix3::zero(),
             state: PhysicsState::Dynamic,
-            friction: 0.5,
-            restitution: 0.2,
-        }
+            material: MaterialId::DEFAULT,
+            ccd_enabled: false,
+            is_sensor: false,
+            collision_groups: CollisionGroups::default(),
+            ignored_bodies: HashSet::new(),
+            kinematic_target: None,
+            sleeping: false,
+            sleep_time: 0.0,
+        };
+        body.update_inertia_world();
+        body
     }
 
     pub fn new_static(shape: Shape, transform: Transform) -> Self {
+        // 直接写出的高度场变体没有经过 Shape::height_field 检查，在这里拦下，免得在模拟中途越界
+        if let Shape::HeightField {
+            rows,
+            cols,
+            heights,
+            scale,
+        } = &shape
+        {
+            assert!(
+                HeightGrid::is_valid(*rows, *cols, heights, *scale),
+                "invalid height field: needs at least 2x2 samples, rows * cols heights and a positive cell size"
+            );
+        }
+
         Self {
             shape,
             transform,
@@ -75,10 +375,59 @@ impl PhysicsBody {
             mass: f32::INFINITY,
             inv_mass: 0.0,
             inertia_tensor: Matrix3::zero(), // 零矩阵表示无限大惯性
+            inv_inertia_world: Matrix3::zero(),
             state: PhysicsState::Static,
-            friction: 0.5,
-            restitution: 0.2,
+            material: MaterialId::DEFAULT,
+            ccd_enabled: false,
+            is_sensor: false,
+            collision_groups: CollisionGroups::default(),
+            ignored_bodies: HashSet::new(),
+            kinematic_target: None,
+            sleeping: false,
+            sleep_time: 0.0,
+        }
+    }
+
+    /// 运动学物体：质量无限大、不受重力，位置由用户代码或关键帧路径驱动
+    pub fn new_kinematic(shape: Shape, transform: Transform) -> Self {
+        Self {
+            state: PhysicsState::Kinematic,
+            ..Self::new_static(shape, transform)
+        }
+    }
+
+    /// 设置运动学物体下一步模拟结束时的位姿，只使用其中的平移与旋转
+    ///
+    /// 模拟时由位移反推线速度与角速度，使物体能推动接触的动态物体；
+    /// 没有设置目标时运动学物体按当前速度匀速运动。
+    pub fn set_kinematic_target(&mut self, target: Transform) {
+        if let PhysicsState::Kinematic = self.state {
+            self.kinematic_target = Some(target);
+        }
+    }
+
+    // 按目标位姿求出本步的速度，返回目标并清除
+    pub(crate) fn take_kinematic_target(&mut self, delta_time: f32) -> Option<Transform> {
+        let target = self.kinematic_target.take()?;
+        self.velocity = (target.translation - self.transform.translation) / delta_time;
+
+        // 姿态差 Δq = q目标 · q⁻¹，取短路径后换算为轴角
+        let mut delta = target.rotation * self.transform.rotation.conjugate();
+        if delta.s < 0.0 {
+            delta = -delta;
         }
+        let sin_half = delta.v.magnitude();
+        self.angular_velocity = if sin_half > 1e-6 {
+            delta.v / sin_half * (2.0 * sin_half.atan2(delta.s) / delta_time)
+        } else {
+            delta.v * (2.0 / delta_time)
+        };
+        Some(target)
+    }
+
+    /// 世界空间包围盒
+    pub fn aabb(&self) -> Aabb {
+        self.shape.aabb(&self.transform)
     }
 
     // 获取物体的位置（从变换中获取）
@@ -90,4 +439,213 @@ impl PhysicsBody {
     pub fn set_position(&mut self, pos: Vector3<f32>) {
         self.transform.translation = pos;
     }
+
+    // 世界空间的惯性张量逆矩阵 R * I^-1 * R^T，静态物体为零矩阵
+    pub fn inv_inertia_world(&self) -> Matrix3<f32> {
+        self.inv_inertia_world
+    }
+
+    // 按当前姿态重新计算世界空间的惯性张量逆矩阵，物理世界每步积分前后都会调用
+    pub fn update_inertia_world(&mut self) {
+        if self.inv_mass == 0.0 {
+            self.inv_inertia_world = Matrix3::zero();
+            return;
+        }
+
+        let inv_inertia = self.inertia_tensor.invert().unwrap_or(Matrix3::zero());
+        let rotation = Matrix3::from(self.transform.rotation);
+        self.inv_inertia_world = rotation * inv_inertia * rotation.transpose();
+    }
+
+    /// 陀螺效应：无外力矩时角动量 L = Iω 守恒，而 ω 会随惯性张量的转动而改变
+    ///
+    /// 在局部坐标中对 I dω/dt + ω × Iω = 0 做一步隐式欧拉（一次牛顿迭代），
+    /// 显式积分在细长物体高速旋转时会使能量发散。
+    pub fn apply_gyroscopic(&mut self, delta_time: f32) {
+        if self.inv_mass == 0.0 {
+            return;
+        }
+
+        let rotation = self.transform.rotation;
+        let omega = rotation.invert() * self.angular_velocity;
+        let inertia = self.inertia_tensor;
+        let momentum = inertia * omega;
+        let residual = omega.cross(momentum) * delta_time;
+        let jacobian = inertia + (skew(omega) * inertia - skew(momentum)) * delta_time;
+        if let Some(inverse) = jacobian.invert() {
+            self.angular_velocity = rotation * (omega - inverse * residual);
+        }
+    }
+
+    // 物体上某一世界坐标点的速度 v + ω × r
+    pub fn velocity_at_point(&self, point: Vector3<f32>) -> Vector3<f32> {
+        self.velocity + self.angular_velocity.cross(point - self.position())
+    }
+
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    // 速度持续低于休眠阈值的时间（秒）
+    pub fn sleep_time(&self) -> f32 {
+        self.sleep_time
+    }
+
+    // 唤醒物体并重新开始计时；在两次模拟之间直接修改休眠物体的速度或位置后需要调用
+    pub fn wake_up(&mut self) {
+        self.sleeping = false;
+        self.sleep_time = 0.0;
+    }
+
+    // 让动态物体休眠，速度清零
+    pub fn sleep(&mut self) {
+        if let PhysicsState::Dynamic = self.state {
+            self.sleeping = true;
+            self.velocity = Vector3::zero();
+            self.angular_velocity = Vector3::zero();
+        }
+    }
+
+    // 按本步结束时的速度更新休眠计时，速度超过阈值时清零
+    pub fn update_sleep_time(
+        &mut self,
+        linear_threshold: f32,
+        angular_threshold: f32,
+        delta_time: f32,
+    ) {
+        if self.velocity.magnitude2() > linear_threshold * linear_threshold
+            || self.angular_velocity.magnitude2() > angular_threshold * angular_threshold
+        {
+            self.sleep_time = 0.0;
+        } else {
+            self.sleep_time += delta_time;
+        }
+    }
+
+    // 在世界坐标点上施加冲量，同时改变线速度与角速度；休眠的物体会被唤醒
+    pub fn apply_impulse(&mut self, impulse: Vector3<f32>, point: Vector3<f32>) {
+        if self.inv_mass == 0.0 {
+            return;
+        }
+
+        if self.sleeping {
+            self.wake_up();
+        }
+        let r = point - self.position();
+        self.velocity += impulse * self.inv_mass;
+        self.angular_velocity += self.inv_inertia_world() * r.cross(impulse);
+    }
+
+    // 施加角冲量，只改变角速度；休眠的物体会被唤醒
+    pub fn apply_angular_impulse(&mut self, impulse: Vector3<f32>) {
+        if self.inv_mass == 0.0 {
+            return;
+        }
+
+        if self.sleeping {
+            self.wake_up();
+        }
+        self.angular_velocity += self.inv_inertia_world() * impulse;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_matrix_eq(a: Matrix3<f32>, b: Matrix3<f32>) {
+        for col in 0..3 {
+            for row in 0..3 {
+                assert!(
+                    (a[col][row] - b[col][row]).abs() < 1e-4,
+                    "{:?} != {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_inertia_tensors() {
+        let mass = 2.0;
+        let unit = Vector3::new(1.0, 1.0, 1.0);
+
+        let sphere = Shape::Sphere(Sphere {
+            center: Vector3::zero(),
+            radius: 0.5,
+        });
+        assert_matrix_eq(
+            sphere.inertia_tensor(mass, unit),
+            Matrix3::identity() * (0.4 * mass * 0.25),
+        );
+
+        // 胶囊体中段长度为零时就是球
+        let capsule = Shape::Capsule {
+            radius: 0.5,
+            half_height: 0.0,
+        };
+        assert_matrix_eq(
+            capsule.inertia_tensor(mass, unit),
+            sphere.inertia_tensor(mass, unit),
+        );
+
+        let cylinder = Shape::Cylinder(Cylinder {
+            center: Vector3::zero(),
+            axis: Vector3::unit_y(),
+            radius: 0.5,
+            height: 2.0,
+        });
+        let side = mass * (3.0 * 0.25 + 4.0) / 12.0;
+        assert_matrix_eq(
+            cylinder.inertia_tensor(mass, unit),
+            Matrix3::from_diagonal(Vector3::new(side, mass * 0.25 / 2.0, side)),
+        );
+
+        // 长方体与同样顶点构成的凸包惯性张量相同
+        let half_extents = Vector3::new(1.0, 0.5, 0.25);
+        let cuboid = Shape::Box { half_extents };
+        let expected = Matrix3::from_diagonal(Vector3::new(
+            mass * (0.25 + 0.0625) / 3.0,
+            mass * (1.0 + 0.0625) / 3.0,
+            mass * (1.0 + 0.25) / 3.0,
+        ));
+        assert_matrix_eq(cuboid.inertia_tensor(mass, unit), expected);
+        let corners = (0..8).map(|i| {
+            Vector3::new(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -0.5 } else { 0.5 },
+                if i & 4 == 0 { -0.25 } else { 0.25 },
+            )
+        });
+        let hull = ConvexHull::new(corners);
+        assert_eq!(hull.faces().len(), 6 * 4);
+        assert_matrix_eq(Shape::ConvexHull(hull).inertia_tensor(mass, unit), expected);
+
+        // 缩放等价于改变形状尺寸
+        let scaled = Shape::Box {
+            half_extents: Vector3::new(0.5, 0.5, 0.25),
+        };
+        assert_matrix_eq(
+            scaled.inertia_tensor(mass, Vector3::new(2.0, 1.0, 1.0)),
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_support_with_nan_direction() {
+        // 方向含 NaN 时不应 panic，仍返回凸包上的某个顶点
+        let points: Vec<_> = (0..8)
+            .map(|i| {
+                Vector3::new(
+                    if i & 1 == 0 { -1.0 } else { 1.0 },
+                    if i & 2 == 0 { -1.0 } else { 1.0 },
+                    if i & 4 == 0 { -1.0 } else { 1.0 },
+                )
+            })
+            .collect();
+        let hull = ConvexHull::new(points.clone());
+        let support = hull.support_local(Vector3::new(f32::NAN, 1.0, 0.0));
+        assert!(points.contains(&support));
+    }
 }
@@ -0,0 +1,477 @@
+use std::collections::HashMap;
+
+use crate::physics::collision::{any_perpendicular, CollisionInfo};
+use crate::physics::material::MaterialLibrary;
+use crate::physics::phymgr::{BodyHandle, BodySet, WorldSettings};
+use crate::physics::shape::PhysicsBody;
+use cgmath::*;
+
+/// 位置修正系数（Baumgarte 系数），每次位置迭代消除该比例的穿透
+pub const BAUMGARTE: f32 = 0.2;
+/// 允许的穿透深度，低于该值不做位置修正，避免接触抖动
+pub const PENETRATION_SLOP: f32 = 0.005;
+/// 单次位置迭代的最大修正量，防止深穿透时物体被弹飞
+pub const MAX_LINEAR_CORRECTION: f32 = 0.2;
+/// 法向接近速度低于该值时不反弹，使静止接触能够收敛
+pub const RESTITUTION_VELOCITY_THRESHOLD: f32 = 1.0;
+/// 切向相对速度低于该值时按静摩擦系数求解，否则按动摩擦系数
+pub const STATIC_FRICTION_VELOCITY: f32 = 0.1;
+
+/// 窄相检测得到的一个接触
+#[derive(Clone, Copy)]
+pub struct Contact {
+    pub body1: BodyHandle,
+    pub body2: BodyHandle,
+    pub info: CollisionInfo,
+}
+
+/// 接触缓存键：物体对 + 接触特征，用于跨帧匹配同一接触点
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContactKey {
+    pub body1: BodyHandle,
+    pub body2: BodyHandle,
+    pub feature: u32,
+}
+
+/// 一个接触点上的累积冲量
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ContactImpulse {
+    pub normal: f32,
+    pub tangent: [f32; 2],
+}
+
+/// 求解期间使用的接触约束
+struct ContactConstraint {
+    key: ContactKey,
+    point: Vector3<f32>,
+    normal: Vector3<f32>,
+    tangents: [Vector3<f32>; 2],
+    normal_mass: f32,
+    tangent_mass: [f32; 2],
+    velocity_bias: f32, // 弹性反弹的目标分离速度
+    friction: f32,      // 按初始切向速度选取的静摩擦或动摩擦系数
+    rolling_friction: f32,
+    rolling_mass: [f32; 2], // 绕两个切向轴转动的有效质量
+    rolling_impulse: [f32; 2],
+    penetration_depth: f32,
+    origin1: Vector3<f32>, // 求解开始时两物体的位置，用于估计位置迭代中的穿透
+    origin2: Vector3<f32>,
+    impulse: ContactImpulse,
+}
+
+/// 沿方向 `direction` 施加单位冲量时的有效质量倒数
+///
+/// k = 1/m1 + 1/m2 + ((I1^-1 (r1 × d)) × r1 + (I2^-1 (r2 × d)) × r2) · d
+fn inv_effective_mass(
+    body1: &PhysicsBody,
+    body2: &PhysicsBody,
+    point: Vector3<f32>,
+    direction: Vector3<f32>,
+) -> f32 {
+    let r1 = point - body1.position();
+    let r2 = point - body2.position();
+    let angular1 = (body1.inv_inertia_world() * r1.cross(direction)).cross(r1);
+    let angular2 = (body2.inv_inertia_world() * r2.cross(direction)).cross(r2);
+    body1.inv_mass + body2.inv_mass + (angular1 + angular2).dot(direction)
+}
+
+/// 绕轴 `axis` 施加单位角冲量时的有效质量倒数：k = a · (I1^-1 a) + a · (I2^-1 a)
+fn inv_angular_mass(body1: &PhysicsBody, body2: &PhysicsBody, axis: Vector3<f32>) -> f32 {
+    (body1.inv_inertia_world() * axis + body2.inv_inertia_world() * axis).dot(axis)
+}
+
+fn mass_from_inv(k: f32) -> f32 {
+    if k > 0.0 {
+        1.0 / k
+    } else {
+        0.0
+    }
+}
+
+/// 顺序冲量（Sequential Impulse）接触求解器
+///
+/// 每帧先用缓存的累积冲量预热，再进行若干次速度迭代与位置迭代。
+/// 冲量 `+P` 作用于 body1，`-P` 作用于 body2，接触法线指向 body1。
+#[derive(Default)]
+pub struct ContactSolver {
+    constraints: Vec<ContactConstraint>,
+    cache: HashMap<ContactKey, ContactImpulse>,
+}
+
+impl ContactSolver {
+    /// 根据本帧的接触构建约束，接触系数由双方的材质组合得到，并按设置进行预热
+    pub fn prepare(
+        &mut self,
+        bodies: &mut BodySet,
+        contacts: Vec<Contact>,
+        materials: &MaterialLibrary,
+        settings: &WorldSettings,
+    ) {
+        self.constraints.clear();
+        if !settings.warm_starting {
+            self.cache.clear();
+        }
+
+        for contact in contacts {
+            let Some((body1, body2)) = bodies.get_pair_mut(contact.body1, contact.body2) else {
+                continue;
+            };
+
+            let info = contact.info;
+            let key = ContactKey {
+                body1: contact.body1,
+                body2: contact.body2,
+                feature: info.feature,
+            };
+            let tangent1 = any_perpendicular(info.normal);
+            let tangent2 = info.normal.cross(tangent1);
+
+            let relative_velocity = body1.velocity_at_point(info.contact_point)
+                - body2.velocity_at_point(info.contact_point);
+            let normal_velocity = relative_velocity.dot(info.normal);
+            let material = materials.combine(body1.material, body2.material);
+            let velocity_bias = if normal_velocity < -RESTITUTION_VELOCITY_THRESHOLD {
+                -material.restitution * normal_velocity
+            } else {
+                0.0
+            };
+            let tangent_speed = (relative_velocity - info.normal * normal_velocity).magnitude();
+            let friction = if tangent_speed < STATIC_FRICTION_VELOCITY {
+                material.static_friction
+            } else {
+                material.dynamic_friction
+            };
+
+            let impulse = self.cache.get(&key).copied().unwrap_or_default();
+
+            let constraint = ContactConstraint {
+                key,
+                point: info.contact_point,
+                normal: info.normal,
+                tangents: [tangent1, tangent2],
+                normal_mass: mass_from_inv(inv_effective_mass(
+                    body1,
+                    body2,
+                    info.contact_point,
+                    info.normal,
+                )),
+                tangent_mass: [
+                    mass_from_inv(inv_effective_mass(
+                        body1,
+                        body2,
+                        info.contact_point,
+                        tangent1,
+                    )),
+                    mass_from_inv(inv_effective_mass(
+                        body1,
+                        body2,
+                        info.contact_point,
+                        tangent2,
+                    )),
+                ],
+                velocity_bias,
+                friction,
+                rolling_friction: material.rolling_friction,
+                rolling_mass: [
+                    mass_from_inv(inv_angular_mass(body1, body2, tangent1)),
+                    mass_from_inv(inv_angular_mass(body1, body2, tangent2)),
+                ],
+                rolling_impulse: [0.0; 2],
+                penetration_depth: info.penetration_depth,
+                origin1: body1.position(),
+                origin2: body2.position(),
+                impulse,
+            };
+
+            // 预热：先施加上一帧的累积冲量
+            let warm_impulse = constraint.normal * impulse.normal
+                + constraint.tangents[0] * impulse.tangent[0]
+                + constraint.tangents[1] * impulse.tangent[1];
+            body1.apply_impulse(warm_impulse, constraint.point);
+            body2.apply_impulse(-warm_impulse, constraint.point);
+
+            self.constraints.push(constraint);
+        }
+    }
+
+    /// 一次速度迭代：依次求解每个接触的摩擦与法向约束
+    pub fn solve_velocities(&mut self, bodies: &mut BodySet) {
+        for constraint in &mut self.constraints {
+            let Some((body1, body2)) =
+                bodies.get_pair_mut(constraint.key.body1, constraint.key.body2)
+            else {
+                continue;
+            };
+            let point = constraint.point;
+
+            // 摩擦：累积冲量被限制在 [-μ·jn, μ·jn] 内
+            let max_friction = constraint.friction * constraint.impulse.normal;
+            for k in 0..2 {
+                let tangent = constraint.tangents[k];
+                let relative_velocity =
+                    body1.velocity_at_point(point) - body2.velocity_at_point(point);
+                let lambda = -relative_velocity.dot(tangent) * constraint.tangent_mass[k];
+
+                let old_impulse = constraint.impulse.tangent[k];
+                let new_impulse = (old_impulse + lambda).clamp(-max_friction, max_friction);
+                constraint.impulse.tangent[k] = new_impulse;
+
+                let impulse = tangent * (new_impulse - old_impulse);
+                body1.apply_impulse(impulse, point);
+                body2.apply_impulse(-impulse, point);
+            }
+
+            // 法向：累积冲量不能为负（接触只能推不能拉）
+            let relative_velocity = body1.velocity_at_point(point) - body2.velocity_at_point(point);
+            let normal_velocity = relative_velocity.dot(constraint.normal);
+            let lambda = constraint.normal_mass * (constraint.velocity_bias - normal_velocity);
+
+            let old_impulse = constraint.impulse.normal;
+            let new_impulse = (old_impulse + lambda).max(0.0);
+            constraint.impulse.normal = new_impulse;
+
+            let impulse = constraint.normal * (new_impulse - old_impulse);
+            body1.apply_impulse(impulse, point);
+            body2.apply_impulse(-impulse, point);
+
+            // 滚动摩擦：阻碍绕切向轴的相对转动，累积角冲量被限制在 [-μr·jn, μr·jn] 内
+            if constraint.rolling_friction > 0.0 {
+                let max_rolling = constraint.rolling_friction * constraint.impulse.normal;
+                for k in 0..2 {
+                    let axis = constraint.tangents[k];
+                    let relative_spin = (body1.angular_velocity - body2.angular_velocity).dot(axis);
+                    let lambda = -relative_spin * constraint.rolling_mass[k];
+
+                    let old_impulse = constraint.rolling_impulse[k];
+                    let new_impulse = (old_impulse + lambda).clamp(-max_rolling, max_rolling);
+                    constraint.rolling_impulse[k] = new_impulse;
+
+                    let impulse = axis * (new_impulse - old_impulse);
+                    body1.apply_angular_impulse(impulse);
+                    body2.apply_angular_impulse(-impulse);
+                }
+            }
+        }
+    }
+
+    /// 一次位置迭代：直接移动物体位置消除穿透，不改变速度
+    pub fn solve_positions(&mut self, bodies: &mut BodySet) {
+        for constraint in &self.constraints {
+            let Some((body1, body2)) =
+                bodies.get_pair_mut(constraint.key.body1, constraint.key.body2)
+            else {
+                continue;
+            };
+
+            let inv_mass_sum = body1.inv_mass + body2.inv_mass;
+            if inv_mass_sum <= 0.0 {
+                continue;
+            }
+
+            // 用两物体相对检测时的位移估计当前的分离距离（负值表示穿透）
+            let moved =
+                (body1.position() - constraint.origin1) - (body2.position() - constraint.origin2);
+            let separation = moved.dot(constraint.normal) - constraint.penetration_depth;
+            let correction =
+                (BAUMGARTE * (separation + PENETRATION_SLOP)).clamp(-MAX_LINEAR_CORRECTION, 0.0);
+
+            let offset = constraint.normal * (-correction / inv_mass_sum);
+            body1.transform.translation += offset * body1.inv_mass;
+            body2.transform.translation -= offset * body2.inv_mass;
+        }
+    }
+
+    /// 保存本帧的累积冲量，供下一帧预热
+    pub fn store_impulses(&mut self) {
+        self.cache = self
+            .constraints
+            .iter()
+            .map(|constraint| (constraint.key, constraint.impulse))
+            .collect();
+    }
+
+    /// 查询某个接触上一帧的累积冲量
+    pub fn cached_impulse(&self, key: &ContactKey) -> Option<ContactImpulse> {
+        self.cache.get(key).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Transform;
+    use crate::physics::material::PhysicsMaterial;
+    use crate::physics::shape::{Cylinder, Plane, Shape};
+
+    fn bodies() -> (BodySet, BodyHandle, BodyHandle) {
+        let mut bodies = BodySet::default();
+        let cylinder = bodies.insert(PhysicsBody::new_dynamic(
+            Shape::Cylinder(Cylinder {
+                center: Vector3::zero(),
+                axis: Vector3::unit_y(),
+                radius: 0.5,
+                height: 1.0,
+            }),
+            Transform::translation(0.0, 0.5, 0.0),
+            1.0,
+        ));
+        let ground = bodies.insert(PhysicsBody::new_static(
+            Shape::Plane(Plane {
+                normal: Vector3::unit_y(),
+                distance: 0.0,
+            }),
+            Transform::identity(),
+        ));
+        (bodies, cylinder, ground)
+    }
+
+    fn ground_contact(cylinder: BodyHandle, ground: BodyHandle) -> Contact {
+        Contact {
+            body1: cylinder,
+            body2: ground,
+            info: CollisionInfo {
+                contact_point: Vector3::new(0.0, 0.0, 0.0),
+                penetration_depth: 0.0,
+                normal: Vector3::unit_y(),
+                feature: 0,
+            },
+        }
+    }
+
+    fn solve(
+        solver: &mut ContactSolver,
+        bodies: &mut BodySet,
+        materials: &MaterialLibrary,
+        contacts: Vec<Contact>,
+    ) {
+        let settings = WorldSettings::default();
+        solver.prepare(bodies, contacts, materials, &settings);
+        for _ in 0..settings.velocity_iterations {
+            solver.solve_velocities(bodies);
+        }
+        solver.store_impulses();
+    }
+
+    #[test]
+    fn test_bounce_with_restitution() {
+        let (mut bodies, cylinder, ground) = bodies();
+        let mut materials = MaterialLibrary::default();
+        let bouncy = materials.register(
+            "bouncy",
+            PhysicsMaterial {
+                restitution: 0.5,
+                ..Default::default()
+            },
+        );
+        bodies.get_mut(cylinder).unwrap().material = bouncy;
+        bodies.get_mut(ground).unwrap().material = bouncy;
+        bodies.get_mut(cylinder).unwrap().velocity = Vector3::new(0.0, -4.0, 0.0);
+
+        let mut solver = ContactSolver::default();
+        solve(
+            &mut solver,
+            &mut bodies,
+            &materials,
+            vec![ground_contact(cylinder, ground)],
+        );
+
+        let body = bodies.get(cylinder).unwrap();
+        assert!((body.velocity.y - 2.0).abs() < 1e-4);
+        assert!(body.angular_velocity.magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn test_friction_is_clamped_by_normal_impulse() {
+        let (mut bodies, cylinder, ground) = bodies();
+        let mut materials = MaterialLibrary::default();
+        let rough = materials.register(
+            "rough",
+            PhysicsMaterial {
+                static_friction: 1.0,
+                dynamic_friction: 0.5,
+                ..Default::default()
+            },
+        );
+        bodies.get_mut(cylinder).unwrap().material = rough;
+        bodies.get_mut(ground).unwrap().material = rough;
+        bodies.get_mut(cylinder).unwrap().velocity = Vector3::new(10.0, -0.5, 0.0);
+
+        let mut solver = ContactSolver::default();
+        solve(
+            &mut solver,
+            &mut bodies,
+            &materials,
+            vec![ground_contact(cylinder, ground)],
+        );
+
+        // 滑动时按动摩擦系数：法向冲量 0.5，摩擦冲量最多 0.25，且接触点在质心下方，会产生转动
+        let body = bodies.get(cylinder).unwrap();
+        assert!(body.velocity.y.abs() < 1e-4);
+        assert!((body.velocity.x - 9.75).abs() < 1e-3);
+        assert!(body.angular_velocity.z < 0.0);
+    }
+
+    #[test]
+    fn test_impulses_are_cached_for_warm_start() {
+        let (mut bodies, cylinder, ground) = bodies();
+        bodies.get_mut(cylinder).unwrap().velocity = Vector3::new(0.0, -0.5, 0.0);
+
+        let mut solver = ContactSolver::default();
+        let contact = ground_contact(cylinder, ground);
+        let materials = MaterialLibrary::default();
+        solve(&mut solver, &mut bodies, &materials, vec![contact]);
+
+        let key = ContactKey {
+            body1: cylinder,
+            body2: ground,
+            feature: 0,
+        };
+        let cached = solver.cached_impulse(&key).unwrap();
+        assert!((cached.normal - 0.5).abs() < 1e-4);
+
+        // 下一帧同样的接触会先施加缓存的冲量
+        bodies.get_mut(cylinder).unwrap().velocity = Vector3::new(0.0, -0.5, 0.0);
+        solver.prepare(
+            &mut bodies,
+            vec![contact],
+            &materials,
+            &WorldSettings::default(),
+        );
+        assert!(bodies.get(cylinder).unwrap().velocity.y.abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_rolling_friction_slows_spin() {
+        // 圆柱以相同的初始状态压在地面上，返回一次求解后的角速度
+        let spin_after = |rolling_friction: f32| {
+            let (mut bodies, cylinder, ground) = bodies();
+            let mut materials = MaterialLibrary::default();
+            let material = materials.register(
+                "rolling",
+                PhysicsMaterial {
+                    rolling_friction,
+                    ..Default::default()
+                },
+            );
+            bodies.get_mut(ground).unwrap().material = material;
+            let body = bodies.get_mut(cylinder).unwrap();
+            body.material = material;
+            body.velocity = Vector3::new(0.0, -0.5, 0.0);
+            body.angular_velocity = Vector3::new(0.0, 0.0, 2.0);
+
+            let mut solver = ContactSolver::default();
+            solve(
+                &mut solver,
+                &mut bodies,
+                &materials,
+                vec![ground_contact(cylinder, ground)],
+            );
+            bodies.get(cylinder).unwrap().angular_velocity.z
+        };
+
+        let free = spin_after(0.0);
+        let resisted = spin_after(0.1);
+        assert!(resisted > 0.0);
+        assert!(resisted < free - 1e-3);
+    }
+}
@@ -0,0 +1,740 @@
+use crate::common::Transform;
+use crate::physics::aabb::Aabb;
+use crate::physics::collision::{
+    any_perpendicular, collide_sphere_sphere, reduce_contacts, support_map, transform_cylinder,
+    transform_sphere, Capsule, CollisionInfo, ContactManifold, Obb, CYLINDER_RIM_SAMPLES,
+};
+use crate::physics::gjk::{closest_on_triangle, closest_points, collide_convex, SupportMap};
+use crate::physics::shape::{Shape, Sphere};
+use cgmath::*;
+use std::collections::HashMap;
+
+/// 三角形，顶点按逆时针顺序排列时法线朝向正面
+#[derive(Clone, Copy)]
+pub struct Triangle {
+    pub a: Vector3<f32>,
+    pub b: Vector3<f32>,
+    pub c: Vector3<f32>,
+}
+
+impl Triangle {
+    pub fn normal(&self) -> Vector3<f32> {
+        (self.b - self.a).cross(self.c - self.a).normalize()
+    }
+
+    /// 三角形上离 p 最近的点，以及该点所在的特征（顶点、边或面的顶点索引）
+    fn closest_feature(&self, p: Vector3<f32>) -> (Vector3<f32>, Vec<usize>) {
+        let (point, indices, _) = closest_on_triangle(self.a - p, self.b - p, self.c - p);
+        (point + p, indices)
+    }
+
+    /// 射线与三角形（双面）的交点参数 t（Möller–Trumbore 算法）
+    pub fn ray_intersection(
+        &self,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        max_t: f32,
+    ) -> Option<f32> {
+        let e1 = self.b - self.a;
+        let e2 = self.c - self.a;
+        let p = direction.cross(e2);
+        let det = e1.dot(p);
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+        let s = origin - self.a;
+        let u = s.dot(p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(e1);
+        let v = direction.dot(q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let t = e2.dot(q) * inv_det;
+        (0.0..=max_t).contains(&t).then_some(t)
+    }
+
+    /// 点在三角形所在平面上的投影是否落在三角形内
+    fn contains_projection(&self, p: Vector3<f32>) -> bool {
+        let normal = (self.b - self.a).cross(self.c - self.a);
+        [(self.a, self.b), (self.b, self.c), (self.c, self.a)]
+            .iter()
+            .all(|&(from, to)| (to - from).cross(p - from).dot(normal) >= -1e-6)
+    }
+}
+
+impl SupportMap for Triangle {
+    fn support(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        let da = self.a.dot(direction);
+        let db = self.b.dot(direction);
+        let dc = self.c.dot(direction);
+        if da >= db && da >= dc {
+            self.a
+        } else if db >= dc {
+            self.b
+        } else {
+            self.c
+        }
+    }
+}
+
+// 叶节点最多包含的三角形数
+const BVH_LEAF_SIZE: usize = 4;
+// 相邻面法线夹角余弦大于该值视为共面，共面的边不是活动边
+const FLAT_EDGE_COS: f32 = 0.999;
+// 焊接顶点时的距离容差
+const WELD_TOLERANCE: f32 = 1e-5;
+
+enum BvhNode {
+    Leaf {
+        aabb: Aabb,
+        start: usize,
+        count: usize,
+    },
+    Internal {
+        aabb: Aabb,
+        left: usize,
+        right: usize,
+    },
+}
+
+impl BvhNode {
+    fn aabb(&self) -> &Aabb {
+        match self {
+            BvhNode::Leaf { aabb, .. } | BvhNode::Internal { aabb, .. } => aabb,
+        }
+    }
+}
+
+/// 三角网格碰撞形状，只用于静态物体（如关卡几何）
+///
+/// 构造时焊接重复顶点、建立三角形 BVH，并标记活动边：
+/// 网格边界上的边和凸起的折边是活动边，共面或内凹的边不是。
+/// 物体压在非活动边或其端点上时，接触法线改用三角形面法线，
+/// 这样滑过三角形接缝时不会被内部边“绊住”。
+pub struct TriMesh {
+    vertices: Vec<Vector3<f32>>,
+    triangles: Vec<[u32; 3]>,
+    // 每个三角形三条边是否为活动边，第 i 条边从顶点 i 指向顶点 i+1
+    active_edges: Vec<[bool; 3]>,
+    bvh: Vec<BvhNode>,
+}
+
+impl TriMesh {
+    /// 由顶点位置与三角形索引构造，数据格式与 `resource::load_mesh_geometry` 的返回值一致
+    pub fn new(positions: Vec<Vector3<f32>>, indices: Vec<u32>) -> Self {
+        let (vertices, remap) = weld_vertices(positions);
+
+        // 焊接后退化的三角形直接丢弃
+        let mut triangles = indices
+            .chunks_exact(3)
+            .map(|t| {
+                [
+                    remap[t[0] as usize],
+                    remap[t[1] as usize],
+                    remap[t[2] as usize],
+                ]
+            })
+            .filter(|t| {
+                let (a, b, c) = (
+                    vertices[t[0] as usize],
+                    vertices[t[1] as usize],
+                    vertices[t[2] as usize],
+                );
+                t[0] != t[1]
+                    && t[1] != t[2]
+                    && t[0] != t[2]
+                    && (b - a).cross(c - a).magnitude2() > 1e-12
+            })
+            .collect::<Vec<_>>();
+        assert!(
+            !triangles.is_empty(),
+            "triangle mesh needs at least one triangle"
+        );
+
+        let mut bvh = Vec::new();
+        let bounds = triangles
+            .iter()
+            .map(|t| Aabb::from_points(t.map(|i| vertices[i as usize])))
+            .collect::<Vec<_>>();
+        let mut order = (0..triangles.len()).collect::<Vec<_>>();
+        build_bvh(&mut bvh, &mut order, 0, &bounds);
+        triangles = order.iter().map(|&i| triangles[i]).collect();
+
+        let mut mesh = Self {
+            vertices,
+            triangles,
+            active_edges: Vec::new(),
+            bvh,
+        };
+        mesh.active_edges = mesh.compute_active_edges();
+        mesh
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// 局部坐标下的三角形
+    pub fn triangle(&self, index: usize) -> Triangle {
+        let [a, b, c] = self.triangles[index];
+        Triangle {
+            a: self.vertices[a as usize],
+            b: self.vertices[b as usize],
+            c: self.vertices[c as usize],
+        }
+    }
+
+    /// 局部坐标下整个网格的包围盒
+    pub fn aabb(&self) -> Aabb {
+        *self.bvh[0].aabb()
+    }
+
+    /// 遍历包围盒与给定区域（局部坐标）相交的三角形
+    pub fn query(&self, region: &Aabb, mut f: impl FnMut(usize)) {
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.bvh[index];
+            if !node.aabb().intersects(region) {
+                continue;
+            }
+            match *node {
+                BvhNode::Leaf { start, count, .. } => (start..start + count).for_each(&mut f),
+                BvhNode::Internal { left, right, .. } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+    }
+
+    fn compute_active_edges(&self) -> Vec<[bool; 3]> {
+        // 无向边 -> 使用该边的三角形列表
+        let mut edge_map: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+        for (index, t) in self.triangles.iter().enumerate() {
+            for e in 0..3 {
+                let (i, j) = (t[e], t[(e + 1) % 3]);
+                edge_map
+                    .entry((i.min(j), i.max(j)))
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        self.triangles
+            .iter()
+            .enumerate()
+            .map(|(index, t)| {
+                let triangle = self.triangle(index);
+                let mut active = [true; 3];
+                for (e, flag) in active.iter_mut().enumerate() {
+                    let (i, j) = (t[e], t[(e + 1) % 3]);
+                    let neighbors = &edge_map[&(i.min(j), i.max(j))];
+                    // 边界边与非流形边保持活动
+                    if neighbors.len() != 2 {
+                        continue;
+                    }
+                    let other = if neighbors[0] == index {
+                        neighbors[1]
+                    } else {
+                        neighbors[0]
+                    };
+                    *flag = edge_is_active(&triangle, &self.triangle(other));
+                }
+                active
+            })
+            .collect()
+    }
+}
+
+impl TriangleSet for TriMesh {
+    fn triangle(&self, index: usize) -> Triangle {
+        TriMesh::triangle(self, index)
+    }
+
+    fn query(&self, region: &Aabb, f: &mut dyn FnMut(usize)) {
+        TriMesh::query(self, region, f)
+    }
+
+    fn feature_is_active(&self, triangle: usize, feature: &[usize]) -> bool {
+        feature_on_active_edge(&self.active_edges[triangle], feature)
+    }
+
+    fn query_ray(
+        &self,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        max_t: f32,
+        f: &mut dyn FnMut(usize),
+    ) {
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.bvh[index];
+            if node
+                .aabb()
+                .ray_intersection(origin, direction, max_t)
+                .is_none()
+            {
+                continue;
+            }
+            match *node {
+                BvhNode::Leaf { start, count, .. } => (start..start + count).for_each(&mut *f),
+                BvhNode::Internal { left, right, .. } => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+    }
+}
+
+/// 可供凸形状做窄相检测的三角形集合（三角网格、高度场），三角形均为局部坐标
+pub(crate) trait TriangleSet {
+    fn triangle(&self, index: usize) -> Triangle;
+    /// 遍历包围盒可能与给定区域（局部坐标）相交的三角形
+    fn query(&self, region: &Aabb, f: &mut dyn FnMut(usize));
+    /// 最近特征是否需要保留原法线：活动边，或至少连着一条活动边的顶点
+    fn feature_is_active(&self, triangle: usize, feature: &[usize]) -> bool;
+
+    /// 遍历可能与线段 origin + t · direction（0 ≤ t ≤ max_t，局部坐标）相交的三角形，
+    /// 默认实现查询线段的包围盒，max_t 需为有限值
+    fn query_ray(
+        &self,
+        origin: Vector3<f32>,
+        direction: Vector3<f32>,
+        max_t: f32,
+        f: &mut dyn FnMut(usize),
+    ) {
+        self.query(&Aabb::from_points([origin, origin + direction * max_t]), f);
+    }
+}
+
+/// 两个三角形共享的边是否为活动边：凸起且不共面
+pub(crate) fn edge_is_active(triangle: &Triangle, neighbor: &Triangle) -> bool {
+    let normal = triangle.normal();
+    // 共享边在本三角形平面内，邻居质心的高度与其对顶点的高度同号
+    let centroid = (neighbor.a + neighbor.b + neighbor.c) / 3.0;
+    let convex = normal.dot(centroid - triangle.a) < 0.0;
+    let flat = normal.dot(neighbor.normal()) > FLAT_EDGE_COS;
+    convex && !flat
+}
+
+/// 由三条边的活动标记判断最近特征（顶点或边）是否活动，第 i 条边从顶点 i 指向顶点 i+1
+pub(crate) fn feature_on_active_edge(edges: &[bool; 3], feature: &[usize]) -> bool {
+    match *feature {
+        [v] => edges[v] || edges[(v + 2) % 3],
+        [0, 1] => edges[0],
+        [1, 2] => edges[1],
+        [0, 2] => edges[2],
+        _ => false,
+    }
+}
+
+/// 合并位置相同的顶点（OBJ 中同一位置常因法线、纹理坐标不同而重复）
+fn weld_vertices(positions: Vec<Vector3<f32>>) -> (Vec<Vector3<f32>>, Vec<u32>) {
+    let mut vertices = Vec::new();
+    let mut lookup: HashMap<[i64; 3], u32> = HashMap::new();
+    let remap = positions
+        .into_iter()
+        .map(|p| {
+            let key = [0, 1, 2].map(|i| (p[i] / WELD_TOLERANCE).round() as i64);
+            *lookup.entry(key).or_insert_with(|| {
+                vertices.push(p);
+                (vertices.len() - 1) as u32
+            })
+        })
+        .collect();
+    (vertices, remap)
+}
+
+/// 自顶向下构建 BVH：沿质心分布最长的轴按中位数划分，返回节点索引
+fn build_bvh(
+    nodes: &mut Vec<BvhNode>,
+    items: &mut [usize],
+    offset: usize,
+    bounds: &[Aabb],
+) -> usize {
+    let aabb = items
+        .iter()
+        .fold(Aabb::empty(), |acc, &i| acc.union(&bounds[i]));
+    let index = nodes.len();
+
+    if items.len() <= BVH_LEAF_SIZE {
+        nodes.push(BvhNode::Leaf {
+            aabb,
+            start: offset,
+            count: items.len(),
+        });
+        return index;
+    }
+
+    let centroids = Aabb::from_points(items.iter().map(|&i| bounds[i].center()));
+    let extent = centroids.max - centroids.min;
+    let axis = if extent.x >= extent.y && extent.x >= extent.z {
+        0
+    } else if extent.y >= extent.z {
+        1
+    } else {
+        2
+    };
+    items.sort_by(|&a, &b| bounds[a].center()[axis].total_cmp(&bounds[b].center()[axis]));
+
+    // 先占位，子节点建好后再填入
+    nodes.push(BvhNode::Leaf {
+        aabb,
+        start: 0,
+        count: 0,
+    });
+    let mid = items.len() / 2;
+    let (left_items, right_items) = items.split_at_mut(mid);
+    let left = build_bvh(nodes, left_items, offset, bounds);
+    let right = build_bvh(nodes, right_items, offset + mid, bounds);
+    nodes[index] = BvhNode::Internal { aabb, left, right };
+    index
+}
+
+/// 球与三角形的碰撞，返回接触信息（法线指向球）和三角形上的最近特征
+fn collide_sphere_triangle(
+    sphere: &Sphere,
+    triangle: &Triangle,
+) -> Option<(CollisionInfo, Vec<usize>)> {
+    let (closest, feature) = triangle.closest_feature(sphere.center);
+    let on_triangle = Sphere {
+        center: closest,
+        radius: 0.0,
+    };
+    let mut info = collide_sphere_sphere(sphere, &on_triangle)?;
+    if (sphere.center - closest).magnitude2() < 1e-12 {
+        // 球心恰在三角形上
+        info.normal = triangle.normal();
+    }
+    info.contact_point = closest;
+    Some((info, feature))
+}
+
+/// 检测凸形状与三角网格之间的碰撞，返回的法线指向凸形状
+///
+/// 网格为单面：形状中心位于三角形背面时忽略该三角形。
+pub fn collide_trimesh(
+    shape: &Shape,
+    transform: &Transform,
+    mesh: &TriMesh,
+    mesh_transform: &Transform,
+) -> Option<ContactManifold> {
+    collide_triangle_set(shape, transform, mesh, mesh_transform)
+}
+
+/// 凸形状与三角形集合的窄相检测，三角网格与高度场共用
+pub(crate) fn collide_triangle_set(
+    shape: &Shape,
+    transform: &Transform,
+    set: &dyn TriangleSet,
+    set_transform: &Transform,
+) -> Option<ContactManifold> {
+    let support = support_map(shape, transform)?;
+    let bounds = Aabb::from_support(support.as_ref());
+    let center = bounds.center();
+
+    let mut points: Vec<CollisionInfo> = Vec::new();
+    set.query(&bounds.inverse_transformed(set_transform), &mut |index| {
+        let local = set.triangle(index);
+        let triangle = Triangle {
+            a: set_transform.transform_point(local.a),
+            b: set_transform.transform_point(local.b),
+            c: set_transform.transform_point(local.c),
+        };
+        let face_normal = triangle.normal();
+        if (center - triangle.a).dot(face_normal) < 0.0 {
+            return;
+        }
+
+        for (local_feature, info) in triangle_contacts(shape, transform, &triangle) {
+            let Some(info) = correct_internal_edge(set, index, &triangle, support.as_ref(), info)
+            else {
+                continue;
+            };
+
+            let feature = ((index as u32) << 8) | local_feature;
+            let face_contact = info.normal.dot(face_normal) > FLAT_EDGE_COS;
+            let expanded = if face_contact {
+                face_contact_points(shape, transform, &triangle, info.penetration_depth)
+            } else {
+                Vec::new()
+            };
+
+            if expanded.is_empty() {
+                push_unique(&mut points, CollisionInfo { feature, ..info });
+            } else {
+                for (k, point) in expanded.into_iter().enumerate() {
+                    let feature = ((index as u32) << 8) | (16 + k as u32);
+                    push_unique(&mut points, CollisionInfo { feature, ..point });
+                }
+            }
+        }
+    });
+
+    ContactManifold { points }.into_option()
+}
+
+/// 相邻三角形可能给出同一个接触点，只保留一个
+fn push_unique(points: &mut Vec<CollisionInfo>, info: CollisionInfo) {
+    let duplicate = points.iter().any(|p| {
+        (p.contact_point - info.contact_point).magnitude2() < 1e-8
+            && p.normal.dot(info.normal) > FLAT_EDGE_COS
+    });
+    if !duplicate {
+        points.push(info);
+    }
+}
+
+/// 单个三角形上的原始接触，附带局部特征编号
+fn triangle_contacts(
+    shape: &Shape,
+    transform: &Transform,
+    triangle: &Triangle,
+) -> Vec<(u32, CollisionInfo)> {
+    match shape {
+        Shape::Sphere(sphere) => {
+            collide_sphere_triangle(&transform_sphere(sphere, transform), triangle)
+                .map(|(info, _)| (0, info))
+                .into_iter()
+                .collect()
+        }
+        Shape::Capsule {
+            radius,
+            half_height,
+        } => {
+            // 两端的球分别检测，以支撑平躺的胶囊体
+            let capsule = Capsule::new(*radius, *half_height, transform);
+            let mut contacts = [capsule.start, capsule.end]
+                .iter()
+                .enumerate()
+                .filter_map(|(k, &center)| {
+                    let sphere = Sphere {
+                        center,
+                        radius: capsule.radius,
+                    };
+                    collide_sphere_triangle(&sphere, triangle).map(|(info, _)| (1 + k as u32, info))
+                })
+                .collect::<Vec<_>>();
+
+            // 中段：线段与三角形的最近点；线段穿过三角形时改用 EPA
+            let segment = Capsule {
+                radius: 0.0,
+                ..capsule
+            };
+            let middle = match closest_points(&segment, triangle) {
+                Some(closest) if closest.distance < capsule.radius => {
+                    let sphere = Sphere {
+                        center: closest.point_a,
+                        radius: capsule.radius,
+                    };
+                    collide_sphere_triangle(&sphere, triangle).map(|(info, _)| info)
+                }
+                Some(_) => None,
+                None => collide_convex(&capsule, triangle),
+            };
+            let end_depth = contacts
+                .iter()
+                .map(|(_, info)| info.penetration_depth)
+                .fold(0.0, f32::max);
+            if let Some(info) = middle {
+                if info.penetration_depth > end_depth + 1e-3 {
+                    contacts.push((3, info));
+                }
+            }
+            contacts
+        }
+        _ => match support_map(shape, transform) {
+            Some(support) => collide_convex(support.as_ref(), triangle)
+                .map(|info| (4, info))
+                .into_iter()
+                .collect(),
+            None => Vec::new(),
+        },
+    }
+}
+
+/// 内部边处理：最近特征为面、非活动边或非活动顶点时，法线改为三角形面法线并重算穿透深度
+fn correct_internal_edge(
+    set: &dyn TriangleSet,
+    index: usize,
+    triangle: &Triangle,
+    shape: &dyn SupportMap,
+    info: CollisionInfo,
+) -> Option<CollisionInfo> {
+    let face_normal = triangle.normal();
+    let (_, feature) = triangle.closest_feature(info.contact_point);
+    if info.normal.dot(face_normal) > 0.0 && set.feature_is_active(index, &feature) {
+        return Some(info);
+    }
+
+    // 沿面法线方向的穿透深度
+    let deepest = shape.support(-face_normal);
+    let depth = (triangle.a - deepest).dot(face_normal);
+    if depth <= 0.0 {
+        return None;
+    }
+
+    Some(CollisionInfo {
+        // 取最深点在三角形平面上的投影，与相邻三角形给出的接触点一致，便于去重
+        contact_point: deepest + face_normal * depth,
+        penetration_depth: depth,
+        normal: face_normal,
+        feature: info.feature,
+    })
+}
+
+/// 面接触时用形状的顶点（或采样点）生成多个接触点，使盒子等平放在网格上时能稳定支撑
+fn face_contact_points(
+    shape: &Shape,
+    transform: &Transform,
+    triangle: &Triangle,
+    depth: f32,
+) -> Vec<CollisionInfo> {
+    let candidates: Vec<Vector3<f32>> = match shape {
+        Shape::Box { half_extents } => Obb::new(*half_extents, transform).vertices().to_vec(),
+        Shape::Cylinder(cylinder) => {
+            let cylinder = transform_cylinder(cylinder, transform);
+            let axis = cylinder.axis.normalize();
+            let u = any_perpendicular(axis);
+            let v = axis.cross(u);
+            [1.0f32, -1.0]
+                .iter()
+                .flat_map(|&sign| {
+                    let cap_center = cylinder.center + axis * (sign * cylinder.height / 2.0);
+                    (0..CYLINDER_RIM_SAMPLES).map(move |k| {
+                        let angle = k as f32 / CYLINDER_RIM_SAMPLES as f32 * std::f32::consts::TAU;
+                        cap_center + (u * angle.cos() + v * angle.sin()) * cylinder.radius
+                    })
+                })
+                .collect()
+        }
+        Shape::ConvexHull(hull) => hull
+            .points
+            .iter()
+            .map(|&p| transform.transform_point(p))
+            .collect(),
+        _ => return Vec::new(),
+    };
+
+    let normal = triangle.normal();
+    // 只取接近最深处的点，避免把远离三角形的顶点当作接触
+    let max_depth = depth * 2.0 + 0.01;
+    let points = candidates
+        .into_iter()
+        .enumerate()
+        .filter_map(|(k, point)| {
+            let dist = (point - triangle.a).dot(normal);
+            (dist <= 0.0 && -dist <= max_depth && triangle.contains_projection(point)).then_some(
+                CollisionInfo {
+                    // 投影到三角形平面上，与内部边修正后的接触点取法一致
+                    contact_point: point - normal * dist,
+                    penetration_depth: -dist,
+                    normal,
+                    feature: k as u32,
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if points.len() > 4 {
+        reduce_contacts(&points, normal)
+    } else {
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 由 n×n 个正方形格子组成的平坦地面，位于 y = 0，法线朝上
+    fn flat_grid(n: usize, cell: f32) -> TriMesh {
+        let mut positions = Vec::new();
+        for i in 0..=n {
+            for j in 0..=n {
+                positions.push(Vector3::new(i as f32 * cell, 0.0, j as f32 * cell));
+            }
+        }
+        let mut indices = Vec::new();
+        let stride = (n + 1) as u32;
+        for i in 0..n as u32 {
+            for j in 0..n as u32 {
+                let v0 = i * stride + j;
+                let v1 = v0 + 1;
+                let v2 = v0 + stride;
+                let v3 = v2 + 1;
+                indices.extend([v0, v1, v2, v2, v1, v3]);
+            }
+        }
+        TriMesh::new(positions, indices)
+    }
+
+    #[test]
+    fn test_bvh_query_and_active_edges() {
+        let mesh = flat_grid(4, 1.0);
+        assert_eq!(mesh.triangle_count(), 32);
+        for i in 0..mesh.triangle_count() {
+            assert!(mesh.triangle(i).normal().y > 0.99);
+        }
+
+        // 查询一个格子大小的区域，只应返回附近的三角形
+        let mut hits = Vec::new();
+        let region = Aabb::new(Vector3::new(1.2, -0.1, 1.2), Vector3::new(1.8, 0.1, 1.8));
+        mesh.query(&region, |i| hits.push(i));
+        assert!(!hits.is_empty() && hits.len() <= 8);
+
+        // 平坦网格内部的边都不是活动边，外围的边是
+        let active = mesh.active_edges.iter().flatten().filter(|&&a| a).count();
+        assert_eq!(active, 16);
+    }
+
+    #[test]
+    fn test_sphere_on_seam_uses_face_normal() {
+        let mesh = flat_grid(2, 1.0);
+        let shape = Shape::Sphere(Sphere {
+            center: Vector3::zero(),
+            radius: 0.5,
+        });
+
+        // 球心正好在两三角形的公共边上方，稍微压入
+        let transform = Transform::translation(0.5, 0.45, 0.5);
+        let manifold =
+            collide_trimesh(&shape, &transform, &mesh, &Transform::identity()).expect("contact");
+        for point in &manifold.points {
+            assert!((point.normal - Vector3::unit_y()).magnitude() < 1e-4);
+            assert!((point.penetration_depth - 0.05).abs() < 1e-4);
+        }
+
+        // 球心越过内部边：左侧三角形的最近点在边上，法线不应指向边，
+        // 修正为面法线后与右侧三角形的接触点重合并被去重
+        let transform = Transform::translation(1.2, 0.45, 0.5);
+        let manifold =
+            collide_trimesh(&shape, &transform, &mesh, &Transform::identity()).expect("contact");
+        assert_eq!(manifold.points.len(), 1);
+        assert!((manifold.points[0].normal - Vector3::unit_y()).magnitude() < 1e-4);
+        assert!((manifold.points[0].penetration_depth - 0.05).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_box_rests_on_mesh_with_manifold() {
+        let mesh = flat_grid(2, 2.0);
+        let shape = Shape::Box {
+            half_extents: Vector3::new(0.5, 0.5, 0.5),
+        };
+        let transform = Transform::translation(1.7, 0.48, 1.7);
+        let manifold =
+            collide_trimesh(&shape, &transform, &mesh, &Transform::identity()).expect("contact");
+
+        // 底面四个顶点分布在多个三角形上，去重后正好四个
+        assert_eq!(manifold.points.len(), 4);
+        for point in &manifold.points {
+            assert!((point.normal - Vector3::unit_y()).magnitude() < 1e-4);
+            assert!((point.penetration_depth - 0.02).abs() < 1e-4);
+        }
+    }
+}
@@ -35,6 +35,39 @@ impl Camera {
         // 3.
         OPENGL_TO_WGPU_MATRIX * proj * view
     }
+
+    /// 把窗口像素坐标反投影为世界空间中的射线，返回 (起点, 单位方向)
+    ///
+    /// 直接对渲染用的观察投影矩阵求逆，保证射线与画面上的像素对应
+    pub fn screen_ray(
+        &self,
+        position: PhysicalPosition<f64>,
+        window_size: PhysicalSize<u32>,
+    ) -> (cgmath::Vector3<f32>, cgmath::Vector3<f32>) {
+        use cgmath::{EuclideanSpace, InnerSpace, SquareMatrix};
+        // 像素坐标转到 [-1, 1] 的标准化设备坐标，y 轴朝上
+        let ndc_x = (2.0 * position.x / window_size.width.max(1) as f64 - 1.0) as f32;
+        let ndc_y = (1.0 - 2.0 * position.y / window_size.height.max(1) as f64) as f32;
+
+        let eye = self.eye.to_vec();
+        let forward = (self.target - self.eye).normalize();
+        let Some(inverse) = self.build_view_projection_matrix().invert() else {
+            return (eye, forward);
+        };
+        let unproject = |depth: f32| {
+            let point = inverse * cgmath::Vector4::new(ndc_x, ndc_y, depth, 1.0);
+            point.truncate() / point.w
+        };
+
+        let near = unproject(0.0);
+        let mut direction = (unproject(1.0) - near).normalize();
+        if direction.dot(forward) < 0.0 {
+            direction = -direction;
+        }
+        // 起点取射线所在直线上离相机最近的点
+        let origin = near + direction * (eye - near).dot(direction);
+        (origin, direction)
+    }
 }
 
 // We need this for Rust to store our data correctly for the shaders
@@ -72,6 +105,9 @@ pub struct CameraController {
     scroll_val: f32,
 
     is_mouse_right_pressed: bool,
+    is_mouse_left_pressed: bool,
+    left_click: Option<PhysicalPosition<f64>>, // 尚未处理的左键按下位置
+    cursor_position: Option<PhysicalPosition<f64>>,
     mouse_speed: f32,
     old_position: Option<PhysicalPosition<f64>>,
     new_position: Option<PhysicalPosition<f64>>,
@@ -89,6 +125,9 @@ impl CameraController {
             is_up_pressed: false,
             is_down_pressed: false,
             is_mouse_right_pressed: false,
+            is_mouse_left_pressed: false,
+            left_click: None,
+            cursor_position: None,
             scroll_val: 0.0,
             old_position: None,
             new_position: None,
@@ -107,7 +146,20 @@ impl CameraController {
                 self.is_mouse_right_pressed = *state == ElementState::Pressed;
                 true
             }
+            // 左键用于拾取物体，按下的位置留给 take_left_click 处理
+            WindowEvent::MouseInput {
+                state,
+                button: MouseButton::Left,
+                ..
+            } => {
+                self.is_mouse_left_pressed = *state == ElementState::Pressed;
+                if self.is_mouse_left_pressed {
+                    self.left_click = self.cursor_position;
+                }
+                true
+            }
             WindowEvent::CursorMoved { position, .. } => {
+                self.cursor_position = Some(*position);
                 if self.is_mouse_right_pressed {
                     self.new_position = Some(*position);
                     // println!("mouse position {:?}", position)
@@ -169,6 +221,19 @@ impl CameraController {
         }
     }
 
+    /// 取出上次处理后发生的左键点击位置
+    pub fn take_left_click(&mut self) -> Option<PhysicalPosition<f64>> {
+        self.left_click.take()
+    }
+
+    pub fn is_mouse_left_pressed(&self) -> bool {
+        self.is_mouse_left_pressed
+    }
+
+    pub fn cursor_position(&self) -> Option<PhysicalPosition<f64>> {
+        self.cursor_position
+    }
+
     pub fn update_camera(&mut self, camera: &mut Camera) {
         use cgmath::InnerSpace;
         let forward = camera.target - camera.eye;
@@ -260,3 +325,41 @@ impl CameraController {
         // println!("update camera");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{InnerSpace, Point3, Vector3, Vector4};
+
+    #[test]
+    fn test_screen_ray_projects_back_to_cursor() {
+        let camera = Camera {
+            eye: Point3::new(1.0, 4.0, 8.0),
+            target: Point3::new(0.0, 0.0, 0.0),
+            up: Vector3::unit_y(),
+            aspect: 16.0 / 9.0,
+            fovy: 45.0,
+            znear: 0.1,
+            zfar: 100.0,
+        };
+        let size = PhysicalSize::new(1600, 900);
+
+        // 画面中心的射线指向观察目标
+        let (origin, direction) = camera.screen_ray(PhysicalPosition::new(800.0, 450.0), size);
+        let forward = (camera.target - camera.eye).normalize();
+        assert!((direction - forward).magnitude() < 1e-5);
+
+        // 射线上的点投影回屏幕，应落在同一个像素上
+        let view_proj = camera.build_view_projection_matrix();
+        for (x, y) in [(0.0, 0.0), (1600.0, 900.0), (400.0, 700.0)] {
+            let (origin_x, direction_x) = camera.screen_ray(PhysicalPosition::new(x, y), size);
+            assert!((origin_x - origin).magnitude() < 0.1);
+            let point = origin_x + direction_x * 5.0;
+            let clip = view_proj * Vector4::new(point.x, point.y, point.z, 1.0);
+            let screen_x = (clip.x / clip.w + 1.0) / 2.0 * 1600.0;
+            let screen_y = (1.0 - clip.y / clip.w) / 2.0 * 900.0;
+            assert!((screen_x - x as f32).abs() < 0.5, "x = {}", screen_x);
+            assert!((screen_y - y as f32).abs() < 0.5, "y = {}", screen_y);
+        }
+    }
+}
@@ -38,19 +38,24 @@ pub struct Vertex {
 
 // pub const INDICES: &[u16] = &[0, 1, 4, 1, 2, 4, 2, 3, 4];
 
+// 调试线使用的顶点
 impl ColorVertex {
-    // const ATTRIBS: [wgpu::VertexAttribute; 2] =
-    //     wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
+    const ATTRIBS: [wgpu::VertexAttribute; 2] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3];
 
-    // pub fn desc() -> wgpu::VertexBufferLayout<'static> {
-    //     use std::mem;
+    pub fn new(position: [f32; 3], color: [f32; 3]) -> Self {
+        Self { position, color }
+    }
 
-    //     wgpu::VertexBufferLayout {
-    //         array_stride: mem::size_of::<Self>() as wgpu::BufferAddress,
-    //         step_mode: wgpu::VertexStepMode::Vertex,
-    //         attributes: &Self::ATTRIBS,
-    //     }
-    // }
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        use std::mem;
+
+        wgpu::VertexBufferLayout {
+            array_stride: mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBS,
+        }
+    }
 }
 
 impl Vertex {
@@ -71,15 +76,19 @@ impl Vertex {
 #[derive(Debug)]
 pub struct Instance {
     pub position: cgmath::Vector3<f32>,
-    pub last_position: cgmath::Vector3<f32>,
     pub rotation: cgmath::Quaternion<f32>,
+    pub tint: [f32; 4], // 与纹理颜色相乘的染色，用于高亮选中的物体
 }
+
+// 不染色
+pub const NO_TINT: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
 impl Instance {
     pub fn to_raw(&self) -> InstanceRaw {
         InstanceRaw {
             model: (cgmath::Matrix4::from_translation(self.position)
                 * cgmath::Matrix4::from(self.rotation))
             .into(),
+            tint: self.tint,
         }
     }
 }
@@ -88,6 +97,7 @@ impl Instance {
 #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct InstanceRaw {
     model: [[f32; 4]; 4],
+    tint: [f32; 4],
 }
 
 pub const NUM_INSTANCE_PER_ROW: u32 = 10;
@@ -131,6 +141,11 @@ impl InstanceRaw {
                     shader_location: 8,
                     format: wgpu::VertexFormat::Float32x4,
                 },
+                wgpu::VertexAttribute {
+                    offset: mem::size_of::<[f32; 16]>() as wgpu::BufferAddress,
+                    shader_location: 9,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
             ],
         }
     }
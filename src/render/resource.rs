@@ -1,5 +1,7 @@
 use super::{model, texture};
+use crate::physics::heightfield::HeightGrid;
 use cfg_if::cfg_if;
+use cgmath::InnerSpace;
 use image::Rgba;
 use std::io::{BufReader, Cursor};
 use wgpu::util::DeviceExt;
@@ -155,6 +157,53 @@ pub async fn load_model(
     Ok(model::Model { meshes, materials })
 }
 
+// 只读取 OBJ 中的顶点位置与三角形索引，不加载材质、不创建 GPU 资源，供物理碰撞形状使用
+// 文件中有多个网格时合并为一个，索引相应偏移
+pub async fn load_mesh_geometry(
+    file_name: &str,
+) -> anyhow::Result<(Vec<cgmath::Vector3<f32>>, Vec<u32>)> {
+    let obj_text = load_string(file_name).await?;
+    let obj_cursor = Cursor::new(obj_text);
+    let mut obj_reader = BufReader::new(obj_cursor);
+
+    let (models, _) = tobj::load_obj_buf_async(
+        &mut obj_reader,
+        &tobj::LoadOptions {
+            triangulate: true,
+            single_index: true,
+            ..Default::default()
+        },
+        |_| async { Ok(Default::default()) },
+    )
+    .await?;
+
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+    for m in models {
+        let offset = positions.len() as u32;
+        positions.extend(
+            m.mesh
+                .positions
+                .chunks_exact(3)
+                .map(|p| cgmath::Vector3::new(p[0], p[1], p[2])),
+        );
+        indices.extend(m.mesh.indices.iter().map(|i| i + offset));
+    }
+
+    Ok((positions, indices))
+}
+
+// 从灰度图读取高度场数据：每个像素为一个采样点，亮度映射到 [0, 1]
+// 返回 (rows, cols, heights)，rows 为图片高度，cols 为图片宽度，高度按行优先存放
+pub async fn load_heightmap(file_name: &str) -> anyhow::Result<(usize, usize, Vec<f32>)> {
+    let data = load_binary(file_name).await?;
+    let img = image::load_from_memory(&data)?.to_luma8();
+    let (cols, rows) = img.dimensions();
+    let heights = img.pixels().map(|p| p.0[0] as f32 / 255.0).collect();
+
+    Ok((rows as usize, cols as usize, heights))
+}
+
 // 在你的资源加载模块（例如 resource.rs）中添加以下代码
 
 // 生成球体模型的函数
@@ -283,6 +332,374 @@ fn generate_sphere(radius: f32, sectors: u32, stacks: u32) -> (Vec<model::ModelV
     (vertices, indices)
 }
 
+// 生成胶囊体模型的函数（轴沿 Y 方向，中段圆柱半高为 half_height）
+pub fn generate_capsule_model(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layout: &wgpu::BindGroupLayout,
+    radius: f32,
+    half_height: f32,
+    sectors: u32,
+    stacks: u32,
+    color: Option<image::Rgba<u8>>,
+) -> anyhow::Result<model::Model> {
+    let (vertices, indices) = generate_capsule(radius, half_height, sectors, stacks);
+
+    let default_color = if let Some(c) = color {
+        c
+    } else {
+        Rgba([255, 255, 255, 255]) // 默认白色
+    };
+
+    let default_texture = texture::Texture::create_color_texture(
+        device,
+        queue,
+        Some("default_capsule_texture"),
+        800,
+        600,
+        default_color,
+    )?;
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&default_texture.view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&default_texture.sampler),
+            },
+        ],
+        label: None,
+    });
+
+    let materials = vec![model::Material {
+        name: "Capsule_Material".to_string(),
+        diffuse_texture: default_texture,
+        bind_group,
+    }];
+
+    // 创建顶点缓冲区
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Capsule Vertex Buffer"),
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+
+    // 创建索引缓冲区
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Capsule Index Buffer"),
+        contents: bytemuck::cast_slice(&indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+
+    // 创建网格
+    let meshes = vec![model::Mesh {
+        name: "Capsule".to_string(),
+        vertex_buffer,
+        index_buffer,
+        num_elements: indices.len() as u32,
+        material: 0, // 使用第一个材质
+    }];
+
+    Ok(model::Model { meshes, materials })
+}
+
+// 胶囊体生成核心逻辑：上下两个半球，赤道处各复制一圈顶点，两圈之间即为中段圆柱面
+fn generate_capsule(
+    radius: f32,
+    half_height: f32,
+    sectors: u32,
+    stacks: u32,
+) -> (Vec<model::ModelVertex>, Vec<u32>) {
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    let pi = std::f32::consts::PI;
+    let half_stacks = (stacks / 2).max(1);
+
+    // 纬度环：上半球 0~π/2 向上平移，下半球 π/2~π 向下平移
+    let rings = (0..=half_stacks)
+        .map(|i| (i, half_height))
+        .chain((half_stacks..=2 * half_stacks).map(|i| (i, -half_height)))
+        .collect::<Vec<_>>();
+    let ring_count = rings.len() as u32;
+
+    for (ring, &(i, offset)) in rings.iter().enumerate() {
+        let phi = pi * i as f32 / (2 * half_stacks) as f32; // 纬度 0~π
+        let ny = phi.cos();
+        let nxz = phi.sin();
+
+        for j in 0..=sectors {
+            let theta = 2.0 * pi * j as f32 / sectors as f32; // 经度 0~2π
+            let nx = nxz * theta.cos();
+            let nz = nxz * theta.sin();
+
+            vertices.push(model::ModelVertex {
+                position: [radius * nx, radius * ny + offset, radius * nz],
+                tex_coords: [theta / (2.0 * pi), ring as f32 / (ring_count - 1) as f32],
+                normal: [nx, ny, nz],
+            });
+        }
+    }
+
+    // 生成索引
+    for i in 0..ring_count - 1 {
+        for j in 0..sectors {
+            let first = i * (sectors + 1) + j;
+            let second = first + sectors + 1;
+
+            indices.push(first);
+            indices.push(first + 1);
+            indices.push(second);
+
+            indices.push(second);
+            indices.push(first + 1);
+            indices.push(second + 1);
+        }
+    }
+
+    (vertices, indices)
+}
+
+// 生成平面模型的函数（位于 XZ 平面，法线朝 +Y）
+pub fn generate_plane_model(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layout: &wgpu::BindGroupLayout,
+    half_size: f32,
+    color: Option<image::Rgba<u8>>,
+) -> anyhow::Result<model::Model> {
+    let (vertices, indices) = generate_plane(half_size);
+
+    let default_color = if let Some(c) = color {
+        c
+    } else {
+        Rgba([255, 255, 255, 255]) // 默认白色
+    };
+
+    let default_texture = texture::Texture::create_color_texture(
+        device,
+        queue,
+        Some("default_plane_texture"),
+        800,
+        600,
+        default_color,
+    )?;
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&default_texture.view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&default_texture.sampler),
+            },
+        ],
+        label: None,
+    });
+
+    let materials = vec![model::Material {
+        name: "Plane_Material".to_string(),
+        diffuse_texture: default_texture,
+        bind_group,
+    }];
+
+    // 创建顶点缓冲区
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Plane Vertex Buffer"),
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+
+    // 创建索引缓冲区
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Plane Index Buffer"),
+        contents: bytemuck::cast_slice(&indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+
+    // 创建网格
+    let meshes = vec![model::Mesh {
+        name: "Plane".to_string(),
+        vertex_buffer,
+        index_buffer,
+        num_elements: indices.len() as u32,
+        material: 0, // 使用第一个材质
+    }];
+
+    Ok(model::Model { meshes, materials })
+}
+
+// 平面生成核心逻辑
+fn generate_plane(half_size: f32) -> (Vec<model::ModelVertex>, Vec<u32>) {
+    let corners = [
+        ([-half_size, 0.0, -half_size], [0.0, 0.0]),
+        ([-half_size, 0.0, half_size], [0.0, 1.0]),
+        ([half_size, 0.0, half_size], [1.0, 1.0]),
+        ([half_size, 0.0, -half_size], [1.0, 0.0]),
+    ];
+
+    let vertices = corners
+        .iter()
+        .map(|(position, tex_coords)| model::ModelVertex {
+            position: *position,
+            tex_coords: *tex_coords,
+            normal: [0.0, 1.0, 0.0],
+        })
+        .collect::<Vec<_>>();
+
+    // 逆时针顺序，从上方看为正面
+    let indices = vec![0, 1, 2, 0, 2, 3];
+
+    (vertices, indices)
+}
+
+// 生成高度场地形模型的函数，网格布局与三角划分和 `Shape::HeightField` 一致
+// 纹理为按格子交替明暗的棋盘格，便于观察地形起伏
+pub fn generate_heightfield_model(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layout: &wgpu::BindGroupLayout,
+    rows: usize,
+    cols: usize,
+    heights: &[f32],
+    scale: cgmath::Vector3<f32>,
+    color: Option<image::Rgba<u8>>,
+) -> anyhow::Result<model::Model> {
+    let grid = HeightGrid::new(rows, cols, heights, scale);
+    let (vertices, indices) = generate_heightfield(&grid);
+
+    let default_color = if let Some(c) = color {
+        c
+    } else {
+        Rgba([255, 255, 255, 255]) // 默认白色
+    };
+
+    let checker = generate_checker_image(cols - 1, rows - 1, default_color);
+    let default_texture = texture::Texture::from_image(
+        device,
+        queue,
+        &image::DynamicImage::ImageRgba8(checker),
+        Some("default_heightfield_texture"),
+    )?;
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&default_texture.view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&default_texture.sampler),
+            },
+        ],
+        label: None,
+    });
+
+    let materials = vec![model::Material {
+        name: "HeightField_Material".to_string(),
+        diffuse_texture: default_texture,
+        bind_group,
+    }];
+
+    // 创建顶点缓冲区
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("HeightField Vertex Buffer"),
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+
+    // 创建索引缓冲区
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("HeightField Index Buffer"),
+        contents: bytemuck::cast_slice(&indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+
+    // 创建网格
+    let meshes = vec![model::Mesh {
+        name: "HeightField".to_string(),
+        vertex_buffer,
+        index_buffer,
+        num_elements: indices.len() as u32,
+        material: 0, // 使用第一个材质
+    }];
+
+    Ok(model::Model { meshes, materials })
+}
+
+// 高度场网格生成核心逻辑：每个采样点一个顶点，法线由相邻采样点的中心差分求得
+fn generate_heightfield(grid: &HeightGrid) -> (Vec<model::ModelVertex>, Vec<u32>) {
+    let (rows, cols) = (grid.rows, grid.cols);
+    let mut vertices = Vec::with_capacity(rows * cols);
+    let mut indices = Vec::with_capacity(grid.triangle_count() * 3);
+
+    for r in 0..rows {
+        for c in 0..cols {
+            let position = grid.vertex(r, c);
+
+            // 边界上退化为单侧差分
+            let (c0, c1) = (c.saturating_sub(1), (c + 1).min(cols - 1));
+            let (r0, r1) = (r.saturating_sub(1), (r + 1).min(rows - 1));
+            let dx = (grid.height(r, c1) - grid.height(r, c0)) / ((c1 - c0) as f32 * grid.scale.x);
+            let dz = (grid.height(r1, c) - grid.height(r0, c)) / ((r1 - r0) as f32 * grid.scale.z);
+            let normal = cgmath::Vector3::new(-dx, 1.0, -dz).normalize();
+
+            vertices.push(model::ModelVertex {
+                position: position.into(),
+                tex_coords: [c as f32 / (cols - 1) as f32, r as f32 / (rows - 1) as f32],
+                normal: normal.into(),
+            });
+        }
+    }
+
+    // 与 HeightGrid 的三角划分一致，逆时针顺序，从上方看为正面
+    for r in 0..rows - 1 {
+        for c in 0..cols - 1 {
+            let p00 = (r * cols + c) as u32;
+            let p01 = p00 + 1;
+            let p10 = p00 + cols as u32;
+            let p11 = p10 + 1;
+
+            indices.extend_from_slice(&[p00, p10, p01]);
+            indices.extend_from_slice(&[p01, p10, p11]);
+        }
+    }
+
+    (vertices, indices)
+}
+
+// 棋盘格纹理：每个格子若干像素，相邻格子明暗交替
+fn generate_checker_image(cells_x: usize, cells_y: usize, color: Rgba<u8>) -> image::RgbaImage {
+    // 纹理边长不超过 2048 像素
+    let cell_size = (2048 / cells_x.max(cells_y)).clamp(1, 8) as u32;
+    let dark = Rgba([
+        (color.0[0] as f32 * 0.8) as u8,
+        (color.0[1] as f32 * 0.8) as u8,
+        (color.0[2] as f32 * 0.8) as u8,
+        color.0[3],
+    ]);
+
+    image::RgbaImage::from_fn(
+        cells_x as u32 * cell_size,
+        cells_y as u32 * cell_size,
+        |x, y| {
+            if (x / cell_size + y / cell_size) % 2 == 0 {
+                color
+            } else {
+                dark
+            }
+        },
+    )
+}
+
 // 生成圆柱体模型的函数
 pub fn generate_cylinder_model(
     device: &wgpu::Device,
@@ -626,4 +1043,52 @@ mod tests {
         assert!(!indices.is_empty());
         assert_eq!(indices.len() % 3, 0); // Should be triangles
     }
+
+    #[test]
+    fn test_generate_capsule() {
+        let (vertices, indices) = generate_capsule(0.5, 1.0, 8, 8);
+
+        assert_eq!(indices.len() % 3, 0);
+        assert!(indices.iter().all(|&i| (i as usize) < vertices.len()));
+
+        // 顶点应落在两端球心 (0, ±1, 0) 半径 0.5 的胶囊表面上
+        for vertex in &vertices {
+            let [x, y, z] = vertex.position;
+            let cy = y.clamp(-1.0, 1.0);
+            let dist = (x * x + (y - cy) * (y - cy) + z * z).sqrt();
+            assert!((dist - 0.5).abs() < 1e-4);
+        }
+        let max_y = vertices
+            .iter()
+            .map(|v| v.position[1])
+            .fold(f32::MIN, f32::max);
+        assert!((max_y - 1.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_generate_heightfield() {
+        use crate::physics::trimesh::TriangleSet;
+
+        let heights = (0..12).map(|i| (i % 4) as f32 * 0.5).collect::<Vec<_>>();
+        let grid = HeightGrid::new(3, 4, &heights, cgmath::Vector3::new(1.0, 2.0, 0.5));
+        let (vertices, indices) = generate_heightfield(&grid);
+
+        assert_eq!(vertices.len(), 12);
+        assert_eq!(indices.len(), grid.triangle_count() * 3);
+
+        // 渲染网格的三角形与碰撞用的三角形完全重合
+        for (i, tri) in indices.chunks_exact(3).enumerate() {
+            let t = grid.triangle(i);
+            for (&index, expected) in tri.iter().zip([t.a, t.b, t.c]) {
+                let position: cgmath::Vector3<f32> = vertices[index as usize].position.into();
+                assert!((position - expected).magnitude() < 1e-6);
+            }
+        }
+
+        // 沿 +X 上升的坡面，法线朝上并偏向 -X
+        for vertex in &vertices {
+            let [nx, ny, nz] = vertex.normal;
+            assert!(ny > 0.0 && nx < 0.0 && nz.abs() < 1e-6);
+        }
+    }
 }
@@ -8,6 +8,7 @@ var<uniform> camera: CameraUniform;
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) tex_coords: vec2<f32>,
+    @location(2) normal: vec3<f32>,
 };
 
 struct InstanceInput {
@@ -15,11 +16,14 @@ struct InstanceInput {
     @location(6) model_matrix_1: vec4<f32>,
     @location(7) model_matrix_2: vec4<f32>,
     @location(8) model_matrix_3: vec4<f32>,
+    @location(9) tint: vec4<f32>,
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) tex_coords: vec2<f32>,
+    @location(1) tint: vec4<f32>,
+    @location(2) world_normal: vec3<f32>,
 };
 
 @vertex
@@ -35,6 +39,14 @@ fn vs_main(
     );
     var out: VertexOutput;
     out.tex_coords = model.tex_coords;
+    out.tint = instance.tint;
+    // 法线只做旋转（及缩放）变换，片元着色器中再归一化
+    let normal_matrix = mat3x3<f32>(
+        instance.model_matrix_0.xyz,
+        instance.model_matrix_1.xyz,
+        instance.model_matrix_2.xyz,
+    );
+    out.world_normal = normal_matrix * model.normal;
     out.clip_position = camera.view_proj * model_matrix * vec4<f32>(model.position, 1.0);
     return out;
 }
@@ -45,7 +57,14 @@ var t_diffuse: texture_2d<f32>;
 @group(0) @binding(1)
 var s_diffuse: sampler;
 
+// 固定方向的平行光，加上环境光使背光面不至于全黑
+const LIGHT_DIRECTION: vec3<f32> = vec3<f32>(0.4, 1.0, 0.3);
+const AMBIENT: f32 = 0.35;
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    let color = textureSample(t_diffuse, s_diffuse, in.tex_coords) * in.tint;
+    let diffuse = max(dot(normalize(in.world_normal), normalize(LIGHT_DIRECTION)), 0.0);
+    let light = AMBIENT + (1.0 - AMBIENT) * diffuse;
+    return vec4<f32>(color.rgb * light, color.a);
 }
@@ -1,13 +1,26 @@
 // main cycle is here
 
+use std::collections::VecDeque;
 use std::sync::Arc;
 
+use crate::common::{Transform, PHYSICS_TIMESTEP};
 use crate::get_current_time;
+use crate::physics::diagnostics::DiagnosticsHistory;
+use crate::physics::events::PhysicsEvent;
+use crate::physics::filter::QueryFilter;
+use crate::physics::joint::JointKind;
+use crate::physics::kinematic::KinematicPath;
+use crate::physics::material::{MaterialId, MaterialLibrary};
+use crate::physics::mousejoint::MouseJoint;
+use crate::physics::phymgr::{BodyHandle, PhyMgr};
+use crate::physics::shape::{ConvexHull, Cylinder, PhysicsBody, Plane, Shape, Sphere};
+use crate::physics::trimesh::TriMesh;
 use crate::render::model::ModelVertex;
 use cgmath::{InnerSpace, Rotation3, Vector3, Zero};
 use web_time::Instant;
 use wgpu::util::DeviceExt;
 use wgpu::TextureView;
+use winit::dpi::PhysicalPosition;
 use winit::event::WindowEvent;
 use winit::window::Window;
 
@@ -18,19 +31,78 @@ use super::model::Vertex;
 
 const SPACE_BETWEEN: f32 = 3.0;
 
+// 演示场景参数
+const CYLINDER_COUNT: usize = 10;
+const CYLINDER_RADIUS: f32 = 0.5;
+const CYLINDER_HEIGHT: f32 = 1.0;
+const SPHERE_COUNT: usize = 5;
+const SPHERE_RADIUS: f32 = 0.5;
+const BOX_COUNT: usize = 2;
+const BOX_HALF_EXTENT: f32 = 1.0; // 与 res/cube.obj 的尺寸一致
+const CAPSULE_COUNT: usize = 3;
+const CAPSULE_RADIUS: f32 = 0.3;
+const CAPSULE_HALF_HEIGHT: f32 = 0.5;
+const GROUND_HEIGHT: f32 = -3.0;
+const GROUND_HALF_SIZE: f32 = 10.0;
+const TERRAIN_ROWS: usize = 21;
+const TERRAIN_COLS: usize = 21;
+const TERRAIN_CELL_SIZE: f32 = 0.5;
+const TERRAIN_HEIGHT_SCALE: f32 = 1.5;
+const CHAIN_LINK_COUNT: usize = 6;
+// 链条挂在世界上的悬挂点，链节从这里沿 x 轴水平伸出
+const CHAIN_ANCHOR: Vector3<f32> = Vector3::new(-9.0, 5.0, 5.0);
+// 弹簧和绳子的悬挂点
+const SPRING_ANCHOR: Vector3<f32> = Vector3::new(6.0, 6.0, 5.0);
+const ROPE_ANCHOR: Vector3<f32> = Vector3::new(8.0, 6.0, 5.0);
+const ROPE_LENGTH: f32 = 2.5;
+// 传感器方块包住最后一个球的出发位置，球落下时离开
+const TRIGGER_POSITION: Vector3<f32> = Vector3::new(8.25, 8.0, 0.0);
+// 运动学转盘：略高于地面，每 TURNTABLE_PERIOD 秒转一周
+const TURNTABLE_POSITION: Vector3<f32> = Vector3::new(0.0, GROUND_HEIGHT + 0.5, 8.0);
+const TURNTABLE_RADIUS: f32 = 2.0;
+const TURNTABLE_HEIGHT: f32 = 0.4;
+const TURNTABLE_PERIOD: f32 = 6.0;
+// 选中物体的染色，偏黄提亮
+const HIGHLIGHT_TINT: [f32; 4] = [1.5, 1.3, 0.6, 1.0];
+// 休眠物体偏暗偏蓝
+const SLEEP_TINT: [f32; 4] = [0.6, 0.6, 0.9, 1.0];
+// 传感器偏绿
+const SENSOR_TINT: [f32; 4] = [0.5, 1.3, 0.6, 1.0];
+// 面板曲线保留的物理步数，60 Hz 下约 10 秒
+const DIAGNOSTICS_HISTORY_LEN: usize = 600;
+// 事件面板保留的事件条数
+const EVENT_LOG_LEN: usize = 200;
+// 调试线缓冲区的初始顶点容量
+const DEBUG_LINE_INITIAL_CAPACITY: usize = 64;
+const SPRING_LINE_COLOR: [f32; 3] = [0.1, 0.9, 0.3];
+const DISTANCE_LINE_COLOR: [f32; 3] = [0.95, 0.8, 0.1];
+
 // 定义模型实例结构
 pub struct ModelInstance {
     pub model: super::model::Model,
     pub instances: Vec<Instance>,
     pub instance_buffer: wgpu::Buffer,
+    // 每个实例对应的物理体句柄，与 instances 一一对应
+    pub bodies: Vec<BodyHandle>,
 }
 
 impl ModelInstance {
     pub fn new(
         model: super::model::Model,
-        instances: Vec<Instance>,
+        bodies: Vec<BodyHandle>,
+        world: &PhyMgr,
         device: &wgpu::Device,
     ) -> Self {
+        // 实例与 bodies 按下标一一对应，拾取与高亮依赖这一点，因此句柄必须有效
+        let instances = bodies
+            .iter()
+            .map(|&handle| {
+                let body = world
+                    .get(handle)
+                    .unwrap_or_else(|| panic!("model instance refers to missing body {handle:?}"));
+                instance_from_body(body, false)
+            })
+            .collect::<Vec<_>>();
         let instance_data = instances.iter().map(Instance::to_raw).collect::<Vec<_>>();
         let instance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Instance Buffer"),
@@ -42,6 +114,16 @@ impl ModelInstance {
             model,
             instances,
             instance_buffer,
+            bodies,
+        }
+    }
+
+    // 从物理世界读取变换，更新实例；selected 为需要高亮的物体
+    pub fn sync_from_physics(&mut self, world: &PhyMgr, selected: Option<BodyHandle>) {
+        for (instance, &handle) in self.instances.iter_mut().zip(&self.bodies) {
+            if let Some(body) = world.get(handle) {
+                *instance = instance_from_body(body, selected == Some(handle));
+            }
         }
     }
 
@@ -75,6 +157,12 @@ pub struct State<'a> {
 
     edge_bind_group: wgpu::BindGroup,
 
+    // 调试线：每个物理步从关节重新生成的世界坐标线段
+    debug_line_pipeline: wgpu::RenderPipeline,
+    debug_line_buffer: wgpu::Buffer,
+    debug_line_capacity: usize, // 缓冲区可容纳的顶点数
+    debug_line_count: u32,      // 当前需要绘制的顶点数
+
     pub camera: Camera,
     camera_uniform: CameraUniform,
     camera_buffer: wgpu::Buffer,
@@ -86,6 +174,21 @@ pub struct State<'a> {
     // 存储所有模型实例的集合
     model_instances: Vec<ModelInstance>,
 
+    // 物理世界，渲染实例的变换每帧从这里读取
+    pub physics_world: PhyMgr,
+    // 每个物理步的能量与动量记录，用于面板中的曲线
+    pub diagnostics_history: DiagnosticsHistory,
+    // 最近的物理事件，超出 EVENT_LOG_LEN 时丢弃最早的
+    pub event_log: VecDeque<PhysicsEvent>,
+    // 是否记录每步都会产生的 TriggerStay 事件
+    pub log_stay_events: bool,
+    // 鼠标左键选中的物体，高亮显示
+    pub selected_body: Option<BodyHandle>,
+    // 拖拽点沿拾取射线到相机的距离，拖拽时目标点保持在这个距离上
+    drag_distance: f32,
+    // 演示场景中从模型文件构造的碰撞形状，重置场景时复用
+    demo_assets: DemoAssets,
+
     last_update_time: i64,
 
     pub phy_tick_trigger: bool,
@@ -104,6 +207,350 @@ pub struct State<'a> {
     pub current_fps: f64,
 }
 
+fn instance_from_body(body: &PhysicsBody, highlighted: bool) -> Instance {
+    Instance {
+        position: body.transform.translation,
+        rotation: body.transform.rotation,
+        tint: if highlighted {
+            HIGHLIGHT_TINT
+        } else if body.is_sensor {
+            SENSOR_TINT
+        } else if body.is_sleeping() {
+            SLEEP_TINT
+        } else {
+            NO_TINT
+        },
+    }
+}
+
+// 演示场景用到的、从模型文件构造的碰撞形状，重置场景时复用
+struct DemoAssets {
+    cube_hull: ConvexHull,
+    cube_mesh: Arc<TriMesh>,
+    terrain_rows: usize,
+    terrain_cols: usize,
+    terrain_heights: Arc<[f32]>,
+    materials: MaterialLibrary, // 从 materials.txt 加载
+}
+
+// 演示地形的高度数据：几座平缓的小山，高度范围 [0, 1]
+// 读不到 terrain.png 时使用这份程序生成的数据
+fn demo_terrain_heights() -> Arc<[f32]> {
+    (0..TERRAIN_ROWS)
+        .flat_map(|r| {
+            (0..TERRAIN_COLS).map(move |c| {
+                let u = r as f32 / (TERRAIN_ROWS - 1) as f32 * std::f32::consts::TAU;
+                let v = c as f32 / (TERRAIN_COLS - 1) as f32 * std::f32::consts::TAU;
+                (u.sin() * v.sin() + 1.0) / 2.0
+            })
+        })
+        .collect()
+}
+
+fn terrain_scale() -> Vector3<f32> {
+    Vector3::new(TERRAIN_CELL_SIZE, TERRAIN_HEIGHT_SCALE, TERRAIN_CELL_SIZE)
+}
+
+// 演示场景中的物理世界与各类物体的句柄
+struct DemoScene {
+    world: PhyMgr,
+    ground: BodyHandle,
+    cylinders: Vec<BodyHandle>,
+    spheres: Vec<BodyHandle>,
+    boxes: Vec<BodyHandle>,
+    capsules: Vec<BodyHandle>,
+    hull: BodyHandle,
+    platform: BodyHandle,
+    terrain: BodyHandle,
+    chain: Vec<BodyHandle>,
+    trigger: BodyHandle,
+    turntable: BodyHandle,
+}
+
+// 创建演示场景的物理世界与各类物体
+fn build_demo_world(assets: &DemoAssets) -> DemoScene {
+    // 新建世界的句柄分配是确定的，因此重复调用得到的句柄相同
+    let mut world = PhyMgr::new(Vector3::new(0.0, -9.8, 0.0));
+    // 平台、地形和转盘以及其上的球使用从 materials.txt 加载的材质
+    world.materials = assets.materials.clone();
+    let material =
+        |world: &PhyMgr, name: &str| world.materials.id(name).unwrap_or(MaterialId::DEFAULT);
+
+    // 静态地面
+    let ground = world.create_instance(
+        Transform::translation(0.0, GROUND_HEIGHT, 0.0),
+        Shape::Plane(Plane {
+            normal: Vector3::unit_y(),
+            distance: 0.0,
+        }),
+        0.0,
+        true,
+    );
+
+    // 一排从空中落下的圆柱体
+    let mut cylinders = (0..CYLINDER_COUNT)
+        .map(|i| {
+            world.create_instance(
+                Transform::translation(i as f32, 5.0, 0.0),
+                Shape::Cylinder(Cylinder {
+                    center: Vector3::zero(),
+                    axis: Vector3::unit_y(),
+                    radius: CYLINDER_RADIUS,
+                    height: CYLINDER_HEIGHT,
+                }),
+                1.0,
+                false,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let sphere_shape = Shape::Sphere(Sphere {
+        center: Vector3::zero(),
+        radius: SPHERE_RADIUS,
+    });
+    // 落在圆柱体上的一排球体
+    let mut spheres = (0..SPHERE_COUNT)
+        .map(|i| {
+            world.create_instance(
+                Transform::translation(i as f32 * 2.0 + 0.25, 8.0, 0.0),
+                sphere_shape.clone(),
+                1.0,
+                false,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // 旁边依次落下叠放的立方体
+    let boxes = (0..BOX_COUNT)
+        .map(|i| {
+            world.create_instance(
+                Transform::translation(-4.0, GROUND_HEIGHT + 2.0 + i as f32 * 3.0, 0.0),
+                Shape::Box {
+                    half_extents: Vector3::new(BOX_HALF_EXTENT, BOX_HALF_EXTENT, BOX_HALF_EXTENT),
+                },
+                1.0,
+                false,
+            )
+        })
+        .collect();
+
+    // 前方横躺落下的胶囊体
+    let capsules = (0..CAPSULE_COUNT)
+        .map(|i| {
+            world.create_instance(
+                Transform {
+                    translation: Vector3::new(i as f32 * 2.0, 2.0, 3.0),
+                    rotation: cgmath::Quaternion::from_angle_z(cgmath::Deg(90.0)),
+                    scale: Vector3::new(1.0, 1.0, 1.0),
+                },
+                Shape::Capsule {
+                    radius: CAPSULE_RADIUS,
+                    half_height: CAPSULE_HALF_HEIGHT,
+                },
+                1.0,
+                false,
+            )
+        })
+        .collect();
+
+    // 由立方体模型顶点构成的凸包以一定角度落下，与立方体共用同一个模型渲染
+    let hull = world.create_instance(
+        Transform {
+            translation: Vector3::new(-4.0, GROUND_HEIGHT + 4.0, -4.0),
+            rotation: cgmath::Quaternion::from_angle_x(cgmath::Deg(30.0)),
+            scale: Vector3::new(1.0, 1.0, 1.0),
+        },
+        Shape::ConvexHull(assets.cube_hull.clone()),
+        1.0,
+        false,
+    );
+
+    // 三角网格平台：与立方体模型同形，静止在地面上
+    let platform = world.create_instance(
+        Transform::translation(4.0, GROUND_HEIGHT + BOX_HALF_EXTENT, -4.0),
+        Shape::TriMesh(assets.cube_mesh.clone()),
+        0.0,
+        true,
+    );
+    world.get_mut(platform).unwrap().material = material(&world, "ice");
+    spheres.push(world.create_instance(
+        Transform::translation(4.6, 3.0, -3.6),
+        sphere_shape.clone(),
+        1.0,
+        false,
+    ));
+
+    // 高度场地形紧挨在地面后方，几个球从上面滚落
+    let terrain = world.create_instance(
+        Transform::translation(0.0, GROUND_HEIGHT, -GROUND_HALF_SIZE - 5.0),
+        Shape::height_field(
+            assets.terrain_rows,
+            assets.terrain_cols,
+            assets.terrain_heights.clone(),
+            terrain_scale(),
+        )
+        .expect("terrain heights must match the grid size"),
+        0.0,
+        true,
+    );
+    world.get_mut(terrain).unwrap().material = material(&world, "turf");
+    spheres.extend((0..3).map(|i| {
+        world.create_instance(
+            Transform::translation(i as f32 * 1.5 - 1.5, 2.0, -GROUND_HALF_SIZE - 4.0),
+            sphere_shape.clone(),
+            1.0,
+            false,
+        )
+    }));
+    // 平台上和地形上的球是橡胶球
+    let rubber = material(&world, "rubber");
+    for &ball in &spheres[spheres.len() - 4..] {
+        world.get_mut(ball).unwrap().material = rubber;
+    }
+
+    // 链节横放，相邻链节在端面中心用球窝关节相连，第一节挂在世界上
+    let mut chain: Vec<BodyHandle> = Vec::new();
+    for i in 0..CHAIN_LINK_COUNT {
+        let anchor = CHAIN_ANCHOR + Vector3::unit_x() * (i as f32 * CYLINDER_HEIGHT);
+        let link = world.create_instance(
+            Transform {
+                translation: anchor + Vector3::unit_x() * (CYLINDER_HEIGHT / 2.0),
+                rotation: cgmath::Quaternion::from_angle_z(cgmath::Deg(90.0)),
+                scale: Vector3::new(1.0, 1.0, 1.0),
+            },
+            Shape::Cylinder(Cylinder {
+                center: Vector3::zero(),
+                axis: Vector3::unit_y(),
+                radius: CYLINDER_RADIUS,
+                height: CYLINDER_HEIGHT,
+            }),
+            1.0,
+            false,
+        );
+        world.create_joint(
+            JointKind::BallSocket,
+            link,
+            chain.last().copied(),
+            anchor,
+            Vector3::unit_z(),
+        );
+        chain.push(link);
+    }
+
+    // 弹簧从静止长度处松开，球在重力作用下上下振动
+    let spring_ball = world.create_instance(
+        Transform::translation(SPRING_ANCHOR.x, SPRING_ANCHOR.y - 1.5, SPRING_ANCHOR.z),
+        sphere_shape.clone(),
+        1.0,
+        false,
+    );
+    world.create_joint_with_anchors(
+        JointKind::Spring {
+            rest_length: 1.5,
+            stiffness: 40.0,
+            damping: 0.5,
+        },
+        spring_ball,
+        None,
+        Vector3::new(SPRING_ANCHOR.x, SPRING_ANCHOR.y - 1.5, SPRING_ANCHOR.z),
+        SPRING_ANCHOR,
+    );
+    spheres.push(spring_ball);
+
+    // 绳子拉直后水平松开，球像单摆一样摆动
+    let rope_ball = world.create_instance(
+        Transform::translation(ROPE_ANCHOR.x + ROPE_LENGTH, ROPE_ANCHOR.y, ROPE_ANCHOR.z),
+        sphere_shape.clone(),
+        1.0,
+        false,
+    );
+    world.create_joint_with_anchors(
+        JointKind::Distance {
+            min_length: 0.0,
+            max_length: ROPE_LENGTH,
+        },
+        rope_ball,
+        None,
+        Vector3::new(ROPE_ANCHOR.x + ROPE_LENGTH, ROPE_ANCHOR.y, ROPE_ANCHOR.z),
+        ROPE_ANCHOR,
+    );
+    spheres.push(rope_ball);
+
+    // 包住球出发位置的静态传感器方块，只报告进出事件
+    let trigger = world.create_instance(
+        Transform::translation(TRIGGER_POSITION.x, TRIGGER_POSITION.y, TRIGGER_POSITION.z),
+        Shape::Box {
+            half_extents: Vector3::new(BOX_HALF_EXTENT, BOX_HALF_EXTENT, BOX_HALF_EXTENT),
+        },
+        0.0,
+        true,
+    );
+    world.get_mut(trigger).unwrap().is_sensor = true;
+
+    // 由关键帧路径驱动绕竖直轴旋转的运动学转盘
+    let turntable = world.create_kinematic(
+        Transform::translation(
+            TURNTABLE_POSITION.x,
+            TURNTABLE_POSITION.y,
+            TURNTABLE_POSITION.z,
+        ),
+        Shape::Cylinder(Cylinder {
+            center: Vector3::zero(),
+            axis: Vector3::unit_y(),
+            radius: TURNTABLE_RADIUS,
+            height: TURNTABLE_HEIGHT,
+        }),
+    );
+    world.set_kinematic_path(
+        turntable,
+        KinematicPath::rotation(TURNTABLE_POSITION, Vector3::unit_y(), TURNTABLE_PERIOD),
+    );
+    world.get_mut(turntable).unwrap().material = material(&world, "grip");
+    // 两个圆柱体立在转盘上转轴两侧，靠摩擦随转盘转动
+    cylinders.extend([-1.0, 1.0].map(|side| {
+        world.create_instance(
+            Transform::translation(
+                TURNTABLE_POSITION.x + side * TURNTABLE_RADIUS * 0.6,
+                TURNTABLE_POSITION.y + (TURNTABLE_HEIGHT + CYLINDER_HEIGHT) / 2.0,
+                TURNTABLE_POSITION.z,
+            ),
+            Shape::Cylinder(Cylinder {
+                center: Vector3::zero(),
+                axis: Vector3::unit_y(),
+                radius: CYLINDER_RADIUS,
+                height: CYLINDER_HEIGHT,
+            }),
+            1.0,
+            false,
+        )
+    }));
+
+    DemoScene {
+        world,
+        ground,
+        cylinders,
+        spheres,
+        boxes,
+        capsules,
+        hull,
+        platform,
+        terrain,
+        chain,
+        trigger,
+        turntable,
+    }
+}
+
+// 调试线顶点缓冲区，capacity 为顶点数
+fn create_debug_line_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Debug Line Buffer"),
+        size: (capacity * std::mem::size_of::<ColorVertex>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
 fn initial_surface_size(window: &Window) -> winit::dpi::PhysicalSize<u32> {
     #[cfg(target_arch = "wasm32")]
     {
@@ -481,6 +928,65 @@ impl State<'_> {
             },
         });
 
+        // 调试线管线：弹簧、距离约束等以世界坐标线段绘制，不做深度测试，总是可见
+        let debug_line_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Debug Line Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/debug_line_shader.wgsl").into()),
+        });
+
+        let debug_line_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Debug Line Pipeline Layout"),
+                bind_group_layouts: &[&camera_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let debug_line_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Debug Line Pipeline"),
+            layout: Some(&debug_line_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &debug_line_shader,
+                entry_point: Some("vs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                buffers: &[ColorVertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &debug_line_shader,
+                entry_point: Some("fs_main"),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::LineList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: texture::Texture::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        let debug_line_buffer = create_debug_line_buffer(&device, DEBUG_LINE_INITIAL_CAPACITY);
+
         let camera_controller = CameraController::new(0.2, 1.0, &size);
 
         // instances
@@ -502,7 +1008,7 @@ impl State<'_> {
                     Instance {
                         position,
                         rotation,
-                        last_position: position,
+                        tint: NO_TINT,
                     }
                 })
             })
@@ -534,34 +1040,50 @@ impl State<'_> {
         //         .await
         //         .unwrap();
 
-        // 创建圆柱体实例
-        let mut cylinder_instances: Vec<Instance> = vec![];
-        for i in 0..10 {
-            cylinder_instances.insert(
-                0,
-                Instance {
-                    position: Vector3 {
-                        x: i as f32,
-                        y: 5.0f32,
-                        z: 0.0f32,
-                    },
-                    last_position: Vector3 {
-                        x: i as f32,
-                        y: 5.0f32,
-                        z: 0.0f32,
-                    },
-                    rotation: cgmath::Quaternion::zero(),
-                },
-            );
-        }
+        // 用立方体模型的顶点与三角形构造凸包和三角网格形状
+        let (cube_points, cube_indices) = resource::load_mesh_geometry("cube.obj").await.unwrap();
+        let mut materials = MaterialLibrary::default();
+        materials
+            .load(&resource::load_string("materials.txt").await.unwrap())
+            .unwrap();
+        // 地形高度优先从灰度图读取，读取失败或尺寸不足 2x2 时退回程序生成的高度
+        let (terrain_rows, terrain_cols, terrain_heights) =
+            match resource::load_heightmap("terrain.png").await {
+                Ok((rows, cols, heights)) if rows >= 2 && cols >= 2 => (rows, cols, heights.into()),
+                _ => (TERRAIN_ROWS, TERRAIN_COLS, demo_terrain_heights()),
+            };
+        let demo_assets = DemoAssets {
+            cube_hull: ConvexHull::new(cube_points.clone()),
+            cube_mesh: Arc::new(TriMesh::new(cube_points, cube_indices)),
+            terrain_rows,
+            terrain_cols,
+            terrain_heights,
+            materials,
+        };
+
+        // 创建物理世界，圆柱体实例的变换由物理体驱动
+        let DemoScene {
+            world: physics_world,
+            ground,
+            cylinders,
+            spheres,
+            boxes,
+            capsules,
+            hull,
+            platform,
+            terrain,
+            chain,
+            trigger,
+            turntable,
+        } = build_demo_world(&demo_assets);
 
         // 创建圆柱体模型（用于填充渲染）
         let cylinder_model = resource::generate_cylinder_model(
             &device,
             &queue,
             &texture_bind_group_layout,
-            0.5,
-            1.0,
+            CYLINDER_RADIUS,
+            CYLINDER_HEIGHT,
             32,
             32,
             None, // Use default color
@@ -573,8 +1095,8 @@ impl State<'_> {
             &device,
             &queue,
             &texture_bind_group_layout,
-            0.5,
-            1.0,
+            CYLINDER_RADIUS,
+            CYLINDER_HEIGHT,
             32,
             32,
             None, // Use default color
@@ -602,10 +1124,98 @@ impl State<'_> {
         let mut model_instances = Vec::new();
 
         // 添加圆柱体模型实例（包含填充和边缘网格）
+        let cylinder_bodies = cylinders.into_iter().chain(chain).collect();
         let cylinder_model_instance =
-            ModelInstance::new(combined_model, cylinder_instances, &device);
+            ModelInstance::new(combined_model, cylinder_bodies, &physics_world, &device);
         model_instances.push(cylinder_model_instance);
 
+        // 添加转盘模型实例
+        let turntable_model = resource::generate_cylinder_model(
+            &device,
+            &queue,
+            &texture_bind_group_layout,
+            TURNTABLE_RADIUS,
+            TURNTABLE_HEIGHT,
+            48,
+            2,
+            Some(image::Rgba([90, 110, 160, 255])),
+        )
+        .unwrap();
+        let turntable_model_instance =
+            ModelInstance::new(turntable_model, vec![turntable], &physics_world, &device);
+        model_instances.push(turntable_model_instance);
+
+        // 添加球体模型实例
+        let sphere_model = resource::generate_sphere_model(
+            &device,
+            &queue,
+            &texture_bind_group_layout,
+            SPHERE_RADIUS,
+            32,
+            16,
+            Some(image::Rgba([200, 80, 60, 255])),
+        )
+        .await
+        .unwrap();
+        let sphere_model_instance =
+            ModelInstance::new(sphere_model, spheres, &physics_world, &device);
+        model_instances.push(sphere_model_instance);
+
+        // 添加立方体模型实例
+        let cube_model =
+            resource::load_model("cube.obj", &device, &queue, &texture_bind_group_layout)
+                .await
+                .unwrap();
+        let cube_bodies = boxes.into_iter().chain([hull, platform, trigger]).collect();
+        let cube_model_instance =
+            ModelInstance::new(cube_model, cube_bodies, &physics_world, &device);
+        model_instances.push(cube_model_instance);
+
+        // 添加胶囊体模型实例
+        let capsule_model = resource::generate_capsule_model(
+            &device,
+            &queue,
+            &texture_bind_group_layout,
+            CAPSULE_RADIUS,
+            CAPSULE_HALF_HEIGHT,
+            32,
+            16,
+            Some(image::Rgba([60, 140, 200, 255])),
+        )
+        .unwrap();
+        let capsule_model_instance =
+            ModelInstance::new(capsule_model, capsules, &physics_world, &device);
+        model_instances.push(capsule_model_instance);
+
+        // 添加地面模型实例
+        let ground_model = resource::generate_plane_model(
+            &device,
+            &queue,
+            &texture_bind_group_layout,
+            GROUND_HALF_SIZE,
+            Some(image::Rgba([128, 128, 128, 255])),
+        )
+        .unwrap();
+        let ground_model_instance =
+            ModelInstance::new(ground_model, vec![ground], &physics_world, &device);
+        model_instances.push(ground_model_instance);
+
+        // 添加高度场地形模型实例，与碰撞形状使用同一份高度数据
+        let terrain_model = resource::generate_heightfield_model(
+            &device,
+            &queue,
+            &texture_bind_group_layout,
+            demo_assets.terrain_rows,
+            demo_assets.terrain_cols,
+            &demo_assets.terrain_heights,
+            terrain_scale(),
+            Some(image::Rgba([110, 160, 90, 255])),
+        )
+        .unwrap();
+        let terrain_model_instance =
+            ModelInstance::new(terrain_model, vec![terrain], &physics_world, &device);
+        model_instances.push(terrain_model_instance);
+
         let last_update_time = get_current_time();
 
         Self {
@@ -619,6 +1229,10 @@ impl State<'_> {
             mesh_pipeline,
             edge_pipeline,
             edge_bind_group,
+            debug_line_pipeline,
+            debug_line_buffer,
+            debug_line_capacity: DEBUG_LINE_INITIAL_CAPACITY,
+            debug_line_count: 0,
             camera,
             camera_uniform,
             camera_buffer,
@@ -626,15 +1240,22 @@ impl State<'_> {
             camera_controller,
             depth_texture,
             model_instances,
+            physics_world,
+            diagnostics_history: DiagnosticsHistory::new(DIAGNOSTICS_HISTORY_LEN),
+            event_log: VecDeque::with_capacity(EVENT_LOG_LEN),
+            log_stay_events: false,
+            selected_body: None,
+            drag_distance: 0.0,
+            demo_assets,
             last_update_time,
             phy_tick_trigger: false,
             phy_single_step: false,
-            max_fps: 60.0,                   // 默认60 FPS
-            physics_time_step: 1.0 / 60.0,   // 固定物理时间步长为 1/60 秒
-            accumulated_time: 0.0,           // 初始累积时间为 0
-            frame_count: 0,                  // 初始帧计数为 0
-            last_fps_update: Instant::now(), // FPS 更新时间
-            current_fps: 0.0,                // 初始 FPS 为 0
+            max_fps: 60.0,                              // 默认60 FPS
+            physics_time_step: PHYSICS_TIMESTEP as f64, // 固定物理时间步长
+            accumulated_time: 0.0,                      // 初始累积时间为 0
+            frame_count: 0,                             // 初始帧计数为 0
+            last_fps_update: Instant::now(),            // FPS 更新时间
+            current_fps: 0.0,                           // 初始 FPS 为 0
         }
     }
 
@@ -658,37 +1279,60 @@ impl State<'_> {
         self.camera_controller.process_events(event)
     }
 
-    pub fn phy_update(&mut self, delta_time: i64) {
-        let delta_time_s = (delta_time as f32) / 1000.0;
-        let dt2_div2 = delta_time_s * delta_time_s / 2.0;
-        let acc = Vector3 {
-            x: 0.0,
-            y: -9.8,
-            z: 0.0,
-        };
-
-        // 更新所有模型实例的物理状态
-        for model_instance in &mut self.model_instances {
-            for instance in &mut model_instance.instances {
-                let llast_position = instance.last_position;
-                let last_position = instance.position;
-                instance.last_position = last_position;
-                instance.position = last_position + last_position - llast_position + acc * dt2_div2;
-                // println!(
-                //     "update sphere instance's position: {:?}, delta_time: {}",
-                //     instance.position, delta_time
-                // )
+    pub fn phy_update(&mut self) {
+        self.physics_world.update(self.physics_time_step as f32);
+        self.diagnostics_history
+            .push(self.physics_world.diagnostics());
+        for event in self.physics_world.drain_events() {
+            if !self.log_stay_events && matches!(event, PhysicsEvent::TriggerStay { .. }) {
+                continue;
             }
+            if self.event_log.len() == EVENT_LOG_LEN {
+                self.event_log.pop_front();
+            }
+            self.event_log.push_back(event);
         }
 
         self.phy_update_write_instance_buffer();
     }
 
     fn phy_update_write_instance_buffer(&mut self) {
-        // 更新所有模型实例的缓冲区
+        // 从物理世界同步变换，并更新所有模型实例的缓冲区
         for model_instance in &mut self.model_instances {
+            model_instance.sync_from_physics(&self.physics_world, self.selected_body);
             model_instance.update_instance_buffer(&self.queue);
         }
+        self.update_debug_lines();
+    }
+
+    // 把弹簧与距离约束两端锚点的连线写入调试线缓冲区，容量不足时重建缓冲区
+    fn update_debug_lines(&mut self) {
+        let vertices = self
+            .physics_world
+            .joints()
+            .filter_map(|(handle, joint)| {
+                let color = match joint.kind {
+                    JointKind::Spring { .. } => SPRING_LINE_COLOR,
+                    JointKind::Distance { .. } => DISTANCE_LINE_COLOR,
+                    _ => return None,
+                };
+                let (anchor1, anchor2) = self.physics_world.joint_anchors(handle)?;
+                Some([
+                    ColorVertex::new(anchor1.into(), color),
+                    ColorVertex::new(anchor2.into(), color),
+                ])
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+
+        if vertices.len() > self.debug_line_capacity {
+            self.debug_line_capacity = vertices.len().next_power_of_two();
+            self.debug_line_buffer =
+                create_debug_line_buffer(&self.device, self.debug_line_capacity);
+        }
+        self.queue
+            .write_buffer(&self.debug_line_buffer, 0, bytemuck::cast_slice(&vertices));
+        self.debug_line_count = vertices.len() as u32;
     }
 
     pub fn update(&mut self) {
@@ -706,6 +1350,7 @@ impl State<'_> {
 
         self.camera_controller.update_camera(&mut self.camera);
         self.camera_uniform.update_view_proj(&self.camera);
+        self.update_picking();
 
         // 物理更新逻辑：持续运行、单步执行或不执行
         // 使用固定时间步长，与渲染帧率解耦
@@ -719,7 +1364,7 @@ impl State<'_> {
             // 使用固定时间步长进行物理更新
             while self.accumulated_time >= self.physics_time_step {
                 // 执行固定时间步长的物理更新
-                self.phy_update((self.physics_time_step * 1000.0) as i64); // 转换回毫秒
+                self.phy_update();
 
                 // 减去一个时间步长
                 self.accumulated_time -= self.physics_time_step;
@@ -741,19 +1386,55 @@ impl State<'_> {
         self.last_update_time = now
     }
 
-    pub fn reset_physics(&mut self) {
-        // 重置所有模型实例
-        for model_instance in &mut self.model_instances {
-            for (i, instance) in model_instance.instances.iter_mut().enumerate() {
-                instance.position = Vector3 {
-                    x: i as f32,
-                    y: 5.0f32,
-                    z: 0.0f32,
-                };
-                instance.last_position = instance.position;
-                instance.rotation = cgmath::Quaternion::zero();
-            }
+    // 左键点击拾取物体，按住时用鼠标约束把物体拉向光标
+    fn update_picking(&mut self) {
+        if let Some(click) = self.camera_controller.take_left_click() {
+            self.pick(click);
         }
+        if !self.camera_controller.is_mouse_left_pressed() {
+            self.physics_world.mouse_joint = None;
+        }
+
+        let cursor = self.camera_controller.cursor_position();
+        if let (Some(joint), Some(cursor)) = (self.physics_world.mouse_joint.as_mut(), cursor) {
+            let (origin, direction) = self.camera.screen_ray(cursor, self.size);
+            joint.target = origin + direction * self.drag_distance;
+        }
+    }
+
+    fn pick(&mut self, position: PhysicalPosition<f64>) {
+        let (origin, direction) = self.camera.screen_ray(position, self.size);
+        // 物理暂停时粗检测可能还没有收录物体，先刷新一次
+        self.physics_world.update_broad_phase();
+        let hit = self.physics_world.raycast(
+            origin,
+            direction,
+            self.camera.zfar,
+            &QueryFilter::default(),
+        );
+
+        self.selected_body = hit.map(|hit| hit.body);
+        self.drag_distance = hit.map_or(0.0, |hit| hit.t);
+        // 只有动态物体可以拖拽
+        self.physics_world.mouse_joint = hit.and_then(|hit| {
+            let body = self.physics_world.get(hit.body)?;
+            (body.inv_mass > 0.0).then(|| MouseJoint::new(hit.body, body, hit.point))
+        });
+
+        // 刷新高亮
+        self.phy_update_write_instance_buffer();
+    }
+
+    pub fn reset_physics(&mut self) {
+        // 重建物理世界，物理体句柄与模型实例的对应关系保持不变
+        // 保留用户在面板中调整过的求解参数与材质（含材质对覆盖）
+        // 材质库只增不删，演示物体引用的材质 ID 在旧库中依然有效
+        let mut physics_world = build_demo_world(&self.demo_assets).world;
+        physics_world.settings = self.physics_world.settings;
+        physics_world.materials = std::mem::take(&mut self.physics_world.materials);
+        self.physics_world = physics_world;
+        self.diagnostics_history.clear();
+        self.event_log.clear();
 
         // 更新 GPU 实例缓冲区以反映重置的位置
         self.phy_update_write_instance_buffer();
@@ -835,6 +1516,14 @@ impl State<'_> {
                     );
                 }
             }
+
+            // 渲染调试线
+            if self.debug_line_count > 0 {
+                render_pass.set_pipeline(&self.debug_line_pipeline);
+                render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, self.debug_line_buffer.slice(..));
+                render_pass.draw(0..self.debug_line_count, 0..1);
+            }
         }
 
         self.queue.submit(std::iter::once(encoder.finish()));
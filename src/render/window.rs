@@ -2,6 +2,14 @@ use std::sync::Arc;
 
 use super::state::State;
 #[cfg(not(target_arch = "wasm32"))]
+use crate::physics::broadphase::BroadPhaseKind;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::physics::filter::CollisionGroups;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::physics::integrator::IntegratorKind;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::physics::material::CombineMode;
+#[cfg(not(target_arch = "wasm32"))]
 use cgmath;
 #[cfg(not(target_arch = "wasm32"))]
 use imgui::FontSource;
@@ -14,6 +22,8 @@ use web_time::{Duration, Instant};
 use wgpu::TextureView;
 #[cfg(not(target_arch = "wasm32"))]
 use winit::event::Event;
+#[cfg(not(target_arch = "wasm32"))]
+use winit::event::MouseButton;
 use winit::{
     event::WindowEvent,
     event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy},
@@ -233,6 +243,93 @@ impl<'a> App<'a> {
                         self.state.phy_single_step = true;
                     }
 
+                    // 求解器参数
+                    let settings = &mut self.state.physics_world.settings;
+                    ui.text("Velocity Iterations");
+                    ui.slider_config("##VelocityIterations", 1, 30)
+                        .build(&mut settings.velocity_iterations);
+                    ui.text("Position Iterations");
+                    ui.slider_config("##PositionIterations", 0, 10)
+                        .build(&mut settings.position_iterations);
+                    ui.checkbox("Warm Starting", &mut settings.warm_starting);
+                    ui.checkbox("Gyroscopic Effects", &mut settings.gyroscopic);
+                    ui.checkbox("Sleeping", &mut settings.sleeping);
+
+                    // 积分方法，可在同一场景中比较能量漂移
+                    ui.text("Integrator");
+                    let mut integrator = IntegratorKind::ALL
+                        .iter()
+                        .position(|&kind| kind == settings.integrator)
+                        .unwrap_or(0);
+                    if ui.combo(
+                        "##Integrator",
+                        &mut integrator,
+                        &IntegratorKind::ALL,
+                        |kind| kind.name().into(),
+                    ) {
+                        settings.integrator = IntegratorKind::ALL[integrator];
+                    }
+
+                    // 粗检测算法，切换后比较候选对数量
+                    ui.text("Broad Phase");
+                    let mut broad_phase = BroadPhaseKind::ALL
+                        .iter()
+                        .position(|&kind| kind == settings.broad_phase)
+                        .unwrap_or(0);
+                    if ui.combo(
+                        "##BroadPhase",
+                        &mut broad_phase,
+                        &BroadPhaseKind::ALL,
+                        |kind| kind.name().into(),
+                    ) {
+                        settings.broad_phase = BroadPhaseKind::ALL[broad_phase];
+                    }
+                    ui.text(format!(
+                        "Candidate Pairs: {}",
+                        self.state.physics_world.broad_phase().pairs().len()
+                    ));
+                    ui.text(format!(
+                        "Sleeping Bodies: {}",
+                        self.state
+                            .physics_world
+                            .iter()
+                            .filter(|(_, body)| body.is_sleeping())
+                            .count()
+                    ));
+
+                    // 能量与动量，观察每一步是否守恒
+                    ui.separator();
+                    ui.text("Diagnostics");
+                    let diagnostics = self.state.physics_world.diagnostics();
+                    ui.text(format!(
+                        "Kinetic: {:.3}  Rotational: {:.3}",
+                        diagnostics.kinetic_energy, diagnostics.rotational_energy
+                    ));
+                    ui.text(format!(
+                        "Potential: {:.3}  Total: {:.3}",
+                        diagnostics.potential_energy,
+                        diagnostics.total_energy()
+                    ));
+                    let p = diagnostics.linear_momentum;
+                    ui.text(format!("Momentum: ({:.3}, {:.3}, {:.3})", p.x, p.y, p.z));
+                    let l = diagnostics.angular_momentum;
+                    ui.text(format!(
+                        "Angular Momentum: ({:.3}, {:.3}, {:.3})",
+                        l.x, l.y, l.z
+                    ));
+                    let history = &self.state.diagnostics_history;
+                    ui.plot_lines("##TotalEnergy", &history.series(|d| d.total_energy()))
+                        .overlay_text("Total Energy")
+                        .graph_size([0.0, 60.0])
+                        .build();
+                    ui.plot_lines(
+                        "##KineticEnergy",
+                        &history.series(|d| d.kinetic_energy + d.rotational_energy),
+                    )
+                    .overlay_text("Kinetic + Rotational")
+                    .graph_size([0.0, 60.0])
+                    .build();
+
                     ui.separator();
                     // 显示当前实际 FPS
                     ui.text(format!("Current FPS: {:.1}", self.state.current_fps));
@@ -259,6 +356,147 @@ impl<'a> App<'a> {
                         self.should_exit = true;
                     }
                 });
+
+            // 物理事件日志，最新的在最下面
+            ui.window("Events")
+                .size([300.0, 250.0], imgui::Condition::FirstUseEver)
+                .position([0.0, 360.0], imgui::Condition::FirstUseEver)
+                .build(|| {
+                    ui.checkbox("Show Stay Events", &mut self.state.log_stay_events);
+                    ui.same_line();
+                    if ui.button("Clear") {
+                        self.state.event_log.clear();
+                    }
+                    ui.separator();
+                    ui.child_window("##EventLog").build(|| {
+                        for event in &self.state.event_log {
+                            ui.text(event.to_string());
+                        }
+                        // 停在底部时跟随新事件滚动
+                        if ui.scroll_y() >= ui.scroll_max_y() {
+                            ui.set_scroll_here_y_with_ratio(1.0);
+                        }
+                    });
+                });
+
+            // 选中物体的材质、碰撞分组与忽略列表
+            if let Some(selected) = self.state.selected_body {
+                ui.window("Selected Body")
+                    .size([300.0, 560.0], imgui::Condition::FirstUseEver)
+                    .position([410.0, 0.0], imgui::Condition::FirstUseEver)
+                    .build(|| {
+                        let world = &mut self.state.physics_world;
+                        let Some(mut material) = world.get(selected).map(|body| body.material)
+                        else {
+                            return;
+                        };
+                        ui.text(format!("Body #{}", selected.index()));
+
+                        // 切换选中物体的材质；修改材质参数会影响所有使用该材质的物体
+                        ui.separator();
+                        ui.text("Material");
+                        let materials = world
+                            .materials
+                            .iter()
+                            .map(|(id, name, _)| (id, name.to_string()))
+                            .collect::<Vec<_>>();
+                        let mut index = materials
+                            .iter()
+                            .position(|&(id, _)| id == material)
+                            .unwrap_or(0);
+                        let mut changed =
+                            ui.combo("##Material", &mut index, &materials, |(_, name)| {
+                                name.into()
+                            });
+                        material = materials[index].0;
+                        if let Some(params) = world.materials.get_mut(material) {
+                            changed |=
+                                ui.slider("Static Friction", 0.0, 1.0, &mut params.static_friction);
+                            changed |= ui.slider(
+                                "Dynamic Friction",
+                                0.0,
+                                1.0,
+                                &mut params.dynamic_friction,
+                            );
+                            changed |= ui.slider("Restitution", 0.0, 1.0, &mut params.restitution);
+                            changed |= ui.slider(
+                                "Rolling Friction",
+                                0.0,
+                                0.1,
+                                &mut params.rolling_friction,
+                            );
+                            for (label, mode) in [
+                                ("Friction Combine", &mut params.friction_combine),
+                                ("Restitution Combine", &mut params.restitution_combine),
+                            ] {
+                                let mut index = CombineMode::ALL
+                                    .iter()
+                                    .position(|other| other == mode)
+                                    .unwrap_or(0);
+                                if ui.combo(label, &mut index, &CombineMode::ALL, |mode| {
+                                    mode.name().into()
+                                }) {
+                                    *mode = CombineMode::ALL[index];
+                                    changed = true;
+                                }
+                            }
+                        }
+                        let Some(body) = world.get_mut(selected) else {
+                            return;
+                        };
+                        body.material = material;
+                        if changed {
+                            body.wake_up();
+                        }
+
+                        // 每一位一个复选框，8 个一行
+                        let groups = body.collision_groups;
+                        let CollisionGroups {
+                            memberships,
+                            filter,
+                        } = &mut body.collision_groups;
+                        for (label, bits) in [("Memberships", memberships), ("Filter", filter)] {
+                            ui.separator();
+                            ui.text(label);
+                            for group in 0..32 {
+                                if group % 8 != 0 {
+                                    ui.same_line();
+                                }
+                                ui.checkbox_flags(
+                                    format!("##{}{}", label, group),
+                                    bits,
+                                    1 << group,
+                                );
+                                if ui.is_item_hovered() {
+                                    ui.tooltip_text(format!("Group {}", group));
+                                }
+                            }
+                        }
+                        if body.collision_groups != groups {
+                            body.wake_up();
+                        }
+
+                        // 勾选的物体与选中物体之间不发生碰撞
+                        ui.separator();
+                        ui.text("Ignored Bodies");
+                        let others = world
+                            .iter()
+                            .filter(|&(handle, _)| handle != selected)
+                            .map(|(handle, other)| {
+                                let ignored = other.ignored_bodies.contains(&selected)
+                                    || world
+                                        .get(selected)
+                                        .is_some_and(|body| body.ignored_bodies.contains(&handle));
+                                (handle, ignored)
+                            })
+                            .collect::<Vec<_>>();
+                        for (handle, mut ignored) in others {
+                            if ui.checkbox(format!("Body #{}", handle.index()), &mut ignored) {
+                                world.set_pair_ignored(selected, handle, ignored);
+                            }
+                        }
+                    });
+            }
         }
 
         let mut encoder = self
@@ -331,7 +569,24 @@ impl winit::application::ApplicationHandler for App<'_> {
         //     event
         // );
 
-        self.state.input(&event);
+        // 点在 imgui 窗口上的左键不用于拾取物体
+        #[cfg(not(target_arch = "wasm32"))]
+        let ui_captures_click = matches!(
+            event,
+            WindowEvent::MouseInput {
+                button: MouseButton::Left,
+                ..
+            }
+        ) && self
+            .imgui
+            .as_ref()
+            .is_some_and(|imgui| imgui.context.io().want_capture_mouse);
+        #[cfg(target_arch = "wasm32")]
+        let ui_captures_click = false;
+
+        if !ui_captures_click {
+            self.state.input(&event);
+        }
 
         match event {
             WindowEvent::RedrawRequested => {